@@ -0,0 +1,183 @@
+#[cfg(test)]
+mod test;
+
+use crate::scene::EntityType;
+use crate::stats::ExportFormat;
+
+const DEFAULT_TICKS: u64 = 1000;
+
+pub const USAGE: &str =
+    "Usage: rusty-island [--headless] [--ticks <N>] [--stats <FILE>] [--log-modes]
+                    [--script <SPECIES>=<FILE>] [--load <FILE>] [--save <FILE>]
+                    [--seed <N>] [--record <FILE>] [--replay <FILE>]
+                    [--day-length <N>] [--season-length <N>]
+
+Options:
+  --headless    Run the simulation without a window.
+  --ticks <N>   Ticks to run in headless mode (default: 1000).
+  --stats <FILE> Export the statistics of each tick (.csv or .jsonl).
+  --log-modes   Print each animal mode change (with its reason).
+  --script <SPECIES>=<FILE>
+                Run a Rhai script on each behaviour tick of a species
+                (ex.: animal2=scripts/herbivore.rhai).
+  --load <FILE>  Continue a saved island.
+  --save <FILE>  Save the island at the end of the headless run
+                (or with F5 in the window, default: island.save).
+  --seed <N>    Seed of the new island (default: random).
+  --record <FILE>
+                Record the seed, the inputs and a checksum of each tick.
+  --replay <FILE>
+                Replay a recorded run (in headless mode it runs all the
+                recorded ticks) and stop when the simulation diverges.
+  --day-length <N>
+                Ticks from a sunrise to the next one (default: 400).
+  --season-length <N>
+                Ticks of each season of the year (default: 1600).
+  --help        Print this message.";
+
+/// Options read from the command line.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Options {
+    pub headless: bool,
+    pub ticks: u64,
+    /// File where the statistics of each tick are exported.
+    pub stats: Option<String>,
+    pub log_modes: bool,
+    /// Behaviour scripts that replace the species ones.
+    pub scripts: Vec<(EntityType, String)>,
+    /// Saved island used instead of a new one.
+    pub load: Option<String>,
+    /// File where the island is saved.
+    pub save: Option<String>,
+    /// Seed of the new island (random when it is not set).
+    pub seed: Option<u64>,
+    /// File where the run is recorded.
+    pub record: Option<String>,
+    /// Recorded run that is replayed.
+    pub replay: Option<String>,
+    /// Ticks of a day (the scene default when it is not set).
+    pub day_length: Option<u64>,
+    /// Ticks of a season (the scene default when it is not set).
+    pub season_length: Option<u64>,
+    pub help: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            headless: false,
+            ticks: DEFAULT_TICKS,
+            stats: None,
+            log_modes: false,
+            scripts: vec![],
+            load: None,
+            save: None,
+            seed: None,
+            record: None,
+            replay: None,
+            day_length: None,
+            season_length: None,
+            help: false,
+        }
+    }
+}
+
+/// Parse the command line arguments (without the program name).
+///
+/// # Arguments
+/// * `args` - The arguments.
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--headless" => options.headless = true,
+            "--ticks" => options.ticks = parse_value(&arg, args.next())?,
+            "--stats" => {
+                let path: String = parse_value(&arg, args.next())?;
+                ExportFormat::from_path(&path)?;
+                options.stats = Some(path);
+            }
+            "--log-modes" => options.log_modes = true,
+            "--script" => {
+                let value: String = parse_value(&arg, args.next())?;
+                options.scripts.push(parse_script(&value)?);
+            }
+            "--load" => options.load = Some(parse_value(&arg, args.next())?),
+            "--save" => options.save = Some(parse_value(&arg, args.next())?),
+            "--seed" => options.seed = Some(parse_value(&arg, args.next())?),
+            "--record" => options.record = Some(parse_value(&arg, args.next())?),
+            "--replay" => options.replay = Some(parse_value(&arg, args.next())?),
+            "--day-length" => {
+                let day_length: u64 = parse_value(&arg, args.next())?;
+                if day_length < 2 {
+                    return Err(format!("Invalid value for {arg}: {day_length}"));
+                }
+                options.day_length = Some(day_length);
+            }
+            "--season-length" => {
+                let season_length: u64 = parse_value(&arg, args.next())?;
+                if season_length == 0 {
+                    return Err(format!("Invalid value for {arg}: {season_length}"));
+                }
+                options.season_length = Some(season_length);
+            }
+            "--help" | "-h" => options.help = true,
+            _ => return Err(format!("Unknown argument: {arg}")),
+        }
+    }
+
+    // The replays start from a new island and have their own seed and inputs.
+    if options.replay.is_some()
+        && (options.load.is_some()
+            || options.record.is_some()
+            || options.seed.is_some()
+            || options.day_length.is_some()
+            || options.season_length.is_some()
+            || !options.scripts.is_empty())
+    {
+        return Err(
+            "--replay can not be used with --load, --record, --seed, --day-length, \
+             --season-length or --script"
+                .to_string(),
+        );
+    }
+    if options.load.is_some() && (options.record.is_some() || options.seed.is_some()) {
+        return Err("--load can not be used with --record or --seed".to_string());
+    }
+
+    Ok(options)
+}
+
+/// Parse the value of an option.
+///
+/// # Arguments
+/// * `name` - Name of the option (used in the error message).
+/// * `value` - The value (if any).
+fn parse_value<T: std::str::FromStr>(name: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or(format!("Missing value for {name}"))?;
+
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value for {name}: {value}"))
+}
+
+/// Parse a species script (`<SPECIES>=<FILE>`).
+///
+/// # Arguments
+/// * `value` - Value of the option.
+fn parse_script(value: &str) -> Result<(EntityType, String), String> {
+    let (name, path) = value
+        .split_once('=')
+        .ok_or(format!("Invalid value for --script: {value}"))?;
+
+    let entity_type =
+        EntityType::from_name(name).ok_or(format!("Unknown species for --script: {name}"))?;
+
+    if entity_type.is_plant() || path.is_empty() {
+        return Err(format!("Invalid value for --script: {value}"));
+    }
+
+    Ok((entity_type, path.to_string()))
+}
@@ -0,0 +1,106 @@
+use super::*;
+
+fn to_args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+mod parse_args {
+    use super::*;
+
+    #[test]
+    fn should_use_the_default_options_without_arguments() {
+        assert_eq!(parse_args(to_args(&[])), Ok(Options::default()));
+    }
+
+    #[test]
+    fn should_read_headless_and_ticks() {
+        let options = parse_args(to_args(&["--headless", "--ticks", "50", "--log-modes"])).unwrap();
+
+        assert!(options.headless);
+        assert_eq!(options.ticks, 50);
+        assert!(options.log_modes);
+    }
+
+    #[test]
+    fn should_read_the_stats_file() {
+        let options = parse_args(to_args(&["--stats", "stats.csv"])).unwrap();
+
+        assert_eq!(options.stats, Some("stats.csv".to_string()));
+        assert!(parse_args(to_args(&["--stats", "stats.txt"])).is_err());
+    }
+
+    #[test]
+    fn should_read_the_species_scripts() {
+        let options = parse_args(to_args(&[
+            "--script",
+            "animal2=scripts/herbivore.rhai",
+            "--script",
+            "Animal1=hunter.rhai",
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            options.scripts,
+            vec![
+                (EntityType::Animal2, "scripts/herbivore.rhai".to_string()),
+                (EntityType::Animal1, "hunter.rhai".to_string())
+            ]
+        );
+        assert!(parse_args(to_args(&["--script", "plant1=plant.rhai"])).is_err());
+        assert!(parse_args(to_args(&["--script", "animal3=a.rhai"])).is_err());
+        assert!(parse_args(to_args(&["--script", "animal1"])).is_err());
+    }
+
+    #[test]
+    fn should_read_the_save_files() {
+        let options = parse_args(to_args(&["--load", "old.save", "--save", "new.save"])).unwrap();
+
+        assert_eq!(options.load, Some("old.save".to_string()));
+        assert_eq!(options.save, Some("new.save".to_string()));
+        assert!(parse_args(to_args(&["--load"])).is_err());
+    }
+
+    #[test]
+    fn should_read_the_replay_options() {
+        let options = parse_args(to_args(&["--seed", "42", "--record", "run.replay"])).unwrap();
+
+        assert_eq!(options.seed, Some(42));
+        assert_eq!(options.record, Some("run.replay".to_string()));
+
+        let options = parse_args(to_args(&["--replay", "run.replay"])).unwrap();
+
+        assert_eq!(options.replay, Some("run.replay".to_string()));
+    }
+
+    #[test]
+    fn should_read_the_day_length() {
+        let options = parse_args(to_args(&["--day-length", "120"])).unwrap();
+
+        assert_eq!(options.day_length, Some(120));
+        assert!(parse_args(to_args(&["--day-length", "1"])).is_err());
+        assert!(parse_args(to_args(&["--replay", "a.replay", "--day-length", "50"])).is_err());
+    }
+
+    #[test]
+    fn should_read_the_season_length() {
+        let options = parse_args(to_args(&["--season-length", "800"])).unwrap();
+
+        assert_eq!(options.season_length, Some(800));
+        assert!(parse_args(to_args(&["--season-length", "0"])).is_err());
+        assert!(parse_args(to_args(&["--replay", "a.replay", "--season-length", "50"])).is_err());
+    }
+
+    #[test]
+    fn should_reject_the_replay_with_other_inputs() {
+        assert!(parse_args(to_args(&["--replay", "a.replay", "--seed", "1"])).is_err());
+        assert!(parse_args(to_args(&["--replay", "a.replay", "--load", "a.save"])).is_err());
+        assert!(parse_args(to_args(&["--load", "a.save", "--record", "a.replay"])).is_err());
+    }
+
+    #[test]
+    fn should_fail_with_invalid_values() {
+        assert!(parse_args(to_args(&["--ticks"])).is_err());
+        assert!(parse_args(to_args(&["--ticks", "many"])).is_err());
+        assert!(parse_args(to_args(&["--unknown"])).is_err());
+    }
+}
@@ -0,0 +1,123 @@
+use crate::scene::{EntityType, EventKind, ReplaySession, Scene, Subscription};
+use crate::stats::{StatsRecorder, TickStats};
+
+/// Ticks between each progress line.
+const REPORT_INTERVAL: u64 = 100;
+pub const ANIMAL_TYPES: [EntityType; 5] = [
+    EntityType::Animal1,
+    EntityType::Animal2,
+    EntityType::Fish,
+    EntityType::Dolphin,
+    EntityType::Bird,
+];
+
+/// Run the simulation without a window and print the summary statistics.
+///
+/// # Arguments
+/// * `scene` - Scene with the entities already created.
+/// * `ticks` - Amount of simulation ticks to run.
+/// * `recorder` - Where the statistics of each tick are recorded (if any).
+/// * `log_modes` - If should print the mode changes of the animals.
+/// * `session` - Recording or replay of the run (the run stops when a replay diverges).
+pub fn run<const W: usize, const H: usize>(
+    scene: &mut Scene<W, H>,
+    ticks: u64,
+    mut recorder: Option<&mut StatsRecorder>,
+    log_modes: bool,
+    session: &mut ReplaySession,
+) -> Result<(), String> {
+    let mut births = [0_usize; ANIMAL_TYPES.len()];
+    let mut deaths = [0_usize; ANIMAL_TYPES.len()];
+
+    let mode_changes = log_modes.then(|| subscribe_mode_changes(scene));
+
+    let first_tick = scene.get_tick();
+    let last_tick = first_tick + ticks;
+    print_populations(scene);
+
+    while scene.get_tick() < last_tick {
+        session.start_tick(scene)?;
+        let report = scene.update();
+        session.end_tick(scene)?;
+        let tick = scene.get_tick();
+
+        if let Some(subscription) = mode_changes {
+            print_events(scene, subscription);
+        }
+
+        for (i, entity_type) in ANIMAL_TYPES.iter().enumerate() {
+            births[i] += report.count_births(*entity_type);
+            deaths[i] += report.count_deaths(*entity_type);
+        }
+
+        if let Some(recorder) = recorder.as_deref_mut() {
+            recorder.record(TickStats::collect(tick, scene, &report));
+        }
+
+        // Without `is_multiple_of`, which needs a newer Rust.
+        #[allow(clippy::manual_is_multiple_of)]
+        let progress_tick = (tick - first_tick) % REPORT_INTERVAL == 0;
+        if progress_tick || tick == last_tick {
+            print_populations(scene);
+        }
+    }
+
+    if let Some(subscription) = mode_changes {
+        scene.unsubscribe(subscription);
+    }
+
+    println!("Summary after {ticks} ticks:");
+    for (i, entity_type) in ANIMAL_TYPES.iter().enumerate() {
+        println!(
+            "  {entity_type:?} - Population: {}, Births: {}, Deaths: {}",
+            scene.get_population(*entity_type),
+            births[i],
+            deaths[i]
+        );
+    }
+    println!("  Plants: {}", scene.get_plant_count());
+
+    Ok(())
+}
+
+/// Print the population of each animal type and the amount of plants.
+///
+/// # Arguments
+/// * `scene` - The scene.
+pub fn print_populations<const W: usize, const H: usize>(scene: &Scene<W, H>) {
+    let populations: Vec<String> = ANIMAL_TYPES
+        .iter()
+        .map(|entity_type| format!("{entity_type:?}: {}", scene.get_population(*entity_type)))
+        .collect();
+
+    println!(
+        "Tick {} - {}, Plants: {}",
+        scene.get_tick(),
+        populations.join(", "),
+        scene.get_plant_count()
+    );
+}
+
+/// Subscribe to the mode changes of the animals.
+///
+/// # Arguments
+/// * `scene` - The scene.
+pub fn subscribe_mode_changes<const W: usize, const H: usize>(
+    scene: &mut Scene<W, H>,
+) -> Subscription {
+    scene.subscribe(|event| matches!(event.kind, EventKind::ModeChanged { .. }))
+}
+
+/// Print (and drain) the events of a subscription.
+///
+/// # Arguments
+/// * `scene` - The scene.
+/// * `subscription` - The subscription.
+pub fn print_events<const W: usize, const H: usize>(
+    scene: &mut Scene<W, H>,
+    subscription: Subscription,
+) {
+    for event in scene.drain_events(subscription) {
+        println!("{event}");
+    }
+}
@@ -1,38 +1,198 @@
+mod cli;
+mod headless;
 mod render;
 mod scene;
+mod stats;
 mod traits;
 mod utils;
 
-const ANIMALS_MOVE_DELAY: u128 = 1500;
+/// Simulation ticks per second.
+const TICK_RATE: f32 = 2_f32;
+const MIN_TICK_RATE: f32 = 0.25_f32;
+const MAX_TICK_RATE: f32 = 64_f32;
+const DEFAULT_SAVE_PATH: &str = "island.save";
 const ISLAND_WIDTH: usize = 120;
 const ISLAND_HEIGHT: usize = 120;
+/// Animals created on a new island (the plants are scattered by their vegetation rules). There
+/// are enough of each species for the animals to find partners and last through the years.
+const INITIAL_ANIMALS: [(scene::EntityType, usize); 5] = [
+    (scene::EntityType::Animal1, 10),
+    (scene::EntityType::Animal2, 80),
+    (scene::EntityType::Fish, 60),
+    (scene::EntityType::Dolphin, 10),
+    (scene::EntityType::Bird, 30),
+];
 
 const CAMERA_SENSI: f32 = 0.15_f32;
 
 fn main() {
-    let (mut glium_render, event_loop) = render::glium::GliumRender::new("Ilha", [15_f32; 3]);
+    let options = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{err}\n\n{}", cli::USAGE);
+            std::process::exit(1);
+        }
+    };
+
+    if options.help {
+        println!("{}", cli::USAGE);
+        return;
+    }
+
+    let (mut scene, tick_rate, mut session) = match (&options.replay, &options.load) {
+        (Some(path), _) => {
+            let replay = scene::Replay::load_file(path).unwrap_or_else(|err| exit_with(&err));
+            let scene = scene::Scene::with_seed(replay.get_seed());
+            (scene, TICK_RATE, scene::ReplaySession::Replaying(replay))
+        }
+        (None, Some(path)) => {
+            let saved = scene::Scene::load_file(path).unwrap_or_else(|err| exit_with(&err));
+            (saved.scene, saved.tick_rate, scene::ReplaySession::Off)
+        }
+        (None, None) => {
+            let seed = options.seed.unwrap_or_else(rand::random);
+            let session = match options.record {
+                Some(_) => scene::ReplaySession::Recording(scene::Replay::new(seed)),
+                None => scene::ReplaySession::Off,
+            };
+            (scene::Scene::with_seed(seed), TICK_RATE, session)
+        }
+    };
+
+    let mut inputs = vec![];
+    if options.replay.is_none() && options.load.is_none() {
+        inputs.extend(
+            scene::EntityType::ALL
+                .into_iter()
+                .filter(|entity_type| entity_type.is_plant())
+                .map(scene::Input::Scatter),
+        );
+        inputs.extend(
+            INITIAL_ANIMALS
+                .iter()
+                .map(|(entity_type, quantity)| scene::Input::Spawn(*entity_type, *quantity)),
+        );
+        inputs.push(scene::Input::TickRate(tick_rate));
+    }
+    inputs.extend(options.day_length.map(scene::Input::DayLength));
+    inputs.extend(options.season_length.map(scene::Input::SeasonLength));
+    inputs.extend(
+        options
+            .scripts
+            .iter()
+            .map(|(entity_type, path)| scene::Input::Script(*entity_type, path.clone())),
+    );
+
+    for input in inputs {
+        if let Err(err) = session.input(&mut scene, input) {
+            exit_with(&err);
+        }
+    }
+
+    if options.headless {
+        let ticks = match &session {
+            scene::ReplaySession::Replaying(replay) => replay.get_ticks(),
+            _ => options.ticks,
+        };
+
+        let mut recorder = stats::StatsRecorder::new();
+        let result = headless::run(
+            &mut scene,
+            ticks,
+            options.stats.as_ref().map(|_| &mut recorder),
+            options.log_modes,
+            &mut session,
+        );
+        save_stats(&recorder, options.stats.as_deref());
+        save_replay(&session, options.record.as_deref());
+        if let Some(path) = options.save.as_deref() {
+            save_scene(&scene, path, tick_rate);
+        }
+
+        match result {
+            Ok(()) if options.replay.is_some() => {
+                println!("The replay matched all the {ticks} ticks")
+            }
+            Ok(()) => (),
+            Err(err) => exit_with(&err),
+        }
+        return;
+    }
+
+    run_window(scene, tick_rate, session, options);
+}
+
+fn exit_with(err: &str) -> ! {
+    eprintln!("{err}");
+    std::process::exit(1);
+}
+
+fn save_scene(scene: &scene::Scene<ISLAND_WIDTH, ISLAND_HEIGHT>, path: &str, tick_rate: f32) {
+    match scene.save_file(path, tick_rate) {
+        Ok(()) => println!("Island saved in {path}"),
+        Err(err) => eprintln!("Could not save the island in {path}: {err}"),
+    }
+}
 
-    let mut scene = scene::Scene::<ISLAND_WIDTH, ISLAND_HEIGHT>::new(&glium_render.display);
+fn save_stats(recorder: &stats::StatsRecorder, path: Option<&str>) {
+    if let Some(path) = path {
+        match recorder.save(path) {
+            Ok(()) => println!("Statistics saved in {path}"),
+            Err(err) => eprintln!("Could not save the statistics in {path}: {err}"),
+        }
+    }
+}
 
-    scene.create_entities(2, scene::EntityType::Animal1);
-    scene.create_entities(2, scene::EntityType::Animal2);
+fn save_replay(session: &scene::ReplaySession, path: Option<&str>) {
+    if let (scene::ReplaySession::Recording(replay), Some(path)) = (session, path) {
+        match replay.save_file(path) {
+            Ok(()) => println!("Replay saved in {path}"),
+            Err(err) => eprintln!("Could not save the replay in {path}: {err}"),
+        }
+    }
+}
 
-    scene.create_entities(10, scene::EntityType::Plant1);
-    scene.create_entities(10, scene::EntityType::Plant2);
+/// Apply an input from the keyboard (it is ignored while replaying).
+///
+/// # Arguments
+/// * `scene` - The scene.
+/// * `session` - Recording or replay of the run.
+/// * `input` - The input.
+fn send_input(
+    scene: &mut scene::Scene<ISLAND_WIDTH, ISLAND_HEIGHT>,
+    session: &mut scene::ReplaySession,
+    input: scene::Input,
+) -> bool {
+    match session.input(scene, input) {
+        Ok(applied) => applied,
+        Err(err) => {
+            eprintln!("{err}");
+            false
+        }
+    }
+}
 
-    glium_render.add_mesh(scene.get_height_map_mesh(&glium_render.display));
+fn run_window(
+    mut scene: scene::Scene<ISLAND_WIDTH, ISLAND_HEIGHT>,
+    tick_rate: f32,
+    mut session: scene::ReplaySession,
+    options: cli::Options,
+) {
+    let save_path = options.save.unwrap_or(DEFAULT_SAVE_PATH.to_string());
+    let stats_path = options.stats;
+    let record_path = options.record;
+    let mode_changes = options
+        .log_modes
+        .then(|| headless::subscribe_mode_changes(&mut scene));
 
-    let colors_sea = ([1_f32; 3], [0_f32, 0_f32, 0.6_f32], [0_f32, 0_f32, 0.2_f32]);
-    glium_render.add_mesh(render::glium::util::height_map_to_mesh(
-        utils::height_map::init_height_map::<ISLAND_WIDTH, ISLAND_HEIGHT>(-1_f32),
-        colors_sea,
-        &glium_render.display,
-    ));
+    let (mut glium_render, event_loop) = render::glium::GliumRender::new("Ilha", [15_f32; 3]);
 
     let mut last_x = 0_f64;
     let mut last_y = 0_f64;
     let mut first_move = true;
-    let mut time = std::time::SystemTime::now();
+    let mut clock = utils::clock::SimulationClock::new(tick_rate);
+    let mut population = vec![];
+    let mut recorder = stats::StatsRecorder::new();
 
     event_loop.run(move |event, _, context| {
         if *context == glium::glutin::event_loop::ControlFlow::Exit {
@@ -42,8 +202,10 @@ fn main() {
         match event {
             glium::glutin::event::Event::WindowEvent { event, .. } => match event {
                 glium::glutin::event::WindowEvent::CloseRequested => {
+                    println!("{} ticks run in this session", clock.get_ticks());
+                    save_stats(&recorder, stats_path.as_deref());
+                    save_replay(&session, record_path.as_deref());
                     *context = glium::glutin::event_loop::ControlFlow::Exit;
-                    return;
                 }
                 glium::glutin::event::WindowEvent::KeyboardInput { input, .. } => {
                     if input.state == glium::glutin::event::ElementState::Pressed {
@@ -95,6 +257,43 @@ fn main() {
                                 glium_render.zoom_in();
                                 return;
                             }
+                            glium::glutin::event::VirtualKeyCode::LBracket => {
+                                let tick_rate = (clock.get_tick_rate() / 2_f32).max(MIN_TICK_RATE);
+                                let input = scene::Input::TickRate(tick_rate);
+                                if send_input(&mut scene, &mut session, input) {
+                                    clock.set_tick_rate(tick_rate);
+                                }
+                                return;
+                            }
+                            glium::glutin::event::VirtualKeyCode::F5 => {
+                                save_scene(&scene, &save_path, clock.get_tick_rate());
+                                return;
+                            }
+                            glium::glutin::event::VirtualKeyCode::RBracket => {
+                                let tick_rate = (clock.get_tick_rate() * 2_f32).min(MAX_TICK_RATE);
+                                let input = scene::Input::TickRate(tick_rate);
+                                if send_input(&mut scene, &mut session, input) {
+                                    clock.set_tick_rate(tick_rate);
+                                }
+                                return;
+                            }
+                            glium::glutin::event::VirtualKeyCode::F => {
+                                send_input(&mut scene, &mut session, scene::Input::Lightning);
+                                return;
+                            }
+                            glium::glutin::event::VirtualKeyCode::Key1
+                            | glium::glutin::event::VirtualKeyCode::Key2
+                            | glium::glutin::event::VirtualKeyCode::Key3
+                            | glium::glutin::event::VirtualKeyCode::Key4
+                            | glium::glutin::event::VirtualKeyCode::Key5
+                            | glium::glutin::event::VirtualKeyCode::Key6
+                            | glium::glutin::event::VirtualKeyCode::Key7 => {
+                                let entity_type = scene::EntityType::ALL[key as usize
+                                    - glium::glutin::event::VirtualKeyCode::Key1 as usize];
+                                let input = scene::Input::Spawn(entity_type, 1);
+                                send_input(&mut scene, &mut session, input);
+                                return;
+                            }
                             _ => return,
                         };
 
@@ -118,16 +317,60 @@ fn main() {
                 }
                 _ => (),
             },
-            _ => (),
-        }
+            glium::glutin::event::Event::MainEventsCleared => {
+                for _ in 0..clock.update() {
+                    match session.start_tick(&mut scene) {
+                        Ok(Some(tick_rate)) => clock.set_tick_rate(tick_rate),
+                        Ok(None) => (),
+                        Err(err) => eprintln!("{err}"),
+                    }
 
-        if let Ok(delay) = std::time::SystemTime::now().duration_since(time) {
-            if delay.as_millis() >= ANIMALS_MOVE_DELAY {
-                scene.move_animals();
-                time = std::time::SystemTime::now();
+                    let report = scene.update();
+
+                    let replaying = session.is_replaying();
+                    match session.end_tick(&scene) {
+                        Ok(()) if replaying && !session.is_replaying() => {
+                            println!("The replay matched all the {} ticks", scene.get_tick())
+                        }
+                        Ok(()) => (),
+                        Err(err) => eprintln!("{err}"),
+                    }
+
+                    if stats_path.is_some() {
+                        recorder.record(stats::TickStats::collect(
+                            scene.get_tick(),
+                            &scene,
+                            &report,
+                        ));
+                    }
+
+                    if !report.is_empty() {
+                        for entity_type in headless::ANIMAL_TYPES {
+                            println!(
+                                "{entity_type:?} - Births: {}, Deaths: {}",
+                                report.count_births(entity_type),
+                                report.count_deaths(entity_type)
+                            );
+                        }
+                    }
+
+                    let current_population: Vec<usize> = scene::EntityType::ALL
+                        .iter()
+                        .map(|entity_type| scene.get_population(*entity_type))
+                        .collect();
+                    if current_population != population {
+                        population = current_population;
+                        headless::print_populations(&scene);
+                    }
+                }
+
+                if let Some(subscription) = mode_changes {
+                    headless::print_events(&mut scene, subscription);
+                }
+
+                glium_render.draw_scene(&scene, clock.get_alpha());
             }
+            _ => (),
         }
-
-        glium_render.draw_scene(&mut scene);
     })
 }
@@ -0,0 +1,186 @@
+use std::time::Instant;
+
+use glium::Surface;
+
+use super::vertex::Vertex;
+
+/// Height of the flames (they flicker around it).
+const FLAME_HEIGHT: f32 = 0.9_f32;
+/// Half of the width of a flame.
+const FLAME_WIDTH: f32 = 0.35_f32;
+/// Flickers of the flames in a second.
+const FLICKER_SPEED: f32 = 12_f32;
+/// Half of the size of an ash patch.
+const ASH_SIZE: f32 = 0.5_f32;
+/// Height of the ash above the ground (so it is drawn over the terrain).
+const ASH_OFFSET: f32 = 0.05_f32;
+const FLAME_COLOR: [f32; 4] = [1_f32, 0.45_f32, 0.05_f32, 0.85_f32];
+const ASH_COLOR: [f32; 4] = [0.12_f32, 0.11_f32, 0.1_f32, 0.85_f32];
+
+/// Flames over the burning cells and ash patches on the burnt ones.
+pub struct Fire {
+    /// Display used to create the buffers of each frame (the amount of fires changes).
+    display: glium::Display,
+    program: glium::Program,
+    start: Instant,
+}
+
+impl Fire {
+    /// Create the shader program of the fire.
+    ///
+    /// # Arguments
+    /// * `display` - Display struct to create buffers and shader programs.
+    pub fn new(display: &glium::Display) -> Self {
+        let program = glium::Program::from_source(
+            display,
+            Self::create_vertex_shader(),
+            Self::create_fragment_shader(),
+            None,
+        )
+        .unwrap();
+
+        Self {
+            display: display.clone(),
+            program,
+            start: Instant::now(),
+        }
+    }
+
+    /// Draw the flames (flickering with the time) and the ash.
+    ///
+    /// # Arguments
+    /// * `frame` - Frame where we draw.
+    /// * `view` - View matrix of the camera.
+    /// * `perspective` - Perspective matrix.
+    /// * `burning` - Positions of the burning cells.
+    /// * `ashes` - Positions of the cells with ash.
+    /// * `light` - Fraction of the ash color that is seen (the flames are always bright).
+    pub fn draw(
+        &self,
+        frame: &mut glium::Frame,
+        view: [[f32; 4]; 4],
+        perspective: [[f32; 4]; 4],
+        burning: &[[f32; 3]],
+        ashes: &[[f32; 3]],
+        light: f32,
+    ) -> Result<(), glium::DrawError> {
+        let time = self.start.elapsed().as_secs_f32() * FLICKER_SPEED;
+        let normal = [0_f32, 1_f32, 0_f32];
+        let vertex = |position: [f32; 3]| Vertex { position, normal };
+
+        // Two crossed triangles per flame.
+        let flames: Vec<Vertex> = burning
+            .iter()
+            .flat_map(|[x, y, z]| {
+                let height =
+                    FLAME_HEIGHT * (0.75_f32 + 0.25_f32 * (time + x * 1.7 + z * 2.3).sin());
+                [
+                    vertex([x - FLAME_WIDTH, *y, *z]),
+                    vertex([x + FLAME_WIDTH, *y, *z]),
+                    vertex([*x, y + height, *z]),
+                    vertex([*x, *y, z - FLAME_WIDTH]),
+                    vertex([*x, *y, z + FLAME_WIDTH]),
+                    vertex([*x, y + height, *z]),
+                ]
+            })
+            .collect();
+
+        let ash: Vec<Vertex> = ashes
+            .iter()
+            .flat_map(|[x, y, z]| {
+                let y = y + ASH_OFFSET;
+                [
+                    vertex([x - ASH_SIZE, y, z - ASH_SIZE]),
+                    vertex([x + ASH_SIZE, y, z - ASH_SIZE]),
+                    vertex([x + ASH_SIZE, y, z + ASH_SIZE]),
+                    vertex([x - ASH_SIZE, y, z - ASH_SIZE]),
+                    vertex([x + ASH_SIZE, y, z + ASH_SIZE]),
+                    vertex([x - ASH_SIZE, y, z + ASH_SIZE]),
+                ]
+            })
+            .collect();
+
+        let ash_color = [
+            ASH_COLOR[0] * light,
+            ASH_COLOR[1] * light,
+            ASH_COLOR[2] * light,
+            ASH_COLOR[3],
+        ];
+
+        self.draw_triangles(frame, view, perspective, &ash, ash_color)?;
+        self.draw_triangles(frame, view, perspective, &flames, FLAME_COLOR)
+    }
+
+    /// Draw a list of triangles with a color.
+    ///
+    /// # Arguments
+    /// * `frame` - Frame where we draw.
+    /// * `view` - View matrix of the camera.
+    /// * `perspective` - Perspective matrix.
+    /// * `vertices` - Vertices of the triangles.
+    /// * `color` - Color (with the alpha) of the triangles.
+    fn draw_triangles(
+        &self,
+        frame: &mut glium::Frame,
+        view: [[f32; 4]; 4],
+        perspective: [[f32; 4]; 4],
+        vertices: &[Vertex],
+        color: [f32; 4],
+    ) -> Result<(), glium::DrawError> {
+        if vertices.is_empty() {
+            return Ok(());
+        }
+
+        let vertices = glium::VertexBuffer::new(&self.display, vertices).unwrap();
+        let params = glium::DrawParameters {
+            depth: glium::Depth {
+                test: glium::draw_parameters::DepthTest::IfLess,
+                write: false,
+                ..Default::default()
+            },
+            blend: glium::Blend::alpha_blending(),
+            ..Default::default()
+        };
+
+        frame.draw(
+            &vertices,
+            glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList),
+            &self.program,
+            &glium::uniform! {
+                view: view,
+                perspective: perspective,
+                u_color: color,
+            },
+            &params,
+        )
+    }
+
+    fn create_vertex_shader() -> &'static str {
+        r#"
+            #version 140
+
+            in vec3 position;
+
+            uniform mat4 perspective;
+            uniform mat4 view;
+
+            void main() {
+                gl_Position = perspective * view * vec4(position, 1.0);
+            }
+        "#
+    }
+
+    fn create_fragment_shader() -> &'static str {
+        r#"
+            #version 140
+
+            out vec4 color;
+
+            uniform vec4 u_color;
+
+            void main() {
+                color = u_color;
+            }
+        "#
+    }
+}
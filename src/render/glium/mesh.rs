@@ -121,7 +121,29 @@ impl Mesh {
         self.matrix[3][2] = new_position[2];
     }
 
+    /// Replace the rotation (and the scale) of the mesh by a rotation around the y axis.
+    ///
+    /// # Arguments
+    /// * `angle` - Angle in degrees.
     pub fn set_rotation_y(&mut self, angle: f32) {
+        for i in 0..3 {
+            for j in 0..3 {
+                self.matrix[i][j] = if i == j { 1_f32 } else { 0_f32 };
+            }
+        }
+
         rotate_y_matrix(&mut self.matrix, angle);
     }
+
+    /// Scale the mesh, composed with its current rotation and scale.
+    ///
+    /// # Arguments
+    /// * `scale` - Scale for all the axis.
+    pub fn set_scale(&mut self, scale: f32) {
+        for row in self.matrix.iter_mut().take(3) {
+            for value in row.iter_mut().take(3) {
+                *value *= scale;
+            }
+        }
+    }
 }
@@ -0,0 +1,93 @@
+use super::mesh::Mesh;
+use crate::render::obj_reader;
+use crate::scene::{EntityType, SeasonCycle};
+
+// HACK: obj_reader does not read the diffuse and specular colors
+// so i'm hard coding here.
+const PLANT1_DIFFUSE: [f32; 3] = [0.237683, 0.640000, 0.010366];
+const PLANT2_DIFFUSE: [f32; 3] = [0.009721, 0.479320, 0.003347];
+
+/// Meshes used to draw each entity type.
+pub struct MeshMap {
+    pub plant1: Mesh,
+    pub plant2: Mesh,
+    pub animal1: Mesh,
+    pub animal2: Mesh,
+    pub fish: Mesh,
+    pub dolphin: Mesh,
+    pub bird: Mesh,
+}
+
+impl MeshMap {
+    /// Load the entity meshes from the assets folder.
+    ///
+    /// # Arguments
+    /// * `display` - Display struct to create buffers and shader programs.
+    ///
+    pub fn load(display: &glium::Display) -> Self {
+        let plant1_obj = obj_reader::ObjReader::new("assets/plant1.obj").unwrap();
+        let plant2_obj = obj_reader::ObjReader::new("assets/plant2.obj").unwrap();
+        let animal1_obj = obj_reader::ObjReader::new("assets/animal1.obj").unwrap();
+        let animal2_obj = obj_reader::ObjReader::new("assets/animal2.obj").unwrap();
+        let fish_obj = obj_reader::ObjReader::new("assets/fish_placeholder.obj").unwrap();
+        let dolphin_obj = obj_reader::ObjReader::new("assets/dolphin_placeholder.obj").unwrap();
+
+        let mut plant1 = Mesh::from_obj(plant1_obj.get_obj(), display);
+        const PLANT1_SPECULAR: [f32; 3] = [1.0, 1.0, 1.0];
+        plant1.set_diffuse(PLANT1_DIFFUSE);
+        plant1.set_specular(PLANT1_SPECULAR);
+
+        let mut plant2 = Mesh::from_obj(plant2_obj.get_obj(), display);
+        const PLANT2_SPECULAR: [f32; 3] = [1.0, 1.0, 1.0];
+        plant2.set_diffuse(PLANT2_DIFFUSE);
+        plant2.set_specular(PLANT2_SPECULAR);
+
+        // There are no real aquatic models yet, so the fish and the dolphins use placeholder
+        // meshes (and the birds reuse a land one) with another color.
+        let mut fish = Mesh::from_obj(fish_obj.get_obj(), display);
+        const FISH_DIFFUSE: [f32; 3] = [0.95, 0.55, 0.1];
+        fish.set_diffuse(FISH_DIFFUSE);
+
+        let mut dolphin = Mesh::from_obj(dolphin_obj.get_obj(), display);
+        const DOLPHIN_DIFFUSE: [f32; 3] = [0.35, 0.45, 0.6];
+        dolphin.set_diffuse(DOLPHIN_DIFFUSE);
+
+        let mut bird = Mesh::from_obj(animal2_obj.get_obj(), display);
+        const BIRD_DIFFUSE: [f32; 3] = [0.9, 0.9, 0.85];
+        bird.set_diffuse(BIRD_DIFFUSE);
+
+        Self {
+            plant1,
+            plant2,
+            animal1: Mesh::from_obj(animal1_obj.get_obj(), display),
+            animal2: Mesh::from_obj(animal2_obj.get_obj(), display),
+            fish,
+            dolphin,
+            bird,
+        }
+    }
+
+    /// Tint the leaves of the plants with the colors of the season.
+    ///
+    /// # Arguments
+    /// * `season_cycle` - Seasons of the scene.
+    /// * `tick` - Tick of the scene.
+    pub fn tint_plants(&mut self, season_cycle: SeasonCycle, tick: u64) {
+        self.plant1
+            .set_diffuse(season_cycle.tint_leaves(tick, PLANT1_DIFFUSE));
+        self.plant2
+            .set_diffuse(season_cycle.tint_leaves(tick, PLANT2_DIFFUSE));
+    }
+
+    pub fn get_mesh(&mut self, entity_type: EntityType) -> &mut Mesh {
+        match entity_type {
+            EntityType::Plant1 => &mut self.plant1,
+            EntityType::Plant2 => &mut self.plant2,
+            EntityType::Animal1 => &mut self.animal1,
+            EntityType::Animal2 => &mut self.animal2,
+            EntityType::Fish => &mut self.fish,
+            EntityType::Dolphin => &mut self.dolphin,
+            EntityType::Bird => &mut self.bird,
+        }
+    }
+}
@@ -1,18 +1,37 @@
 pub mod camera;
+pub mod fire;
 pub mod mesh;
+pub mod mesh_map;
 pub mod normal;
+pub mod rain;
+pub mod sky;
 pub mod util;
 pub mod vertex;
 
 use glium::Surface;
 
-use self::{camera::Camera, mesh::Mesh, vertex::Vertex};
+use crate::scene::FireCell;
+
+use self::{
+    camera::Camera, fire::Fire, mesh::Mesh, mesh_map::MeshMap, rain::Rain, sky::Sky, vertex::Vertex,
+};
+
+/// Color of the burning plants.
+const BURNING_DIFFUSE: [f32; 3] = [0.9, 0.25, 0.02];
 
 pub struct GliumRender {
     pub display: glium::Display,
     camera: Camera,
-    meshes: Vec<Mesh>,
-    light: [f32; 3],
+    mesh_map: MeshMap,
+    terrain: Option<Mesh>,
+    /// Translucent surface drawn over everything under the water.
+    water: Option<Mesh>,
+    /// Version of the scene height map used to build the terrain and the water.
+    terrain_version: Option<u64>,
+    /// Rain drops (created on the first rain).
+    rain: Option<Rain>,
+    /// Flames and ash (created on the first fire).
+    fire: Option<Fire>,
 }
 
 impl GliumRender {
@@ -34,26 +53,46 @@ impl GliumRender {
         let cb = glium::glutin::ContextBuilder::new();
 
         let display = glium::Display::new(wb, cb, &event_loop).unwrap();
+        let mesh_map = MeshMap::load(&display);
 
         (
             Self {
                 display,
                 camera: Camera::new(camera_pos, [0.0, -1.0, 0.0], [0.0, 0.0, 1.0]),
-                meshes: vec![],
-                light: [10_f32, 15_f32, -10_f32],
+                mesh_map,
+                terrain: None,
+                water: None,
+                terrain_version: None,
+                rain: None,
+                fire: None,
             },
             event_loop,
         )
     }
 
-    /// Function to draw the scene (using the meshes of the struct).
+    /// Function to draw the scene (using the meshes of the struct), lit by the sun or the
+    /// moon of the scene time, covered by clouds while it rains and with the fires.
+    ///
+    /// # Arguments
+    /// * `scene` - Scene with the entities.
+    /// * `alpha` - Fraction (between 0 and 1) of the next simulation tick that already passed.
+    ///
     pub fn draw_scene<const W: usize, const H: usize>(
         &mut self,
-        scene: &mut crate::scene::Scene<W, H>,
+        scene: &crate::scene::Scene<W, H>,
+        alpha: f32,
     ) {
+        self.update_terrain(scene);
+
         let mut frame = self.display.draw();
 
-        frame.clear_color_and_depth((1_f32, 1_f32, 1_f32, 1_f32), 1.0);
+        let raining = scene.get_weather().is_raining();
+        let clouds = if raining { 1_f32 } else { 0_f32 };
+        let day_length = scene.get_day_cycle().get_day_length() as f32;
+        let sky =
+            Sky::at((scene.get_time_of_day() + alpha / day_length).fract()).with_clouds(clouds);
+
+        frame.clear_color_and_depth(sky.clear_color, 1.0);
 
         let perspective = self.get_perspective_matrix(&frame);
         let params = glium::DrawParameters {
@@ -65,17 +104,19 @@ impl GliumRender {
             ..Default::default()
         };
 
-        let default_uniforms = (self.camera.get_view_matrix(), perspective, self.light);
+        let default_uniforms = (self.camera.get_view_matrix(), perspective, sky);
 
-        scene.draw_entities(&mut frame, default_uniforms, &params);
+        self.draw_entities(&mut frame, scene, default_uniforms, &params, alpha);
 
-        for mesh in self.meshes.iter() {
+        if let Some(mesh) = &self.terrain {
             mesh.draw(
                 &mut frame,
                 &glium::uniform! {
                     view: self.camera.get_view_matrix(),
                     perspective: perspective,
-                    u_light: self.light,
+                    u_light: sky.light,
+                    u_light_color: sky.light_color,
+                    u_ambient: sky.ambient,
                     matrix: mesh.matrix,
                     ambient_color: mesh.ambient,
                     diffuse_color: mesh.diffuse,
@@ -85,11 +126,173 @@ impl GliumRender {
             );
         }
 
+        if let Some(water) = &self.water {
+            let water_params = glium::DrawParameters {
+                depth: glium::Depth {
+                    test: glium::draw_parameters::DepthTest::IfLess,
+                    write: false,
+                    ..Default::default()
+                },
+                blend: glium::Blend::alpha_blending(),
+                ..Default::default()
+            };
+
+            water.draw(
+                &mut frame,
+                &glium::uniform! {
+                    view: self.camera.get_view_matrix(),
+                    perspective: perspective,
+                    u_light: sky.light,
+                    u_light_color: sky.light_color,
+                    u_ambient: sky.ambient,
+                    matrix: water.matrix,
+                    ambient_color: water.ambient,
+                    diffuse_color: water.diffuse,
+                    specular_color: water.specular,
+                },
+                &water_params,
+            );
+        }
+
+        let burning = scene.get_burning_cells();
+        let ashes = scene.get_fire_cells(|cell| matches!(cell, FireCell::Ash { .. }));
+        if !burning.is_empty() || !ashes.is_empty() {
+            let fire = self.fire.get_or_insert_with(|| Fire::new(&self.display));
+            let result = fire.draw(
+                &mut frame,
+                self.camera.get_view_matrix(),
+                perspective,
+                &burning,
+                &ashes,
+                sky.ambient,
+            );
+
+            if let Err(err) = result {
+                eprintln!("Could not draw the fire: {err}");
+            }
+        }
+
+        if raining {
+            let rain = self
+                .rain
+                .get_or_insert_with(|| Rain::new(W, H, &self.display));
+            let result = rain.draw(
+                &mut frame,
+                self.camera.get_view_matrix(),
+                perspective,
+                sky.ambient,
+            );
+
+            if let Err(err) = result {
+                eprintln!("Could not draw the rain: {err}");
+            }
+        }
+
         frame.finish().unwrap();
     }
 }
 
 impl GliumRender {
+    /// Draw the plants (with the leaves of the season) and the animals (interpolating the
+    /// animals between ticks).
+    ///
+    /// # Arguments
+    /// * `frame` - Frame where we draw.
+    /// * `scene` - Scene with the entities.
+    /// * `uniforms` - View, perspective and sky uniforms.
+    /// * `params` - Params with the scene configuration.
+    /// * `alpha` - Fraction (between 0 and 1) of the next tick that already passed.
+    ///
+    fn draw_entities<const W: usize, const H: usize>(
+        &mut self,
+        frame: &mut glium::Frame,
+        scene: &crate::scene::Scene<W, H>,
+        uniforms: ([[f32; 4]; 4], [[f32; 4]; 4], Sky),
+        params: &glium::DrawParameters,
+        alpha: f32,
+    ) {
+        self.mesh_map
+            .tint_plants(scene.get_season_cycle(), scene.get_tick());
+
+        for entity in scene.iter_entities() {
+            let mesh = self.mesh_map.get_mesh(entity.get_type());
+
+            mesh.set_position(entity.get_interpolated_position(alpha));
+            mesh.set_rotation_y(entity.get_interpolated_rotation(alpha));
+            mesh.set_scale(entity.get_scale());
+
+            // The burning plants glow with the fire, and each animal has the tint of its genome.
+            let diffuse = mesh.diffuse;
+            if entity.get_type().is_plant() {
+                if scene.is_burning(entity.position) {
+                    mesh.set_diffuse(BURNING_DIFFUSE);
+                }
+            } else {
+                let tint = entity.get_genome().tint;
+                mesh.set_diffuse([0, 1, 2].map(|i| (diffuse[i] * tint[i]).min(1_f32)));
+            }
+
+            mesh.draw(
+                frame,
+                &glium::uniform! {
+                    view: uniforms.0,
+                    perspective: uniforms.1,
+                    u_light: uniforms.2.light,
+                    u_light_color: uniforms.2.light_color,
+                    u_ambient: uniforms.2.ambient,
+                    matrix: mesh.matrix,
+                    ambient_color: mesh.ambient,
+                    diffuse_color: mesh.diffuse,
+                    specular_color: mesh.specular,
+                },
+                params,
+            );
+
+            mesh.set_diffuse(diffuse);
+        }
+    }
+
+    /// Build the terrain and the water meshes again when the height map of the scene changed
+    /// (the lakes fill and dry with the weather).
+    ///
+    /// # Arguments
+    /// * `scene` - Scene with the height map.
+    ///
+    fn update_terrain<const W: usize, const H: usize>(
+        &mut self,
+        scene: &crate::scene::Scene<W, H>,
+    ) {
+        let version = scene.get_terrain_version();
+        if self.terrain_version == Some(version) {
+            return;
+        }
+        self.terrain_version = Some(version);
+
+        let height_map = *scene.get_height_map();
+        let terrain_colors = (
+            [0.25_f32, 0.25_f32, 0.25_f32],
+            [1_f32, 0_f32, 0_f32],
+            [0_f32, 0_f32, 0_f32],
+        );
+        let water_colors = (
+            [1_f32; 3],
+            [0_f32, 0.2_f32, 0.6_f32],
+            [0_f32, 0_f32, 0.2_f32],
+        );
+
+        self.terrain = Some(util::height_map_to_mesh(
+            height_map,
+            terrain_colors,
+            &self.display,
+        ));
+        self.water = Some(util::height_map_to_shaded_mesh(
+            util::get_water_levels(&height_map, crate::scene::WATER_LEVEL),
+            water_colors,
+            Self::create_water_fragment_shader(),
+            &self.display,
+        ));
+    }
+
     /// Default function to create a simple vertex shader program.
     pub fn create_default_vertex_shader() -> &'static str {
         r#"
@@ -123,6 +326,39 @@ impl GliumRender {
             out vec4 color;
 
             uniform vec3 u_light;
+            uniform vec3 u_light_color;
+            uniform float u_ambient;
+
+            uniform vec3 ambient_color;
+            uniform vec3 diffuse_color;
+            uniform vec3 specular_color;
+
+            void main() {
+                float diffuse = max(dot(normalize(v_normal), normalize(u_light)), 0.0);
+
+                vec3 camera_dir = normalize(-v_position);
+                vec3 half_direction = normalize(normalize(u_light) + camera_dir);
+                float specular = pow(max(dot(half_direction, normalize(v_normal)), 0.0), 16.0);
+
+                vec3 lit = u_light_color * (diffuse * diffuse_color + specular * specular_color);
+                color = vec4(u_ambient * ambient_color + lit, 1.0);
+            }
+        "#
+    }
+
+    /// Fragment shader of the translucent water.
+    pub fn create_water_fragment_shader() -> &'static str {
+        r#"
+            #version 140
+
+            in vec3 v_normal;
+            in vec3 v_position;
+
+            out vec4 color;
+
+            uniform vec3 u_light;
+            uniform vec3 u_light_color;
+            uniform float u_ambient;
 
             uniform vec3 ambient_color;
             uniform vec3 diffuse_color;
@@ -135,7 +371,8 @@ impl GliumRender {
                 vec3 half_direction = normalize(normalize(u_light) + camera_dir);
                 float specular = pow(max(dot(half_direction, normalize(v_normal)), 0.0), 16.0);
 
-                color = vec4(ambient_color + diffuse * diffuse_color + specular * specular_color, 1.0);
+                vec3 lit = u_light_color * (diffuse * diffuse_color + specular * specular_color);
+                color = vec4(u_ambient * ambient_color + lit, 0.6);
             }
         "#
     }
@@ -176,10 +413,6 @@ impl GliumRender {
         self.camera.rotate(angles);
     }
 
-    pub fn add_mesh(&mut self, mesh: Mesh) {
-        self.meshes.push(mesh);
-    }
-
     pub fn zoom_in(&mut self) {
         self.camera.zoom_in();
     }
@@ -0,0 +1,158 @@
+use std::time::Instant;
+
+use glium::Surface;
+use rand::Rng;
+
+use super::vertex::Vertex;
+
+/// Amount of rain drops drawn over the island.
+const DROP_COUNT: usize = 4000;
+/// Height (above the sea) where the drops start to fall.
+const RAIN_HEIGHT: f32 = 12_f32;
+/// Length of the streak of a drop.
+const DROP_LENGTH: f32 = 0.6_f32;
+/// Distance fallen by a drop in a second.
+const FALL_SPEED: f32 = 9_f32;
+/// Horizontal shift of the streaks (the drops fall with the wind).
+const WIND: [f32; 2] = [0.15_f32, 0.05_f32];
+const DROP_COLOR: [f32; 4] = [0.7_f32, 0.75_f32, 0.85_f32, 0.5_f32];
+
+/// Streaks of rain falling over the island (drawn while it rains).
+pub struct Rain {
+    /// Start position of each drop (the drops fall in a loop).
+    drops: Vec<[f32; 3]>,
+    vertices: glium::VertexBuffer<Vertex>,
+    program: glium::Program,
+    start: Instant,
+}
+
+impl Rain {
+    /// Scatter the rain drops over an island.
+    ///
+    /// # Arguments
+    /// * `width` - Width of the island.
+    /// * `height` - Height (on the z axis) of the island.
+    /// * `display` - Display struct to create buffers and shader programs.
+    pub fn new(width: usize, height: usize, display: &glium::Display) -> Self {
+        let mut rand = rand::thread_rng();
+        let drops = (0..DROP_COUNT)
+            .map(|_| {
+                [
+                    rand.gen_range(0_f32..width as f32),
+                    rand.gen_range(0_f32..RAIN_HEIGHT),
+                    rand.gen_range(0_f32..height as f32),
+                ]
+            })
+            .collect();
+
+        let vertices = glium::VertexBuffer::empty_dynamic(display, DROP_COUNT * 2).unwrap();
+        let program = glium::Program::from_source(
+            display,
+            Self::create_vertex_shader(),
+            Self::create_fragment_shader(),
+            None,
+        )
+        .unwrap();
+
+        Self {
+            drops,
+            vertices,
+            program,
+            start: Instant::now(),
+        }
+    }
+
+    /// Move the drops down (based on the time since the rain was created) and draw them.
+    ///
+    /// # Arguments
+    /// * `frame` - Frame where we draw.
+    /// * `view` - View matrix of the camera.
+    /// * `perspective` - Perspective matrix.
+    /// * `light` - Fraction of the drop color that is seen (darker at night).
+    pub fn draw(
+        &mut self,
+        frame: &mut glium::Frame,
+        view: [[f32; 4]; 4],
+        perspective: [[f32; 4]; 4],
+        light: f32,
+    ) -> Result<(), glium::DrawError> {
+        let fallen = self.start.elapsed().as_secs_f32() * FALL_SPEED;
+        let normal = [0_f32, 1_f32, 0_f32];
+
+        let vertices: Vec<Vertex> = self
+            .drops
+            .iter()
+            .flat_map(|[x, y, z]| {
+                let y = (y - fallen).rem_euclid(RAIN_HEIGHT);
+                [
+                    Vertex {
+                        position: [*x, y + DROP_LENGTH, *z],
+                        normal,
+                    },
+                    Vertex {
+                        position: [x + WIND[0], y, z + WIND[1]],
+                        normal,
+                    },
+                ]
+            })
+            .collect();
+        self.vertices.write(&vertices);
+
+        let params = glium::DrawParameters {
+            depth: glium::Depth {
+                test: glium::draw_parameters::DepthTest::IfLess,
+                write: false,
+                ..Default::default()
+            },
+            blend: glium::Blend::alpha_blending(),
+            ..Default::default()
+        };
+        let color = [
+            DROP_COLOR[0] * light,
+            DROP_COLOR[1] * light,
+            DROP_COLOR[2] * light,
+            DROP_COLOR[3],
+        ];
+
+        frame.draw(
+            &self.vertices,
+            glium::index::NoIndices(glium::index::PrimitiveType::LinesList),
+            &self.program,
+            &glium::uniform! {
+                view: view,
+                perspective: perspective,
+                u_color: color,
+            },
+            &params,
+        )
+    }
+
+    fn create_vertex_shader() -> &'static str {
+        r#"
+            #version 140
+
+            in vec3 position;
+
+            uniform mat4 perspective;
+            uniform mat4 view;
+
+            void main() {
+                gl_Position = perspective * view * vec4(position, 1.0);
+            }
+        "#
+    }
+
+    fn create_fragment_shader() -> &'static str {
+        r#"
+            #version 140
+
+            out vec4 color;
+
+            uniform vec4 u_color;
+
+            void main() {
+                color = u_color;
+            }
+        "#
+    }
+}
@@ -0,0 +1,123 @@
+use std::f32::consts::TAU;
+
+/// Distance from the center of the island to the sun (and the moon).
+const LIGHT_DISTANCE: f32 = 18_f32;
+/// Depth (on the z axis) of the sun path.
+const LIGHT_DEPTH: f32 = -10_f32;
+/// Height of the sun (between -1 and 1) where the dusk ends.
+const DUSK_HEIGHT: f32 = 0.2_f32;
+/// Grey of a sky covered by clouds (at noon).
+const OVERCAST_SKY: [f32; 3] = [0.55_f32, 0.57_f32, 0.6_f32];
+/// Fraction of the sunlight that gets through the clouds.
+const OVERCAST_LIGHT: f32 = 0.45_f32;
+
+const DAY: SkyColors = SkyColors {
+    sky: [0.53_f32, 0.81_f32, 0.92_f32],
+    light: [1_f32, 1_f32, 1_f32],
+    ambient: 1_f32,
+};
+const DUSK: SkyColors = SkyColors {
+    sky: [0.98_f32, 0.6_f32, 0.35_f32],
+    light: [1_f32, 0.6_f32, 0.35_f32],
+    ambient: 0.7_f32,
+};
+const NIGHT: SkyColors = SkyColors {
+    sky: [0.03_f32, 0.04_f32, 0.1_f32],
+    light: [0.25_f32, 0.3_f32, 0.45_f32],
+    ambient: 0.35_f32,
+};
+
+/// Colors of a moment of the day.
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct SkyColors {
+    sky: [f32; 3],
+    light: [f32; 3],
+    /// Fraction of the ambient color of the meshes that is seen.
+    ambient: f32,
+}
+
+impl SkyColors {
+    /// Mix two colors.
+    ///
+    /// # Arguments
+    /// * `other` - The other colors.
+    /// * `t` - Weight (between 0 and 1) of the other colors.
+    fn mix(&self, other: &SkyColors, t: f32) -> SkyColors {
+        let mix = |a: f32, b: f32| a + (b - a) * t;
+
+        SkyColors {
+            sky: [0, 1, 2].map(|i| mix(self.sky[i], other.sky[i])),
+            light: [0, 1, 2].map(|i| mix(self.light[i], other.light[i])),
+            ambient: mix(self.ambient, other.ambient),
+        }
+    }
+}
+
+/// Light and sky of the scene on a time of the day.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Sky {
+    /// Position of the sun (or the moon at night).
+    pub light: [f32; 3],
+    pub light_color: [f32; 3],
+    /// Fraction of the ambient color of the meshes that is seen.
+    pub ambient: f32,
+    /// Color used to clear the frame.
+    pub clear_color: (f32, f32, f32, f32),
+}
+
+impl Sky {
+    /// Get the sky of a time of the day.
+    ///
+    /// # Arguments
+    /// * `time_of_day` - Fraction (between 0 and 1) of the day (0 is the sunrise and 0.5 the
+    ///   sunset).
+    pub fn at(time_of_day: f32) -> Self {
+        let angle = time_of_day * TAU;
+        let sun_height = angle.sin();
+
+        // The moon is on the opposite side of the sun.
+        let side = if sun_height >= 0_f32 { 1_f32 } else { -1_f32 };
+        let light = [
+            side * LIGHT_DISTANCE * angle.cos(),
+            side * LIGHT_DISTANCE * sun_height,
+            LIGHT_DEPTH,
+        ];
+
+        let daylight = ((sun_height + DUSK_HEIGHT) / (2_f32 * DUSK_HEIGHT)).clamp(0_f32, 1_f32);
+        let colors = if daylight < 0.5_f32 {
+            NIGHT.mix(&DUSK, daylight * 2_f32)
+        } else {
+            DUSK.mix(&DAY, (daylight - 0.5_f32) * 2_f32)
+        };
+
+        Self {
+            light,
+            light_color: colors.light,
+            ambient: colors.ambient,
+            clear_color: (colors.sky[0], colors.sky[1], colors.sky[2], 1_f32),
+        }
+    }
+
+    /// Cover the sky with clouds (the sky becomes grey and the light dimmer).
+    ///
+    /// # Arguments
+    /// * `clouds` - Fraction (between 0 and 1) of the sky covered by clouds.
+    pub fn with_clouds(self, clouds: f32) -> Self {
+        let mix = |a: f32, b: f32| a + (b - a) * clouds;
+        let brightness = self.light_color.iter().sum::<f32>() / 3_f32;
+        let grey = OVERCAST_SKY.map(|value| value * brightness);
+
+        Self {
+            light_color: self
+                .light_color
+                .map(|value| value * mix(1_f32, OVERCAST_LIGHT)),
+            clear_color: (
+                mix(self.clear_color.0, grey[0]),
+                mix(self.clear_color.1, grey[1]),
+                mix(self.clear_color.2, grey[2]),
+                self.clear_color.3,
+            ),
+            ..self
+        }
+    }
+}
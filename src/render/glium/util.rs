@@ -41,6 +41,28 @@ pub fn height_map_to_mesh<const W: usize, const H: usize>(
     height_map: [[f32; H]; W],
     mesh_colors: ([f32; 3], [f32; 3], [f32; 3]),
     display: &glium::Display,
+) -> Mesh {
+    height_map_to_shaded_mesh(
+        height_map,
+        mesh_colors,
+        GliumRender::create_default_fragment_shader(),
+        display,
+    )
+}
+
+/// Calculate the mesh for a height map drawn with another fragment shader.
+///
+/// # Arguments
+/// * `height_map` - Height map to create the mesh.
+/// * `mesh_colors` - Colors (specular, diffuse and ambient) for the mesh
+/// * `fragment_shader` - Source of the fragment shader.
+/// * `display` - Display struct to create buffers and shader programs.
+///
+pub fn height_map_to_shaded_mesh<const W: usize, const H: usize>(
+    height_map: [[f32; H]; W],
+    mesh_colors: ([f32; 3], [f32; 3], [f32; 3]),
+    fragment_shader: &str,
+    display: &glium::Display,
 ) -> Mesh {
     let mut vertex_array = Vec::<super::Vertex>::new();
     let mut index_array = Vec::<u16>::new();
@@ -72,7 +94,6 @@ pub fn height_map_to_mesh<const W: usize, const H: usize>(
     }
 
     let vertex_shader = GliumRender::create_default_vertex_shader();
-    let fragment_shader = GliumRender::create_default_fragment_shader();
 
     let program =
         glium::Program::from_source(display, vertex_shader, fragment_shader, None).unwrap();
@@ -103,6 +124,35 @@ pub fn height_map_to_mesh<const W: usize, const H: usize>(
     Mesh::new(vertex_buffer, indices, program, matrix, mesh_colors)
 }
 
+/// Height of the water surface on each cell (the surface sinks under the land that is away
+/// from the water, so it does not cover the dry lake beds).
+///
+/// # Arguments
+/// * `height_map` - Height map of the terrain.
+/// * `level` - Height of the water.
+///
+pub fn get_water_levels<const W: usize, const H: usize>(
+    height_map: &[[f32; H]; W],
+    level: f32,
+) -> [[f32; H]; W] {
+    const DRY_DEPTH: f32 = 0.5_f32;
+
+    let mut levels = [[level; H]; W];
+    for (x, column) in levels.iter_mut().enumerate() {
+        for (z, cell) in column.iter_mut().enumerate() {
+            let near_water = (x.saturating_sub(1)..(x + 2).min(W)).any(|n_x| {
+                (z.saturating_sub(1)..(z + 2).min(H)).any(|n_z| height_map[n_x][n_z] < level)
+            });
+
+            if !near_water {
+                *cell = level - DRY_DEPTH;
+            }
+        }
+    }
+
+    levels
+}
+
 /// Calculate the normal for a vertex based on their neightbors.
 ///
 /// # Arguments
@@ -208,6 +258,19 @@ mod test {
         assert_eq!(normalized, [1_f32, 0_f32, 0_f32]);
     }
 
+    #[test]
+    fn should_sink_the_water_under_the_dry_land() {
+        let mut height_map = [[1_f32; 4]; 1];
+        height_map[0][0] = 0.5_f32;
+
+        let levels = get_water_levels(&height_map, 1_f32);
+
+        assert_eq!(levels[0][0], 1_f32);
+        assert_eq!(levels[0][1], 1_f32);
+        assert!(levels[0][2] < 1_f32);
+        assert!(levels[0][3] < 1_f32);
+    }
+
     #[test]
     fn should_cross_vec3() {
         let x = [1_f32, 0_f32, 0_f32];
@@ -0,0 +1,39 @@
+/// A period of the simulation clock that repeats (a day or a season).
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Cycle {
+    /// Ticks of each period.
+    length: u64,
+}
+
+impl Cycle {
+    /// Create a cycle with a period length.
+    ///
+    /// # Arguments
+    /// * `length` - Ticks of a period.
+    /// * `min_length` - The shortest period allowed.
+    pub fn new(length: u64, min_length: u64) -> Self {
+        Self {
+            length: length.max(min_length),
+        }
+    }
+
+    pub fn get_length(&self) -> u64 {
+        self.length
+    }
+
+    /// Amount of whole periods before a tick.
+    ///
+    /// # Arguments
+    /// * `tick` - Tick of the scene.
+    pub fn get_count(&self, tick: u64) -> u64 {
+        tick / self.length
+    }
+
+    /// Fraction (between 0 and 1) of the current period that already passed.
+    ///
+    /// # Arguments
+    /// * `tick` - Tick of the scene.
+    pub fn get_progress(&self, tick: u64) -> f32 {
+        (tick % self.length) as f32 / self.length as f32
+    }
+}
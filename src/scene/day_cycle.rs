@@ -0,0 +1,53 @@
+use super::cycle::Cycle;
+
+/// Ticks of a day when the length is not configured.
+pub const DEFAULT_DAY_LENGTH: u64 = 400;
+
+/// Days and nights of the simulation clock.
+///
+/// Each day starts at sunrise: the sun is up on the first half of the day and the moon on
+/// the second half.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct DayCycle {
+    /// Days, from a sunrise to the next one.
+    days: Cycle,
+}
+
+impl Default for DayCycle {
+    fn default() -> Self {
+        Self::new(DEFAULT_DAY_LENGTH)
+    }
+}
+
+impl DayCycle {
+    /// Create a cycle with a day length.
+    ///
+    /// # Arguments
+    /// * `day_length` - Ticks of a day (at least 2).
+    pub fn new(day_length: u64) -> Self {
+        Self {
+            days: Cycle::new(day_length, 2),
+        }
+    }
+
+    pub fn get_day_length(&self) -> u64 {
+        self.days.get_length()
+    }
+
+    /// Fraction (between 0 and 1) of the day that already passed (0 is the sunrise, 0.25 the
+    /// noon, 0.5 the sunset and 0.75 the midnight).
+    ///
+    /// # Arguments
+    /// * `tick` - Tick of the scene.
+    pub fn get_time_of_day(&self, tick: u64) -> f32 {
+        self.days.get_progress(tick)
+    }
+
+    /// Indicate if the sun is down.
+    ///
+    /// # Arguments
+    /// * `tick` - Tick of the scene.
+    pub fn is_night(&self, tick: u64) -> bool {
+        self.get_time_of_day(tick) >= 0.5_f32
+    }
+}
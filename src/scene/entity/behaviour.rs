@@ -0,0 +1,252 @@
+use std::fmt;
+
+use super::{Entity, EntityMode, EntityType, Need, CATCH_DISTANCE};
+
+/// Stamina below that an animal goes to sleep.
+const TIRED_STAMINA: f32 = 0.2_f32;
+
+/// State of the world around the animals on a tick.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct Environment<'a> {
+    pub night: bool,
+    pub raining: bool,
+    /// Positions of the burning cells.
+    pub fires: &'a [[f32; 3]],
+}
+
+/// What an animal perceives on a tick (used to check the transitions).
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct Senses {
+    /// Nearest visible predator.
+    pub predator: Option<[f32; 3]>,
+    /// Nearest visible burning cell (the aquatic animals ignore the fires).
+    pub fire: Option<[f32; 3]>,
+    /// Nearest visible food (only searched when hungry).
+    pub food: Option<[f32; 3]>,
+    /// Nearest visible water cell (only searched when thirsty).
+    pub water: Option<[f32; 3]>,
+    /// Nearest visible plant that shelters the animal (only searched when it rains).
+    pub shelter: Option<[f32; 3]>,
+    /// If there is no free neighbor cell.
+    pub blocked: bool,
+    pub night: bool,
+}
+
+impl Senses {
+    /// What the animal flees from (the predators first, and then the fires).
+    pub fn get_danger(&self) -> Option<[f32; 3]> {
+        self.predator.or(self.fire)
+    }
+}
+
+impl EntityMode {
+    pub const ALL: [EntityMode; 9] = [
+        EntityMode::Idle,
+        EntityMode::Wandering,
+        EntityMode::Seeking(Need::Food),
+        EntityMode::Seeking(Need::Water),
+        EntityMode::Seeking(Need::Shelter),
+        EntityMode::Eating,
+        EntityMode::Drinking,
+        EntityMode::Sleeping,
+        EntityMode::Fleeing,
+    ];
+
+    /// Name used by the scripts and the saved scenes.
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            EntityMode::Idle => "Idle",
+            EntityMode::Wandering => "Wandering",
+            EntityMode::Seeking(Need::Food) => "SeekingFood",
+            EntityMode::Seeking(Need::Water) => "SeekingWater",
+            EntityMode::Seeking(Need::Shelter) => "SeekingShelter",
+            EntityMode::Eating => "Eating",
+            EntityMode::Drinking => "Drinking",
+            EntityMode::Sleeping => "Sleeping",
+            EntityMode::Fleeing => "Fleeing",
+        }
+    }
+
+    /// Find a mode by its name.
+    ///
+    /// # Arguments
+    /// * `name` - Name of the mode.
+    pub fn from_name(name: &str) -> Option<EntityMode> {
+        Self::ALL.into_iter().find(|mode| mode.get_name() == name)
+    }
+}
+
+/// Condition that triggers a transition.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Condition {
+    PredatorNear,
+    PredatorGone,
+    /// There is a visible fire.
+    FireNear,
+    /// Hungry and there is visible food.
+    FoodSeen,
+    /// Hungry and the food is in reach.
+    FoodReached,
+    FoodLost,
+    Fed,
+    /// Thirsty and there is visible water.
+    WaterSeen,
+    /// Thirsty and the water is in reach.
+    WaterReached,
+    WaterLost,
+    Quenched,
+    /// It rains and there is a visible shelter out of reach.
+    ShelterSeen,
+    /// It rains and the shelter is in reach.
+    Sheltered,
+    /// The rain stopped (or the shelter is not visible anymore).
+    ShelterLost,
+    Tired,
+    /// Full stamina, and it is the active time of the species (or the animal is hungry or
+    /// thirsty).
+    Rested,
+    /// It is out of the active time of the species, and the animal is not hungry or thirsty.
+    RestTime,
+    Blocked,
+    /// The animal has been in the current mode for at least this amount of ticks.
+    TimeInMode(u32),
+    /// Requested by the species script (it is never met on the transitions).
+    Script,
+}
+
+impl Condition {
+    /// Check the condition for an animal.
+    ///
+    /// # Arguments
+    /// * `entity` - The animal.
+    /// * `senses` - What the animal perceives.
+    pub fn is_met(&self, entity: &Entity, senses: &Senses) -> bool {
+        let is_active = entity
+            .entity_type
+            .species()
+            .activity
+            .is_active(senses.night);
+        let in_reach = |target: Option<[f32; 3]>| {
+            target.is_some_and(|target| entity.distance(target) <= CATCH_DISTANCE)
+        };
+
+        match self {
+            Condition::PredatorNear => senses.predator.is_some(),
+            Condition::PredatorGone => senses.predator.is_none(),
+            Condition::FireNear => senses.fire.is_some(),
+            Condition::FoodSeen => entity.is_hungry() && senses.food.is_some(),
+            Condition::FoodReached => entity.is_hungry() && in_reach(senses.food),
+            Condition::FoodLost => senses.food.is_none(),
+            Condition::Fed => !entity.is_hungry(),
+            Condition::WaterSeen => entity.is_thirsty() && senses.water.is_some(),
+            Condition::WaterReached => entity.is_thirsty() && in_reach(senses.water),
+            Condition::WaterLost => senses.water.is_none(),
+            Condition::Quenched => entity.hydration >= entity.entity_type.species().max_hydration,
+            Condition::ShelterSeen => senses.shelter.is_some() && !in_reach(senses.shelter),
+            Condition::Sheltered => in_reach(senses.shelter),
+            Condition::ShelterLost => senses.shelter.is_none(),
+            Condition::Tired => entity.stamina <= TIRED_STAMINA,
+            Condition::Rested => {
+                entity.stamina >= 1_f32 && (is_active || entity.is_hungry() || entity.is_thirsty())
+            }
+            Condition::RestTime => !is_active && !entity.is_hungry() && !entity.is_thirsty(),
+            Condition::Blocked => senses.blocked,
+            Condition::TimeInMode(ticks) => entity.mode_ticks >= *ticks,
+            Condition::Script => false,
+        }
+    }
+}
+
+/// Change to the `to` mode (from any of the `from` modes) when the condition is met.
+///
+/// A transition to the mode the animal is already in is a hold: it keeps the animal in the
+/// mode while the condition is met, since the later transitions are not checked.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Transition {
+    pub from: &'static [EntityMode],
+    pub to: EntityMode,
+    pub condition: Condition,
+}
+
+/// A mode change of an animal (used to debug the behaviour).
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ModeChange {
+    pub entity_type: EntityType,
+    pub position: [f32; 3],
+    pub from: EntityMode,
+    pub to: EntityMode,
+    pub reason: Condition,
+}
+
+impl fmt::Display for ModeChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} at ({}, {}): {:?} -> {:?} ({:?})",
+            self.entity_type, self.position[0], self.position[2], self.from, self.to, self.reason
+        )
+    }
+}
+
+impl Entity {
+    /// Find the first transition (in the species order) that leaves the current mode.
+    ///
+    /// A matching transition to the current mode keeps the animal in it.
+    ///
+    /// # Arguments
+    /// * `senses` - What the animal perceives.
+    pub fn next_mode(&self, senses: &Senses) -> Option<(EntityMode, Condition)> {
+        self.entity_type
+            .species()
+            .behaviour
+            .iter()
+            .find(|transition| {
+                transition.from.contains(&self.entity_mode)
+                    && transition.condition.is_met(self, senses)
+            })
+            .filter(|transition| transition.to != self.entity_mode)
+            .map(|transition| (transition.to, transition.condition))
+    }
+
+    /// Change the mode (running the exit and enter hooks).
+    ///
+    /// # Arguments
+    /// * `mode` - The new mode.
+    /// * `reason` - Condition that triggered the change.
+    /// * `senses` - What the animal perceives.
+    pub fn set_mode(&mut self, mode: EntityMode, reason: Condition, senses: &Senses) -> ModeChange {
+        let change = ModeChange {
+            entity_type: self.entity_type,
+            position: self.position,
+            from: self.entity_mode,
+            to: mode,
+            reason,
+        };
+
+        self.exit_mode();
+        self.entity_mode = mode;
+        self.enter_mode(senses);
+
+        change
+    }
+
+    fn exit_mode(&mut self) {
+        self.target = None;
+    }
+
+    fn enter_mode(&mut self, senses: &Senses) {
+        self.mode_ticks = 0;
+
+        let facing = match self.entity_mode {
+            EntityMode::Eating => senses.food,
+            EntityMode::Drinking => senses.water,
+            _ => None,
+        };
+
+        if let Some(point) = facing {
+            let delta_x = point[0] - self.position[0];
+            let delta_z = point[2] - self.position[2];
+            self.set_rotation(delta_z.atan2(delta_x).to_degrees().rem_euclid(360_f32));
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use rand::Rng;
+
+use super::behaviour::Environment;
+use super::species::Habitat;
+use super::tree_entity::TreeEntity;
+use super::{get_height, Entity, EntityMode, MoveReport, Need, WATER_LEVEL};
+
+/// Horizontal distance flown on each tick (by an animal with the base speed).
+const FLIGHT_SPEED: f32 = 1.5_f32;
+/// Min distance between a flying animal and the ground.
+const CLEARANCE: f32 = 0.5_f32;
+/// Distance between the points where the ground under a flight is checked.
+const GROUND_SAMPLE_STEP: f32 = 0.5_f32;
+/// Segments used to measure the length of a flight.
+const LENGTH_SEGMENTS: usize = 8;
+
+/// Flight of an animal between two perches.
+///
+/// The path is a cubic Bézier curve that climbs from the start to the cruise height (the
+/// species altitude above the highest ground under the path) and descends to the landing
+/// point.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Flight {
+    pub from: [f32; 3],
+    pub to: [f32; 3],
+    /// Fraction (between 0 and 1) of the path already flown.
+    pub progress: f32,
+}
+
+impl Flight {
+    pub fn new(from: [f32; 3], to: [f32; 3]) -> Self {
+        Self {
+            from,
+            to,
+            progress: 0_f32,
+        }
+    }
+
+    /// Height of the top of the path.
+    ///
+    /// # Arguments
+    /// * `altitude` - Height above the ground where the animal flies.
+    /// * `height_map` - Height map of the scene.
+    pub fn get_cruise_height<const W: usize, const H: usize>(
+        &self,
+        altitude: f32,
+        height_map: &[[f32; H]; W],
+    ) -> f32 {
+        let delta_x = self.to[0] - self.from[0];
+        let delta_z = self.to[2] - self.from[2];
+        let distance = (delta_x * delta_x + delta_z * delta_z).sqrt();
+        let samples = (distance / GROUND_SAMPLE_STEP).ceil() as usize;
+
+        let highest_ground = (0..=samples)
+            .map(|sample| {
+                let t = sample as f32 / samples.max(1) as f32;
+                get_ground(
+                    [
+                        self.from[0] + delta_x * t,
+                        0_f32,
+                        self.from[2] + delta_z * t,
+                    ],
+                    height_map,
+                )
+            })
+            .fold(WATER_LEVEL, f32::max);
+
+        (highest_ground + altitude)
+            .max(self.from[1])
+            .max(self.to[1])
+    }
+
+    /// Point of the path.
+    ///
+    /// # Arguments
+    /// * `t` - Fraction (between 0 and 1) of the path.
+    /// * `cruise_height` - Height of the top of the path.
+    pub fn get_point(&self, t: f32, cruise_height: f32) -> [f32; 3] {
+        let controls = [
+            self.from,
+            [self.from[0], cruise_height, self.from[2]],
+            [self.to[0], cruise_height, self.to[2]],
+            self.to,
+        ];
+        let weights = [
+            (1_f32 - t).powi(3),
+            3_f32 * (1_f32 - t).powi(2) * t,
+            3_f32 * (1_f32 - t) * t * t,
+            t.powi(3),
+        ];
+
+        let mut point = [0_f32; 3];
+        for (control, weight) in controls.iter().zip(weights) {
+            for (coord, value) in point.iter_mut().zip(control) {
+                *coord += value * weight;
+            }
+        }
+
+        point
+    }
+
+    /// Approximated length of the path.
+    ///
+    /// # Arguments
+    /// * `cruise_height` - Height of the top of the path.
+    fn get_length(&self, cruise_height: f32) -> f32 {
+        let points: Vec<[f32; 3]> = (0..=LENGTH_SEGMENTS)
+            .map(|segment| self.get_point(segment as f32 / LENGTH_SEGMENTS as f32, cruise_height))
+            .collect();
+
+        points
+            .windows(2)
+            .map(|pair| {
+                let squared: f32 = (0..3).map(|i| (pair[1][i] - pair[0][i]).powi(2)).sum();
+                squared.sqrt()
+            })
+            .sum()
+    }
+}
+
+impl Entity {
+    pub fn is_flying(&self) -> bool {
+        self.flight.is_some()
+    }
+
+    /// Run the behaviour tick of a flying animal: fly to the next perch or check the mode
+    /// transitions and choose a perch (a plant) based on the mode.
+    ///
+    /// # Arguments
+    /// * `height_map` - Height map of the scene.
+    /// * `tree_plants` - Plants of the scene (used as perches).
+    /// * `animals` - Animals of the scene.
+    /// * `environment` - State of the world around the animal.
+    /// * `rand` - Random generator used to wander.
+    pub(super) fn change_flight_mode<const W: usize, const H: usize>(
+        &mut self,
+        height_map: &[[f32; H]; W],
+        tree_plants: &TreeEntity,
+        animals: &[Entity],
+        environment: &Environment,
+        rand: &mut impl Rng,
+    ) -> MoveReport {
+        let mut report = MoveReport::default();
+
+        if self.flight.is_some() {
+            self.fly(height_map);
+            return report;
+        }
+
+        let sight_radius = self.get_sight_radius();
+        let perches: Vec<Entity> = tree_plants
+            .get_near(self.position, sight_radius)
+            .into_iter()
+            .filter(|plant| plant.distance(self.position) > 0_f32)
+            .collect();
+
+        let senses = self.sense(
+            height_map,
+            tree_plants,
+            animals,
+            environment,
+            perches.is_empty(),
+        );
+        if let Some((mode, reason)) = self.next_mode(&senses) {
+            report.mode_change = Some(self.set_mode(mode, reason, &senses));
+        }
+
+        let perch = match self.entity_mode {
+            EntityMode::Idle
+            | EntityMode::Eating
+            | EntityMode::Sleeping
+            | EntityMode::Seeking(Need::Water)
+            | EntityMode::Seeking(Need::Shelter) => return report,
+            EntityMode::Drinking => {
+                self.drink(super::DRINK_AMOUNT);
+                report.drank = true;
+                return report;
+            }
+            EntityMode::Seeking(Need::Food) => senses.food,
+            EntityMode::Fleeing => senses.get_danger().and_then(|danger| {
+                perches.iter().map(|perch| perch.position).max_by(|a, b| {
+                    let distance = |point: &[f32; 3]| {
+                        (point[0] - danger[0]).powi(2) + (point[2] - danger[2]).powi(2)
+                    };
+                    distance(a).total_cmp(&distance(b))
+                })
+            }),
+            EntityMode::Wandering if perches.is_empty() => None,
+            EntityMode::Wandering => Some(perches[rand.gen_range(0..perches.len())].position),
+        };
+
+        match perch {
+            Some(perch) => self.take_off(perch, height_map),
+            None => report.failed = true,
+        }
+
+        report
+    }
+
+    /// Start a flight to a perch (facing it).
+    ///
+    /// # Arguments
+    /// * `perch` - Position of the perch.
+    /// * `height_map` - Height map of the scene.
+    pub(super) fn take_off<const W: usize, const H: usize>(
+        &mut self,
+        perch: [f32; 3],
+        height_map: &[[f32; H]; W],
+    ) {
+        let cell = (perch[0] as isize, perch[2] as isize);
+        let ground = get_height(cell, height_map).unwrap_or(perch[1]);
+        let landing = [
+            perch[0],
+            self.entity_type.species().habitat.get_height(ground),
+            perch[2],
+        ];
+
+        let delta_x = landing[0] - self.position[0];
+        let delta_z = landing[2] - self.position[2];
+        if delta_x != 0_f32 || delta_z != 0_f32 {
+            self.set_rotation(delta_z.atan2(delta_x).to_degrees().rem_euclid(360_f32));
+        }
+
+        self.flight = Some(Flight::new(self.position, landing));
+    }
+
+    /// Fly along the path of the flight (landing at its end).
+    ///
+    /// # Arguments
+    /// * `height_map` - Height map of the scene.
+    pub(super) fn fly<const W: usize, const H: usize>(&mut self, height_map: &[[f32; H]; W]) {
+        let (flight, altitude) = match (self.flight, self.entity_type.species().habitat) {
+            (Some(flight), Habitat::Air { altitude, .. }) => (flight, altitude),
+            _ => return,
+        };
+
+        let cruise_height = flight.get_cruise_height(altitude, height_map);
+        let speed = FLIGHT_SPEED * self.genome.get_speed_ratio();
+        let length = flight.get_length(cruise_height).max(speed);
+        let progress = (flight.progress + speed / length).min(1_f32);
+
+        let mut point = flight.get_point(progress, cruise_height);
+        if progress < 1_f32 {
+            point[1] = point[1].max(get_ground(point, height_map) + CLEARANCE);
+        }
+        self.position = point;
+
+        self.flight = if progress < 1_f32 {
+            Some(Flight { progress, ..flight })
+        } else {
+            None
+        };
+    }
+}
+
+/// Highest ground (or water surface) around a point, so the terrain mesh between the cells
+/// is never above it.
+///
+/// # Arguments
+/// * `point` - The point.
+/// * `height_map` - Height map of the scene.
+fn get_ground<const W: usize, const H: usize>(point: [f32; 3], height_map: &[[f32; H]; W]) -> f32 {
+    let (x, z) = (point[0].floor() as isize, point[2].floor() as isize);
+
+    [(x, z), (x + 1, z), (x, z + 1), (x + 1, z + 1)]
+        .into_iter()
+        .filter_map(|cell| get_height(cell, height_map))
+        .fold(WATER_LEVEL, f32::max)
+}
@@ -0,0 +1,141 @@
+use super::{species::Flocking, Entity};
+
+impl Entity {
+    /// Calculate the boids steering (separation, alignment, cohesion and leader) on the xz plane.
+    ///
+    /// # Arguments
+    /// * `animals` - Animals of the scene.
+    /// * `flocking` - Steering weights of the species.
+    pub fn get_flocking_steering(&self, animals: &[Entity], flocking: &Flocking) -> [f32; 2] {
+        let sight_radius = self.get_sight_radius();
+
+        let flockmates: Vec<&Entity> = animals
+            .iter()
+            .filter(|animal| animal.entity_type == self.entity_type)
+            .filter(|animal| {
+                let distance = self.distance(animal.position);
+                distance > 0_f32 && distance <= sight_radius
+            })
+            .collect();
+
+        if flockmates.is_empty() {
+            return [0_f32; 2];
+        }
+
+        let leader = flockmates
+            .iter()
+            .copied()
+            .filter(|flockmate| flockmate.is_older(self))
+            .reduce(|oldest, flockmate| {
+                if flockmate.is_older(oldest) {
+                    flockmate
+                } else {
+                    oldest
+                }
+            });
+
+        // The herd leader wanders freely and the others follow it.
+        if flocking.leader_weight > 0_f32 && leader.is_none() {
+            return [0_f32; 2];
+        }
+
+        let mut separation = [0_f32; 2];
+        let mut alignment = [0_f32; 2];
+        let mut center = [0_f32; 2];
+
+        for flockmate in flockmates.iter() {
+            let distance = self.distance(flockmate.position);
+            if distance < flocking.separation_distance {
+                separation[0] += (self.position[0] - flockmate.position[0]) / (distance * distance);
+                separation[1] += (self.position[2] - flockmate.position[2]) / (distance * distance);
+            }
+
+            let heading = flockmate.get_heading();
+            alignment[0] += heading[0];
+            alignment[1] += heading[1];
+
+            center[0] += flockmate.position[0];
+            center[1] += flockmate.position[2];
+        }
+
+        let count = flockmates.len() as f32;
+        let cohesion = normalize_2d([
+            center[0] / count - self.position[0],
+            center[1] / count - self.position[2],
+        ]);
+        let alignment = normalize_2d(alignment);
+        let separation = normalize_2d(separation);
+
+        let leader = match leader {
+            Some(leader) if self.distance(leader.position) > flocking.separation_distance => {
+                normalize_2d([
+                    leader.position[0] - self.position[0],
+                    leader.position[2] - self.position[2],
+                ])
+            }
+            _ => [0_f32; 2],
+        };
+
+        let mut steering = [0_f32; 2];
+        for i in 0..2 {
+            steering[i] = separation[i] * flocking.separation_weight
+                + alignment[i] * flocking.alignment_weight
+                + cohesion[i] * flocking.cohesion_weight
+                + leader[i] * flocking.leader_weight;
+        }
+
+        steering
+    }
+
+    /// Indicate if the entity is older than another one (ties are broken by the position).
+    ///
+    /// # Arguments
+    /// * `other` - Entity to compare.
+    fn is_older(&self, other: &Entity) -> bool {
+        let key = |entity: &Entity| (entity.age, entity.position[0], entity.position[2]);
+
+        key(self) > key(other)
+    }
+
+    /// Direction (on the xz plane) where the entity is looking.
+    fn get_heading(&self) -> [f32; 2] {
+        let radians = self.rotation.to_radians();
+
+        [radians.cos(), radians.sin()]
+    }
+
+    /// Choose the index (in `valid_positions`) that best follows a steering direction.
+    ///
+    /// # Arguments
+    /// * `possible_position` - Neighbor cells.
+    /// * `valid_positions` - Indexes of the walkable cells in `possible_position`.
+    /// * `steering` - Desired direction on the xz plane.
+    pub fn choose_steering_position(
+        &self,
+        possible_position: &[(isize, isize)],
+        valid_positions: &[usize],
+        steering: [f32; 2],
+    ) -> usize {
+        let alignment = |index: &usize| {
+            let (x, z) = possible_position[valid_positions[*index]];
+            let direction =
+                normalize_2d([x as f32 - self.position[0], z as f32 - self.position[2]]);
+
+            direction[0] * steering[0] + direction[1] * steering[1]
+        };
+
+        (0..valid_positions.len())
+            .max_by(|a, b| alignment(a).total_cmp(&alignment(b)))
+            .unwrap_or(0)
+    }
+}
+
+fn normalize_2d(vector: [f32; 2]) -> [f32; 2] {
+    let len = (vector[0] * vector[0] + vector[1] * vector[1]).sqrt();
+
+    if len == 0_f32 {
+        [0_f32; 2]
+    } else {
+        [vector[0] / len, vector[1] / len]
+    }
+}
@@ -0,0 +1,134 @@
+use rand::Rng;
+
+/// Cells walked on each tick by an animal without mutations.
+pub const BASE_SPEED: f32 = 0.5_f32;
+/// Amount of traits of a genome.
+pub const TRAIT_COUNT: usize = 7;
+/// Names of the traits (in the `Genome::get_traits` order).
+pub const TRAIT_NAMES: [&str; TRAIT_COUNT] = [
+    "speed",
+    "sight",
+    "size",
+    "metabolism",
+    "tint_r",
+    "tint_g",
+    "tint_b",
+];
+/// Min and max of each trait (in the `Genome::get_traits` order).
+const TRAIT_RANGES: [(f32, f32); TRAIT_COUNT] = [
+    (0.25_f32, 1_f32),
+    (0.5_f32, 1.5_f32),
+    (0.5_f32, 1.5_f32),
+    (0.5_f32, 1.5_f32),
+    (0.6_f32, 1.4_f32),
+    (0.6_f32, 1.4_f32),
+    (0.6_f32, 1.4_f32),
+];
+/// Chance (between 0 and 1) of each trait of an offspring to mutate.
+const MUTATION_CHANCE: f64 = 0.2_f64;
+/// Max change of a trait on a mutation (as a fraction of the value).
+const MUTATION_SIZE: f32 = 0.1_f32;
+/// Extra energy spent for each unit of the speed ratio above the base speed.
+const SPEED_COST: f32 = 0.5_f32;
+/// Extra energy spent for each unit of the sight ratio above 1.
+const SIGHT_COST: f32 = 0.3_f32;
+
+/// Heritable traits of an animal (the plants keep the default genome).
+///
+/// Each trait has a cost: the faster, wider sighted, bigger and faster aging animals spend more
+/// energy to stay alive.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Genome {
+    /// Cells walked on each tick (the flights are faster in the same ratio).
+    pub speed: f32,
+    /// Ratio of the species sight radius.
+    pub sight: f32,
+    /// Ratio of the body size (the bigger animals store more energy).
+    pub size: f32,
+    /// Ratio of the metabolic rate (the animals mature sooner, but also get old sooner and get
+    /// thirsty faster).
+    pub metabolism: f32,
+    /// Ratio of each channel of the species color (it does not change the fitness).
+    pub tint: [f32; 3],
+}
+
+impl Default for Genome {
+    fn default() -> Self {
+        Self {
+            speed: BASE_SPEED,
+            sight: 1_f32,
+            size: 1_f32,
+            metabolism: 1_f32,
+            tint: [1_f32; 3],
+        }
+    }
+}
+
+impl Genome {
+    /// Create a genome from the values of its traits (in the `get_traits` order).
+    ///
+    /// # Arguments
+    /// * `traits` - Values of the traits.
+    pub fn from_traits(traits: [f32; TRAIT_COUNT]) -> Self {
+        Self {
+            speed: traits[0],
+            sight: traits[1],
+            size: traits[2],
+            metabolism: traits[3],
+            tint: [traits[4], traits[5], traits[6]],
+        }
+    }
+
+    /// Values of the traits (in the `TRAIT_NAMES` order).
+    pub fn get_traits(&self) -> [f32; TRAIT_COUNT] {
+        [
+            self.speed,
+            self.sight,
+            self.size,
+            self.metabolism,
+            self.tint[0],
+            self.tint[1],
+            self.tint[2],
+        ]
+    }
+
+    /// Create the genome of an offspring: each trait comes from one of the parents and may
+    /// mutate (inside the trait range).
+    ///
+    /// # Arguments
+    /// * `first` - Genome of a parent.
+    /// * `second` - Genome of the other parent.
+    /// * `rand` - Random generator of the scene.
+    pub fn inherit(first: &Genome, second: &Genome, rand: &mut impl Rng) -> Self {
+        let first = first.get_traits();
+        let second = second.get_traits();
+
+        Self::from_traits(std::array::from_fn(|i| {
+            let mut value = if rand.gen_bool(0.5_f64) {
+                first[i]
+            } else {
+                second[i]
+            };
+
+            if rand.gen_bool(MUTATION_CHANCE) {
+                value *= 1_f32 + rand.gen_range(-MUTATION_SIZE..=MUTATION_SIZE);
+            }
+
+            let (min, max) = TRAIT_RANGES[i];
+            value.clamp(min, max)
+        }))
+    }
+
+    /// Ratio of the speed to the base speed.
+    pub fn get_speed_ratio(&self) -> f32 {
+        self.speed / BASE_SPEED
+    }
+
+    /// Ratio of the species energy cost spent by an animal with this genome.
+    pub fn get_energy_ratio(&self) -> f32 {
+        let speed = 1_f32 + SPEED_COST * (self.get_speed_ratio() - 1_f32);
+        let sight = 1_f32 + SIGHT_COST * (self.sight - 1_f32);
+
+        self.size * self.metabolism * speed * sight
+    }
+}
@@ -1,15 +1,81 @@
 use rand::Rng;
 
+use self::behaviour::{Environment, ModeChange, Senses};
+use self::flight::Flight;
+use self::genome::Genome;
+use self::species::Habitat;
 use self::tree_entity::TreeEntity;
 
+pub mod behaviour;
+pub mod flight;
+mod flocking;
+pub mod genome;
+pub mod perception;
+pub mod record;
+pub mod species;
 #[cfg(test)]
 mod test;
 pub mod tree_entity;
 
+/// Energy ratio (of the species max energy) below that an animal starts to hunt.
+const HUNGRY_RATIO: f32 = 0.75_f32;
+/// Max distance where a predator catches its prey.
+pub const CATCH_DISTANCE: f32 = 1.5_f32;
+/// Min growth of a plant to be eaten.
+const EDIBLE_GROWTH: f32 = 0.5_f32;
+/// Min growth of a plant to shelter the animals from the rain.
+const SHELTER_GROWTH: f32 = 0.5_f32;
+/// Scale of a plant that just sprouted.
+const SEEDLING_SCALE: f32 = 0.1_f32;
+/// Height of the water surface (the land animals walk above it).
+pub const WATER_LEVEL: f32 = 1_f32;
+/// Distance (in cells) that an animal walks around to save one energy of climbing.
+const GENTLE_ROUTE_WEIGHT: f32 = 2_f32;
+/// Hydration ratio (of the species max hydration) below that an animal looks for water.
+const THIRSTY_RATIO: f32 = 0.75_f32;
+/// Fraction of the energy cost spent on each tick sleeping.
+const SLEEP_ENERGY_RATIO: f32 = 0.5_f32;
+/// Hydration gained on each tick drinking.
+const DRINK_AMOUNT: f32 = 25_f32;
+/// Growth of a plant eaten on each tick eating (the animal gains the same share of the plant
+/// nutrition).
+pub const BITE_GROWTH: f32 = 0.25_f32;
+
+/// Identifier of an entity (given by the scene, the entities outside a scene have 0).
+pub type EntityId = u64;
+
+/// What happened when an animal tried to choose its next cell.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct MoveReport {
+    /// Walkable neighbor cells rejected because another entity was there.
+    pub collisions_avoided: usize,
+    /// If there was no cell to move to.
+    pub failed: bool,
+    pub mode_change: Option<ModeChange>,
+    /// If the animal drank on this tick.
+    pub drank: bool,
+}
+
+/// What an animal is looking for.
 #[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Need {
+    Food,
+    Water,
+    /// A plant to hide under while it rains.
+    Shelter,
+}
+
+/// States of the animal behaviour (the transitions are defined by each species).
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
 pub enum EntityMode {
+    #[default]
     Idle,
-    Walking { target: (f32, f32) },
+    Wandering,
+    Seeking(Need),
+    Eating,
+    Drinking,
+    Sleeping,
+    Fleeing,
 }
 
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -18,100 +84,598 @@ pub enum EntityType {
     Animal2,
     Plant1,
     Plant2,
+    Fish,
+    Dolphin,
+    Bird,
 }
 
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub struct Entity {
+    id: EntityId,
     pub position: [f32; 3],
     pub rotation: f32,
-    speed: f32,
+    last_position: [f32; 3],
+    last_rotation: f32,
+    /// Heritable traits (the animals of a species are different from each other).
+    genome: Genome,
+    energy: f32,
+    hydration: f32,
+    stamina: f32,
+    growth: f32,
+    age: u32,
     entity_type: EntityType,
     entity_mode: EntityMode,
+    /// Ticks since the last mode change.
+    mode_ticks: u32,
+    /// Cell where the animal is walking to.
+    target: Option<(f32, f32)>,
+    /// Energy spent to climb or descend to the target (given back if the walk is cancelled).
+    target_cost: f32,
+    /// Flight to the next perch (only for the flying animals).
+    flight: Option<Flight>,
 }
 
 impl Entity {
     pub fn new(position: [f32; 3], entity_type: EntityType) -> Self {
-        let rotation = match entity_type {
-            EntityType::Plant1 | EntityType::Plant2 => {
-                let mut rand = rand::thread_rng();
-                rand.gen_range(0_f32..360_f32)
-            }
-            _ => 0_f32,
-        };
+        Self::with_rotation(position, 0_f32, entity_type)
+    }
 
+    /// Create an entity already facing a direction.
+    ///
+    /// # Arguments
+    /// * `position` - Position of the entity.
+    /// * `rotation` - Angle (in degrees) of the entity.
+    /// * `entity_type` - Type of the entity.
+    pub fn with_rotation(position: [f32; 3], rotation: f32, entity_type: EntityType) -> Self {
         Self {
+            id: 0,
             position,
             rotation,
-            speed: 0.5_f32,
+            last_position: position,
+            last_rotation: rotation,
+            genome: Genome::default(),
+            energy: entity_type.species().max_energy,
+            hydration: entity_type.species().max_hydration,
+            stamina: 1_f32,
+            growth: 1_f32,
+            age: 0,
             entity_type,
             entity_mode: EntityMode::Idle,
+            mode_ticks: 0,
+            target: None,
+            target_cost: 0_f32,
+            flight: None,
         }
     }
 
+    /// Create an animal with a genome (inherited from its parents).
+    ///
+    /// # Arguments
+    /// * `position` - Position of the entity.
+    /// * `entity_type` - Type of the entity.
+    /// * `genome` - Heritable traits of the entity.
+    pub fn with_genome(position: [f32; 3], entity_type: EntityType, genome: Genome) -> Self {
+        let mut entity = Self::new(position, entity_type);
+        entity.genome = genome;
+        entity.energy = entity.get_max_energy();
+
+        entity
+    }
+
+    pub fn get_id(&self) -> EntityId {
+        self.id
+    }
+
+    pub fn set_id(&mut self, id: EntityId) {
+        self.id = id;
+    }
+
     pub fn get_type(&self) -> EntityType {
         self.entity_type
     }
 
+    pub fn get_genome(&self) -> Genome {
+        self.genome
+    }
+
+    /// Energy of a well fed entity (the bigger animals store more energy).
+    pub fn get_max_energy(&self) -> f32 {
+        self.entity_type.species().max_energy * self.genome.size
+    }
+
+    /// Max distance (in cells) where the entity can see other entities.
+    pub fn get_sight_radius(&self) -> f32 {
+        self.entity_type.species().sight_radius * self.genome.sight
+    }
+
     pub fn set_rotation(&mut self, angle: f32) {
         self.rotation = angle;
     }
 
+    /// Update the height of the entity (used to put it on the ground).
+    ///
+    /// # Arguments
+    /// * `height` - The new height.
+    pub fn set_height(&mut self, height: f32) {
+        self.position[1] = height;
+        self.last_position[1] = height;
+    }
+
+    /// Restore the energy after eating.
+    ///
+    /// # Arguments
+    /// * `energy` - Energy gained.
+    pub fn feed(&mut self, energy: f32) {
+        self.energy = (self.energy + energy).min(self.get_max_energy());
+    }
+
+    /// Spend energy (it does not get below zero).
+    ///
+    /// # Arguments
+    /// * `energy` - Energy spent.
+    pub fn spend_energy(&mut self, energy: f32) {
+        self.energy = (self.energy - energy).max(0_f32);
+    }
+
+    pub fn is_starving(&self) -> bool {
+        !self.entity_type.is_plant() && self.energy <= 0_f32
+    }
+
+    /// Restore the hydration after drinking.
+    ///
+    /// # Arguments
+    /// * `hydration` - Hydration gained.
+    pub fn drink(&mut self, hydration: f32) {
+        self.hydration = (self.hydration + hydration).min(self.entity_type.species().max_hydration);
+    }
+
+    pub fn is_thirsty(&self) -> bool {
+        self.hydration < self.entity_type.species().max_hydration * THIRSTY_RATIO
+    }
+
+    pub fn is_dehydrated(&self) -> bool {
+        !self.entity_type.is_plant() && self.hydration <= 0_f32
+    }
+
+    pub fn get_growth(&self) -> f32 {
+        self.growth
+    }
+
+    /// Update the growth stage (between 0 and 1).
+    ///
+    /// # Arguments
+    /// * `growth` - The new growth stage.
+    pub fn set_growth(&mut self, growth: f32) {
+        self.growth = growth.clamp(0_f32, 1_f32);
+    }
+
+    /// Scale used to draw the entity (plants grow from a seedling).
+    pub fn get_scale(&self) -> f32 {
+        if self.entity_type.is_plant() {
+            SEEDLING_SCALE + (1_f32 - SEEDLING_SCALE) * self.growth
+        } else {
+            self.genome.size
+        }
+    }
+
+    pub fn is_edible(&self) -> bool {
+        !self.entity_type.is_plant() || self.growth >= EDIBLE_GROWTH
+    }
+
+    /// Change the ticks lived by the entity.
+    ///
+    /// # Arguments
+    /// * `age` - The new age.
+    pub fn set_age(&mut self, age: u32) {
+        self.age = age;
+    }
+
+    pub fn is_adult(&self) -> bool {
+        self.get_biological_age() >= self.entity_type.species().maturity_age as f32
+    }
+
+    pub fn is_too_old(&self) -> bool {
+        !self.entity_type.is_plant()
+            && self.get_biological_age() >= self.entity_type.species().max_age as f32
+    }
+
+    /// Age scaled by the metabolic rate (a faster metabolism matures and gets old sooner).
+    fn get_biological_age(&self) -> f32 {
+        self.age as f32 * self.genome.metabolism
+    }
+
+    /// Indicate if the entity can mate with another one.
+    ///
+    /// # Arguments
+    /// * `other` - The possible partner.
+    pub fn can_mate(&self, other: &Entity) -> bool {
+        let species = self.entity_type.species();
+        let is_ready =
+            |entity: &Entity| entity.is_adult() && entity.energy >= species.mating_energy;
+
+        !self.entity_type.is_plant()
+            && self.entity_type == other.entity_type
+            && is_ready(self)
+            && is_ready(other)
+            && self.distance(other.position) <= CATCH_DISTANCE
+    }
+
+    pub fn is_hungry(&self) -> bool {
+        self.energy < self.get_max_energy() * HUNGRY_RATIO
+    }
+
+    /// Position between the last tick and the current one.
+    ///
+    /// # Arguments
+    /// * `alpha` - Fraction (between 0 and 1) of the way from the last tick.
+    pub fn get_interpolated_position(&self, alpha: f32) -> [f32; 3] {
+        let mut position = self.position;
+
+        for (i, coord) in position.iter_mut().enumerate() {
+            *coord = self.last_position[i] + (self.position[i] - self.last_position[i]) * alpha;
+        }
+
+        position
+    }
+
+    /// Rotation between the last tick and the current one (using the shortest turn).
+    ///
+    /// # Arguments
+    /// * `alpha` - Fraction (between 0 and 1) of the way from the last tick.
+    pub fn get_interpolated_rotation(&self, alpha: f32) -> f32 {
+        let delta = (self.rotation - self.last_rotation + 540_f32).rem_euclid(360_f32) - 180_f32;
+
+        self.last_rotation + delta * alpha
+    }
+
+    /// Keep the current transform as the start of the next interpolation.
+    fn save_transform(&mut self) {
+        self.last_position = self.position;
+        self.last_rotation = self.rotation;
+    }
+
+    /// Distance between the entity and a point (ignoring the height).
+    ///
+    /// # Arguments
+    /// * `position` - The point to compare.
+    pub fn distance(&self, position: [f32; 3]) -> f32 {
+        let delta_x = self.position[0] - position[0];
+        let delta_z = self.position[2] - position[2];
+
+        (delta_x * delta_x + delta_z * delta_z).sqrt()
+    }
+
+    /// Run a behaviour tick: spend the needs, walk to the target or check the mode transitions
+    /// and act based on the mode.
+    ///
+    /// # Arguments
+    /// * `height_map` - Height map of the scene.
+    /// * `tree_plants` - Plants of the scene.
+    /// * `animals` - Animals of the scene.
+    /// * `environment` - State of the world around the animal.
+    /// * `rand` - Random generator used to wander.
     pub fn change_mode<const W: usize, const H: usize>(
         &mut self,
         height_map: &[[f32; H]; W],
         tree_plants: &TreeEntity,
         animals: &Vec<Entity>,
+        environment: &Environment,
+        rand: &mut impl Rng,
+    ) -> MoveReport {
+        let mut report = MoveReport::default();
+
+        if self.entity_type.is_plant() {
+            return report;
+        }
+
+        self.save_transform();
+
+        let species = self.entity_type.species();
+        let energy_cost = species.energy_cost * self.genome.get_energy_ratio();
+        if self.entity_mode == EntityMode::Sleeping {
+            self.spend_energy(energy_cost * SLEEP_ENERGY_RATIO);
+        } else {
+            self.spend_energy(energy_cost);
+        }
+        self.feed(species.plankton);
+        if self.entity_mode != EntityMode::Drinking {
+            let thirst_cost = species.thirst_cost * self.genome.metabolism;
+            self.hydration = (self.hydration - thirst_cost).max(0_f32);
+        }
+        self.stamina = if self.entity_mode == EntityMode::Sleeping {
+            (self.stamina + species.rest_rate).min(1_f32)
+        } else {
+            (self.stamina - species.fatigue_rate).max(0_f32)
+        };
+        self.age += 1;
+        self.mode_ticks += 1;
+
+        if let Habitat::Air { .. } = species.habitat {
+            return self.change_flight_mode(height_map, tree_plants, animals, environment, rand);
+        }
+
+        if self.target.is_some() {
+            self.walk();
+            return report;
+        }
+
+        let possible_position = self.get_neighbors();
+        let valid_positions =
+            self.get_valid_position(&possible_position, height_map, tree_plants, animals);
+
+        let senses = self.sense(
+            height_map,
+            tree_plants,
+            animals,
+            environment,
+            valid_positions.is_empty(),
+        );
+        if let Some((mode, reason)) = self.next_mode(&senses) {
+            report.mode_change = Some(self.set_mode(mode, reason, &senses));
+        }
+
+        let destination = match self.entity_mode {
+            EntityMode::Idle | EntityMode::Eating | EntityMode::Sleeping => return report,
+            EntityMode::Drinking => {
+                self.drink(DRINK_AMOUNT);
+                report.drank = true;
+                return report;
+            }
+            EntityMode::Seeking(Need::Food) => senses.food.map(|food| (food, true)),
+            EntityMode::Seeking(Need::Water) => senses.water.map(|water| (water, true)),
+            EntityMode::Seeking(Need::Shelter) => senses.shelter.map(|shelter| (shelter, true)),
+            EntityMode::Fleeing => senses.get_danger().map(|danger| (danger, false)),
+            EntityMode::Wandering => None,
+        };
+
+        let walkable_positions = possible_position
+            .iter()
+            .filter(|position| self.get_move_cost(**position, height_map).is_some())
+            .count();
+        report.collisions_avoided = walkable_positions - valid_positions.len();
+
+        if valid_positions.is_empty() {
+            report.failed = true;
+            return report;
+        }
+
+        let index = match destination {
+            Some((point, closer)) => self.choose_position(
+                &possible_position,
+                &valid_positions,
+                point,
+                closer,
+                height_map,
+            ),
+            None => {
+                let steering = match species.flocking {
+                    Some(flocking) => self.get_flocking_steering(animals, &flocking),
+                    None => [0_f32; 2],
+                };
+
+                if steering != [0_f32; 2] {
+                    self.choose_steering_position(&possible_position, &valid_positions, steering)
+                } else {
+                    rand.gen_range(0..valid_positions.len())
+                }
+            }
+        };
+
+        self.set_target(&possible_position, valid_positions[index], height_map);
+
+        report
+    }
+
+    /// Walk to the free neighbor cell closest to a point (used by the species scripts).
+    ///
+    /// # Arguments
+    /// * `point` - Point where the animal wants to go.
+    /// * `height_map` - Height map of the scene.
+    /// * `tree_plants` - Plants of the scene.
+    /// * `animals` - Animals of the scene.
+    pub fn steer_to<const W: usize, const H: usize>(
+        &mut self,
+        point: [f32; 3],
+        height_map: &[[f32; H]; W],
+        tree_plants: &TreeEntity,
+        animals: &Vec<Entity>,
     ) {
-        if self.entity_type == EntityType::Plant1 || self.entity_type == EntityType::Plant2 {
+        let possible_position = self.get_neighbors();
+        let valid_positions =
+            self.get_valid_position(&possible_position, height_map, tree_plants, animals);
+
+        if valid_positions.is_empty() {
             return;
         }
 
-        if self.entity_mode == EntityMode::Idle {
-            let current_x = self.position[0] as isize;
-            let current_z = self.position[2] as isize;
-            let possible_position = vec![
-                (current_x + 1, current_z),
-                (current_x + 1, current_z + 1),
-                (current_x, current_z + 1),
-                (current_x - 1, current_z + 1),
-                (current_x - 1, current_z),
-                (current_x - 1, current_z - 1),
-                (current_x, current_z - 1),
-                (current_x + 1, current_z - 1),
-            ];
-            let valid_positions =
-                Self::get_valid_position(&possible_position, height_map, tree_plants, animals);
-
-            if valid_positions.len() == 0 {
-                return;
-            }
+        let index = self.choose_position(
+            &possible_position,
+            &valid_positions,
+            point,
+            true,
+            height_map,
+        );
+        self.set_target(&possible_position, valid_positions[index], height_map);
+    }
 
-            let mut rand = rand::thread_rng();
-            let index = rand.gen_range(0..valid_positions.len());
+    /// Walk to a neighbor cell (facing it) and spend the energy to climb or descend to it.
+    ///
+    /// # Arguments
+    /// * `possible_position` - Neighbor cells.
+    /// * `position_index` - Index of the cell in `possible_position`.
+    /// * `height_map` - Height map of the scene.
+    fn set_target<const W: usize, const H: usize>(
+        &mut self,
+        possible_position: &[(isize, isize)],
+        position_index: usize,
+        height_map: &[[f32; H]; W],
+    ) {
+        let cell = possible_position[position_index];
+        let energy = self.energy;
+        if let Some(cost) = self.get_move_cost(cell, height_map) {
+            self.spend_energy(cost);
+        }
+        self.target_cost = energy - self.energy;
+
+        self.target = Some((cell.0 as f32, cell.1 as f32));
+        self.set_rotation(position_index as f32 * 45_f32);
+    }
+
+    pub fn get_mode(&self) -> EntityMode {
+        self.entity_mode
+    }
+
+    /// Cell where the animal is walking to.
+    pub fn get_target(&self) -> Option<(f32, f32)> {
+        self.target
+    }
+
+    /// Give up the walk chosen on this tick (used when another animal took the cell) and get
+    /// back the energy spent to climb or descend to it.
+    pub fn cancel_move(&mut self) {
+        self.feed(self.target_cost);
+        self.target = None;
+        self.target_cost = 0_f32;
+        self.rotation = self.last_rotation;
+    }
+
+    /// Look for the visible predators, food (when hungry), water (when thirsty) and shelter (when
+    /// it rains on a land animal), and for the fires inside the sight radius (the smoke is seen
+    /// over the hills, but not by the aquatic animals).
+    ///
+    /// # Arguments
+    /// * `height_map` - Height map of the scene.
+    /// * `tree_plants` - Plants of the scene.
+    /// * `animals` - Animals of the scene.
+    /// * `environment` - State of the world around the animal.
+    /// * `blocked` - If there is no free neighbor cell.
+    fn sense<const W: usize, const H: usize>(
+        &self,
+        height_map: &[[f32; H]; W],
+        tree_plants: &TreeEntity,
+        animals: &[Entity],
+        environment: &Environment,
+        blocked: bool,
+    ) -> Senses {
+        let entity_type = self.entity_type;
+        let sight_radius = self.get_sight_radius();
+        let raining_on_land = environment.raining && entity_type.species().habitat == Habitat::Land;
+
+        // The plants are only looked at when the animal wants food or shelter.
+        let plants = if self.is_hungry() || raining_on_land {
+            tree_plants.get_near(self.position, sight_radius)
+        } else {
+            vec![]
+        };
+        let entities = self.get_entities_in_range(&plants, animals);
 
-            let position_index = valid_positions[index];
-            let x = possible_position[position_index].0 as f32;
-            let z = possible_position[position_index].1 as f32;
+        let food = self.find_food(&entities, height_map);
 
-            self.entity_mode = EntityMode::Walking { target: (x, z) };
-            self.set_rotation(position_index as f32 * 45_f32);
+        let water = if self.is_thirsty() {
+            self.find_water(height_map)
         } else {
-            self.walk();
+            None
+        };
+
+        let shelter = if raining_on_land {
+            self.find_visible(&entities, height_map, |plant| {
+                plant.entity_type.species().shelter && plant.growth >= SHELTER_GROWTH
+            })
+        } else {
+            None
+        };
+
+        // The sleeping animals do not see the predators coming (only the smoke wakes them).
+        let predator = if self.entity_mode == EntityMode::Sleeping {
+            None
+        } else {
+            self.find_visible(&entities, height_map, |other| {
+                other.entity_type.hunts(entity_type)
+            })
+        };
+
+        let fire = match entity_type.species().habitat {
+            Habitat::Water { .. } => None,
+            _ => environment
+                .fires
+                .iter()
+                .copied()
+                .filter(|fire| self.distance(*fire) <= sight_radius)
+                .min_by(|a, b| self.distance(*a).total_cmp(&self.distance(*b))),
+        };
+
+        Senses {
+            predator,
+            fire,
+            food,
+            water,
+            shelter,
+            blocked,
+            night: environment.night,
         }
     }
 
+    /// Distance travelled (ignoring the height) since the last tick.
+    pub fn get_travelled_distance(&self) -> f32 {
+        self.distance(self.last_position)
+    }
+
+    pub fn get_energy(&self) -> f32 {
+        self.energy
+    }
+
+    pub fn get_hydration(&self) -> f32 {
+        self.hydration
+    }
+
+    pub fn get_stamina(&self) -> f32 {
+        self.stamina
+    }
+
+    /// Choose the index (in `valid_positions`) closest or farthest of a point, preferring the
+    /// cells that are cheaper to climb.
+    ///
+    /// # Arguments
+    /// * `possible_position` - Neighbor cells.
+    /// * `valid_positions` - Indexes of the walkable cells in `possible_position`.
+    /// * `point` - Point used to compare the distances.
+    /// * `closer` - If should get the closest (or the farthest) cell.
+    /// * `height_map` - Height map of the scene.
+    fn choose_position<const W: usize, const H: usize>(
+        &self,
+        possible_position: &[(isize, isize)],
+        valid_positions: &[usize],
+        point: [f32; 3],
+        closer: bool,
+        height_map: &[[f32; H]; W],
+    ) -> usize {
+        let score = |index: &usize| {
+            let (x, z) = possible_position[valid_positions[*index]];
+            let delta_x = x as f32 - point[0];
+            let delta_z = z as f32 - point[2];
+            let distance = (delta_x * delta_x + delta_z * delta_z).sqrt();
+            let cost = self.get_move_cost((x, z), height_map).unwrap_or(0_f32);
+
+            let distance = if closer { distance } else { -distance };
+            distance + cost * GENTLE_ROUTE_WEIGHT
+        };
+
+        (0..valid_positions.len())
+            .min_by(|a, b| score(a).total_cmp(&score(b)))
+            .unwrap_or(0)
+    }
+
     pub fn walk(&mut self) {
-        if self.entity_type == EntityType::Plant1
-            || self.entity_type == EntityType::Plant2
-            || self.entity_mode == EntityMode::Idle
-        {
+        if self.entity_type.is_plant() {
             return;
         }
 
-        let (target_x, target_z) = match self.entity_mode {
-            EntityMode::Walking { target } => target,
-            _ => return,
+        let (target_x, target_z) = match self.target {
+            Some(target) => target,
+            None => return,
         };
+        // The walk was kept, so its cost is not given back anymore.
+        self.target_cost = 0_f32;
 
         let delta_x = target_x - self.position[0];
         let delta_z = target_z - self.position[2];
@@ -129,32 +693,66 @@ impl Entity {
         };
 
         if self.position[0] == target_x && self.position[2] == target_z {
-            self.entity_mode = EntityMode::Idle;
+            self.target = None;
             return;
         }
 
-        self.position[0] += self.speed * signal_x;
-        self.position[2] += self.speed * signal_z;
+        // The steps stop on the target (the speed of each animal is different).
+        let speed = self.genome.speed;
+        self.position[0] += (speed * signal_x).clamp(-delta_x.abs(), delta_x.abs());
+        self.position[2] += (speed * signal_z).clamp(-delta_z.abs(), delta_z.abs());
+    }
+
+    /// Get the eight neighbor cells of the entity (the order follows the rotation angles).
+    pub fn get_neighbors(&self) -> Vec<(isize, isize)> {
+        let current_x = self.position[0] as isize;
+        let current_z = self.position[2] as isize;
+
+        vec![
+            (current_x + 1, current_z),
+            (current_x + 1, current_z + 1),
+            (current_x, current_z + 1),
+            (current_x - 1, current_z + 1),
+            (current_x - 1, current_z),
+            (current_x - 1, current_z - 1),
+            (current_x, current_z - 1),
+            (current_x + 1, current_z - 1),
+        ]
+    }
+
+    /// Get the neighbor cells where an animal can stay.
+    ///
+    /// # Arguments
+    /// * `height_map` - Height map of the scene.
+    /// * `tree_plants` - Plants of the scene.
+    /// * `animals` - Animals of the scene.
+    pub fn get_free_neighbors<const W: usize, const H: usize>(
+        &self,
+        height_map: &[[f32; H]; W],
+        tree_plants: &TreeEntity,
+        animals: &Vec<Entity>,
+    ) -> Vec<(isize, isize)> {
+        let possible_position = self.get_neighbors();
+
+        self.get_valid_position(&possible_position, height_map, tree_plants, animals)
+            .into_iter()
+            .map(|index| possible_position[index])
+            .collect()
     }
 
     fn get_valid_position<const W: usize, const H: usize>(
+        &self,
         possible_position: &Vec<(isize, isize)>,
         height_map: &[[f32; H]; W],
         tree_plants: &TreeEntity,
         animals: &Vec<Entity>,
     ) -> Vec<usize> {
-        const VALID_HEIGHT: f32 = 1_f32;
-
         let mut valid_positions = Vec::<usize>::new();
         for i in 0..possible_position.len() {
             let (x, z) = possible_position[i];
-            let valid_x = x >= 0 && x < W as isize;
-            let valid_z = z >= 0 && z < H as isize;
 
             let pos = [x as f32, 0_f32, z as f32];
-            if valid_x
-                && valid_z
-                && height_map[x as usize][z as usize] >= VALID_HEIGHT
+            if self.get_move_cost((x, z), height_map).is_some()
                 && !tree_plants.collide(pos)
                 && !Self::have_another_animal(pos, animals)
             {
@@ -165,8 +763,52 @@ impl Entity {
         valid_positions
     }
 
+    /// Energy spent to walk to a cell, or `None` when the animal can not walk there (outside
+    /// the map or its habitat, or climbing a slope steeper than its species can).
+    ///
+    /// # Arguments
+    /// * `(x, z)` - The cell.
+    /// * `height_map` - Height map of the scene.
+    pub fn get_move_cost<const W: usize, const H: usize>(
+        &self,
+        (x, z): (isize, isize),
+        height_map: &[[f32; H]; W],
+    ) -> Option<f32> {
+        let species = self.entity_type.species();
+        let height =
+            get_height((x, z), height_map).filter(|height| species.habitat.contains(*height))?;
+
+        match species.habitat {
+            // The flying animals do not walk on the cells.
+            Habitat::Air { .. } => return None,
+            // The water does not have slopes.
+            Habitat::Water { .. } => return Some(0_f32),
+            Habitat::Land => (),
+        }
+
+        let current_x = self.position[0] as isize;
+        let current_z = self.position[2] as isize;
+        let current_height = get_height((current_x, current_z), height_map).unwrap_or(height);
+
+        let delta_x = (x - current_x) as f32;
+        let delta_z = (z - current_z) as f32;
+        let run = (delta_x * delta_x + delta_z * delta_z).sqrt().max(1_f32);
+        let rise = height - current_height;
+
+        let terrain = species.terrain;
+        if rise / run > terrain.max_slope {
+            return None;
+        }
+
+        if rise > 0_f32 {
+            Some(rise * terrain.climb_cost)
+        } else {
+            Some(-rise * terrain.descent_cost)
+        }
+    }
+
     fn have_another_animal(position: [f32; 3], animals: &Vec<Entity>) -> bool {
-        for animal in animals.iter() {
+        for animal in animals.iter().filter(|animal| !animal.is_flying()) {
             let [animal_x, _, animal_z] = animal.position;
 
             if animal_x == position[0] && animal_z == position[2] {
@@ -177,3 +819,22 @@ impl Entity {
         false
     }
 }
+
+/// Height of a cell (if it is inside the map).
+///
+/// # Arguments
+/// * `(x, z)` - The cell.
+/// * `height_map` - Height map of the scene.
+fn get_height<const W: usize, const H: usize>(
+    (x, z): (isize, isize),
+    height_map: &[[f32; H]; W],
+) -> Option<f32> {
+    let valid_x = x >= 0 && x < W as isize;
+    let valid_z = z >= 0 && z < H as isize;
+
+    if valid_x && valid_z {
+        Some(height_map[x as usize][z as usize])
+    } else {
+        None
+    }
+}
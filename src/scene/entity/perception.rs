@@ -0,0 +1,240 @@
+use super::tree_entity::TreeEntity;
+use super::{get_height, Entity, WATER_LEVEL};
+
+/// Height of the eyes above the animal position.
+const EYE_HEIGHT: f32 = 0.5_f32;
+/// Height above the ground (or the water) of the point seen on the entities and the water.
+const TARGET_HEIGHT: f32 = 0.25_f32;
+/// Distance (in cells) between the samples of the terrain on a line of sight.
+const RAY_STEP: f32 = 0.5_f32;
+
+/// What an animal sees: the entities and the water inside its view cone (up to its view
+/// distance) that are not hidden by the terrain.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Perception {
+    /// Visible animals and plants (the nearest first).
+    pub entities: Vec<Entity>,
+    /// Nearest visible cell with water.
+    pub water: Option<[f32; 3]>,
+    /// Nearest visible food (the animals that it hunts first, and then the edible plants), only
+    /// when the animal is hungry.
+    pub food: Option<[f32; 3]>,
+}
+
+impl Entity {
+    /// Look around for the visible entities, water and food.
+    ///
+    /// # Arguments
+    /// * `height_map` - Height map of the scene.
+    /// * `tree_plants` - Plants of the scene.
+    /// * `animals` - Animals of the scene.
+    pub fn perceive<const W: usize, const H: usize>(
+        &self,
+        height_map: &[[f32; H]; W],
+        tree_plants: &TreeEntity,
+        animals: &[Entity],
+    ) -> Perception {
+        let plants = tree_plants.get_near(self.position, self.get_sight_radius());
+        let entities = self.get_entities_in_range(&plants, animals);
+
+        let mut visible: Vec<Entity> = entities
+            .iter()
+            .filter(|other| self.can_see(other.position, height_map))
+            .copied()
+            .collect();
+        self.sort_by_distance(&mut visible);
+
+        Perception {
+            food: self.find_food(&entities, height_map),
+            water: self.find_water(height_map),
+            entities: visible,
+        }
+    }
+
+    /// Get the animals and plants inside the view distance, hidden or not.
+    ///
+    /// # Arguments
+    /// * `plants` - Plants near the animal.
+    /// * `animals` - Animals of the scene.
+    pub(super) fn get_entities_in_range(
+        &self,
+        plants: &[Entity],
+        animals: &[Entity],
+    ) -> Vec<Entity> {
+        let sight_radius = self.get_sight_radius();
+
+        animals
+            .iter()
+            .filter(|animal| animal.position != self.position)
+            .chain(plants.iter())
+            .filter(|other| self.distance(other.position) <= sight_radius)
+            .copied()
+            .collect()
+    }
+
+    /// Sort entities by their distance to the animal (the nearest first).
+    ///
+    /// # Arguments
+    /// * `entities` - The entities.
+    fn sort_by_distance(&self, entities: &mut [Entity]) {
+        entities.sort_by(|a, b| {
+            self.distance(a.position)
+                .total_cmp(&self.distance(b.position))
+        });
+    }
+
+    /// Find the nearest visible entity that matches a filter (the line of sight is only checked
+    /// for the entities that match).
+    ///
+    /// # Arguments
+    /// * `entities` - Entities inside the view distance.
+    /// * `height_map` - Height map of the scene.
+    /// * `filter` - Returns if the entity is wanted.
+    pub(super) fn find_visible<const W: usize, const H: usize>(
+        &self,
+        entities: &[Entity],
+        height_map: &[[f32; H]; W],
+        filter: impl Fn(&Entity) -> bool,
+    ) -> Option<[f32; 3]> {
+        let mut wanted: Vec<Entity> = entities
+            .iter()
+            .filter(|other| filter(other))
+            .copied()
+            .collect();
+        self.sort_by_distance(&mut wanted);
+
+        wanted
+            .into_iter()
+            .find(|other| self.can_see(other.position, height_map))
+            .map(|other| other.position)
+    }
+
+    /// Find the nearest visible food (the animals that it hunts first, and then the edible
+    /// plants). The animals that are not hungry do not look for food.
+    ///
+    /// # Arguments
+    /// * `entities` - Entities inside the view distance.
+    /// * `height_map` - Height map of the scene.
+    pub(super) fn find_food<const W: usize, const H: usize>(
+        &self,
+        entities: &[Entity],
+        height_map: &[[f32; H]; W],
+    ) -> Option<[f32; 3]> {
+        if !self.is_hungry() {
+            return None;
+        }
+
+        let hunts = |other: &Entity| self.entity_type.hunts(other.entity_type);
+
+        self.find_visible(entities, height_map, |other| {
+            !other.entity_type.is_plant() && hunts(other)
+        })
+        .or_else(|| {
+            self.find_visible(entities, height_map, |other| {
+                other.entity_type.is_plant() && hunts(other) && other.is_edible()
+            })
+        })
+    }
+
+    /// Indicate if a point is inside the view distance and the view cone of the animal, and
+    /// it is not hidden by the terrain.
+    ///
+    /// # Arguments
+    /// * `point` - The point.
+    /// * `height_map` - Height map of the scene.
+    pub fn can_see<const W: usize, const H: usize>(
+        &self,
+        point: [f32; 3],
+        height_map: &[[f32; H]; W],
+    ) -> bool {
+        self.distance(point) <= self.get_sight_radius()
+            && self.is_in_view_cone(point)
+            && self.has_line_of_sight(point, height_map)
+    }
+
+    /// Indicate if a point is inside the view cone (centred where the animal faces).
+    ///
+    /// # Arguments
+    /// * `point` - The point.
+    fn is_in_view_cone(&self, point: [f32; 3]) -> bool {
+        let view_angle = self.entity_type.species().view_angle;
+        if view_angle >= 360_f32 || self.distance(point) == 0_f32 {
+            return true;
+        }
+
+        let delta_x = point[0] - self.position[0];
+        let delta_z = point[2] - self.position[2];
+        let direction = delta_z.atan2(delta_x).to_degrees();
+        let turn = (direction - self.rotation + 540_f32).rem_euclid(360_f32) - 180_f32;
+
+        turn.abs() <= view_angle / 2_f32
+    }
+
+    /// March along the line between the eyes and a point, checking if the terrain is higher
+    /// than the line on any sample.
+    ///
+    /// # Arguments
+    /// * `point` - The point.
+    /// * `height_map` - Height map of the scene.
+    // Without `is_none_or`, which needs a newer Rust.
+    #[allow(clippy::unnecessary_map_or)]
+    fn has_line_of_sight<const W: usize, const H: usize>(
+        &self,
+        point: [f32; 3],
+        height_map: &[[f32; H]; W],
+    ) -> bool {
+        let eye = get_surface(self.position, height_map) + EYE_HEIGHT;
+        let target = get_surface(point, height_map) + TARGET_HEIGHT;
+        let distance = self.distance(point);
+        let steps = (distance / RAY_STEP).ceil() as usize;
+
+        (1..steps).all(|step| {
+            let ratio = step as f32 * RAY_STEP / distance;
+            let x = self.position[0] + (point[0] - self.position[0]) * ratio;
+            let z = self.position[2] + (point[2] - self.position[2]) * ratio;
+            let cell = (x.round() as isize, z.round() as isize);
+
+            get_height(cell, height_map)
+                .map_or(true, |ground| ground <= eye + (target - eye) * ratio)
+        })
+    }
+
+    /// Find the nearest visible cell with water.
+    ///
+    /// # Arguments
+    /// * `height_map` - Height map of the scene.
+    pub(super) fn find_water<const W: usize, const H: usize>(
+        &self,
+        height_map: &[[f32; H]; W],
+    ) -> Option<[f32; 3]> {
+        let sight_radius = self.get_sight_radius();
+        let radius = sight_radius as isize;
+        let current_x = self.position[0].round() as isize;
+        let current_z = self.position[2].round() as isize;
+
+        let mut cells = vec![];
+        for x in (current_x - radius)..=(current_x + radius) {
+            for z in (current_z - radius)..=(current_z + radius) {
+                if get_height((x, z), height_map).is_some_and(|height| height < WATER_LEVEL) {
+                    cells.push([x as f32, WATER_LEVEL, z as f32]);
+                }
+            }
+        }
+        cells.sort_by(|a, b| self.distance(*a).total_cmp(&self.distance(*b)));
+
+        cells
+            .into_iter()
+            .find(|cell| self.can_see(*cell, height_map))
+    }
+}
+
+/// Height of the ground under a point (or of the point, when it is above the ground).
+///
+/// # Arguments
+/// * `point` - The point.
+/// * `height_map` - Height map of the scene.
+fn get_surface<const W: usize, const H: usize>(point: [f32; 3], height_map: &[[f32; H]; W]) -> f32 {
+    let cell = (point[0].round() as isize, point[2].round() as isize);
+
+    get_height(cell, height_map).map_or(point[1], |ground| ground.max(point[1]))
+}
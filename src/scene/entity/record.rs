@@ -0,0 +1,134 @@
+use super::flight::Flight;
+use super::genome::{Genome, TRAIT_COUNT};
+use super::{Entity, EntityMode, EntityType};
+
+/// Placeholder used in a record when the entity has no target (or flight).
+const NO_TARGET: &str = "-";
+/// Amount of fields of a record.
+const RECORD_FIELDS: usize = 33;
+
+impl Entity {
+    /// Write the whole state of the entity in a line (fields separated by spaces).
+    pub fn get_record(&self) -> String {
+        let target = match self.target {
+            Some((x, z)) => format!("{x} {z}"),
+            None => format!("{NO_TARGET} {NO_TARGET}"),
+        };
+        // The speed is written with the other fields of the movement.
+        let traits = self.genome.get_traits()[1..]
+            .iter()
+            .map(|value| value.to_string())
+            .collect::<Vec<String>>()
+            .join(" ");
+        let flight = match self.flight {
+            Some(Flight { from, to, progress }) => format!(
+                "{} {} {} {} {} {} {progress}",
+                from[0], from[1], from[2], to[0], to[1], to[2]
+            ),
+            None => [NO_TARGET; 7].join(" "),
+        };
+
+        format!(
+            "{} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {target} {flight} {} {traits}",
+            self.entity_type.get_name(),
+            self.position[0],
+            self.position[1],
+            self.position[2],
+            self.rotation,
+            self.last_position[0],
+            self.last_position[1],
+            self.last_position[2],
+            self.last_rotation,
+            self.genome.speed,
+            self.energy,
+            self.hydration,
+            self.stamina,
+            self.growth,
+            self.age,
+            self.entity_mode.get_name(),
+            self.mode_ticks,
+            self.id,
+        )
+    }
+
+    /// Read an entity written by `get_record`.
+    ///
+    /// # Arguments
+    /// * `record` - The line with the entity fields.
+    pub fn from_record(record: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = record.split_whitespace().collect();
+        if fields.len() != RECORD_FIELDS {
+            return Err(format!("Invalid entity record: {record}"));
+        }
+
+        let entity_type = EntityType::from_name(fields[0])
+            .ok_or(format!("Unknown entity type: {}", fields[0]))?;
+        let entity_mode =
+            EntityMode::from_name(fields[15]).ok_or(format!("Unknown mode: {}", fields[15]))?;
+
+        let target = match (fields[17], fields[18]) {
+            (NO_TARGET, NO_TARGET) => None,
+            (x, z) => Some((parse_field(x)?, parse_field(z)?)),
+        };
+
+        let flight = if fields[19..26].iter().all(|field| *field == NO_TARGET) {
+            None
+        } else {
+            let values = fields[19..26]
+                .iter()
+                .map(|field| parse_field(field))
+                .collect::<Result<Vec<f32>, String>>()?;
+
+            Some(Flight {
+                from: [values[0], values[1], values[2]],
+                to: [values[3], values[4], values[5]],
+                progress: values[6],
+            })
+        };
+
+        let mut traits = [0_f32; TRAIT_COUNT];
+        traits[0] = parse_field(fields[9])?;
+        for (value, field) in traits[1..].iter_mut().zip(fields[27..].iter()) {
+            *value = parse_field(field)?;
+        }
+
+        Ok(Self {
+            id: parse_field(fields[26])?,
+            position: [
+                parse_field(fields[1])?,
+                parse_field(fields[2])?,
+                parse_field(fields[3])?,
+            ],
+            rotation: parse_field(fields[4])?,
+            last_position: [
+                parse_field(fields[5])?,
+                parse_field(fields[6])?,
+                parse_field(fields[7])?,
+            ],
+            last_rotation: parse_field(fields[8])?,
+            genome: Genome::from_traits(traits),
+            energy: parse_field(fields[10])?,
+            hydration: parse_field(fields[11])?,
+            stamina: parse_field(fields[12])?,
+            growth: parse_field(fields[13])?,
+            age: parse_field(fields[14])?,
+            entity_type,
+            entity_mode,
+            mode_ticks: parse_field(fields[16])?,
+            target,
+            // The cost is only given back on the tick the walk is chosen.
+            target_cost: 0_f32,
+            flight,
+        })
+    }
+}
+
+/// Parse a field of a record.
+///
+/// # Arguments
+/// * `field` - The text of the field.
+pub fn parse_field<T: std::str::FromStr>(field: &str) -> Result<T, String> {
+    field
+        .parse()
+        .map_err(|_| format!("Invalid value in the record: {field}"))
+}
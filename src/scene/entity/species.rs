@@ -0,0 +1,677 @@
+use super::behaviour::{Condition, Transition};
+use super::{EntityMode, EntityType, Need, WATER_LEVEL};
+use crate::scene::season::Season;
+
+/// Boids steering weights for social species.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Flocking {
+    /// Flockmates closer than this distance push the entity away (up to the catch distance, or
+    /// the flockmates would never get close enough to mate).
+    pub separation_distance: f32,
+    pub separation_weight: f32,
+    pub alignment_weight: f32,
+    pub cohesion_weight: f32,
+    /// Weight to follow the herd leader (the oldest flockmate), zero disables the leader.
+    pub leader_weight: f32,
+}
+
+/// How the terrain slope limits the walk of a species.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Terrain {
+    /// Max height climbed per cell walked (steeper climbs are cliffs).
+    pub max_slope: f32,
+    /// Energy spent per height unit climbed.
+    pub climb_cost: f32,
+    /// Energy spent per height unit descended.
+    pub descent_cost: f32,
+}
+
+/// Cells where a species lives.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Habitat {
+    /// Cells above the water (the slopes are limited by the species `Terrain`).
+    Land,
+    /// Cells under the water, with a depth (below the water level) inside a range.
+    Water {
+        min_depth: f32,
+        max_depth: f32,
+        /// Depth where the entities swim (it is never deeper than the ground).
+        swim_depth: f32,
+    },
+    /// Flying over any cell and resting on the plants.
+    Air {
+        /// Height above the highest ground under a flight.
+        altitude: f32,
+        /// Height above the ground of a perched animal.
+        perch_height: f32,
+    },
+}
+
+impl Habitat {
+    /// Indicate if a cell is part of the habitat.
+    ///
+    /// # Arguments
+    /// * `height` - Height of the cell.
+    pub fn contains(&self, height: f32) -> bool {
+        match self {
+            Habitat::Land => height >= WATER_LEVEL,
+            Habitat::Water {
+                min_depth,
+                max_depth,
+                ..
+            } => {
+                let depth = WATER_LEVEL - height;
+                depth >= *min_depth && depth <= *max_depth
+            }
+            Habitat::Air { .. } => true,
+        }
+    }
+
+    /// Height where the entities stay on a cell.
+    ///
+    /// # Arguments
+    /// * `ground` - Height of the cell.
+    pub fn get_height(&self, ground: f32) -> f32 {
+        match self {
+            Habitat::Land => ground,
+            Habitat::Water { swim_depth, .. } => (WATER_LEVEL - swim_depth).max(ground),
+            Habitat::Air { perch_height, .. } => ground + perch_height,
+        }
+    }
+}
+
+/// Part of the day when a species is awake.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Activity {
+    Diurnal,
+    Nocturnal,
+    /// Awake at any time (it only sleeps when tired).
+    Always,
+}
+
+impl Activity {
+    /// Indicate if the species is awake at a time of the day.
+    ///
+    /// # Arguments
+    /// * `night` - If it is night.
+    pub fn is_active(&self, night: bool) -> bool {
+        match self {
+            Activity::Diurnal => !night,
+            Activity::Nocturnal => night,
+            Activity::Always => true,
+        }
+    }
+}
+
+/// Rules used to scatter the plants of a species on the island.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Vegetation {
+    /// Min distance (in cells) between two plants of the species.
+    pub min_spacing: f32,
+    /// Chance (between 0 and 1) to use each point of the Poisson-disk sampling.
+    pub density: f32,
+    /// Max height difference to the neighbor cells.
+    pub max_slope: f32,
+    /// Min and max height of the cells.
+    pub elevation: (f32, f32),
+    /// Max distance (in cells) from the water.
+    pub max_water_distance: usize,
+    /// Chance (between 0 and 1) to skip a point without other plants of the species
+    /// nearby, higher values create denser groves.
+    pub clustering: f32,
+}
+
+/// Behaviour parameters shared by all the entities of the same type.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Species {
+    /// Entity types that this species eats.
+    pub diet: &'static [EntityType],
+    /// Max distance (in cells) where the species can see other entities.
+    pub sight_radius: f32,
+    /// Width (in degrees) of the view cone, centred where the animal faces.
+    pub view_angle: f32,
+    /// Energy of a well fed entity.
+    pub max_energy: f32,
+    /// Energy spent on each simulation tick.
+    pub energy_cost: f32,
+    /// Energy gained on each tick by filtering the plankton of the water (it does not need
+    /// to hunt).
+    pub plankton: f32,
+    /// Energy given to who eats an entity of this species.
+    pub nutrition: f32,
+    /// Growth gained on each tick (in a cell with full moisture).
+    pub growth_rate: f32,
+    /// Chance to spread a seed on each tick (only for grown plants).
+    pub spread_rate: f32,
+    /// Max distance (in cells) where the seeds can fall.
+    pub spread_radius: f32,
+    /// Max amount of plants inside the spread radius (in a cell with full moisture), or of
+    /// animals of the species inside the sight radius of an animal that mates.
+    pub max_density: f32,
+    /// Age (in ticks) when the entity becomes an adult.
+    pub maturity_age: u32,
+    /// Age (in ticks) when the entity dies.
+    pub max_age: u32,
+    /// Min energy to mate.
+    pub mating_energy: f32,
+    /// Energy spent by each parent to create an offspring.
+    pub mating_cost: f32,
+    /// Steering used to move in herds (when there is nothing else to do).
+    pub flocking: Option<Flocking>,
+    /// Hydration of an animal that just drank.
+    pub max_hydration: f32,
+    /// Hydration lost on each simulation tick.
+    pub thirst_cost: f32,
+    /// Stamina (between 0 and 1) lost on each tick awake.
+    pub fatigue_rate: f32,
+    /// Stamina recovered on each tick sleeping.
+    pub rest_rate: f32,
+    /// Part of the day when the species is awake (it sleeps on the rest of the day).
+    pub activity: Activity,
+    /// If the land animals hide under the plants of the species when it rains.
+    pub shelter: bool,
+    /// How easily (between 0 and 1) the plants catch fire from the burning plants nearby.
+    pub flammability: f32,
+    /// Seasons when the animals mate.
+    pub breeding_seasons: &'static [Season],
+    /// Mode transitions (the first one that matches is used).
+    pub behaviour: &'static [Transition],
+    /// Path of a script run on each behaviour tick.
+    pub script: Option<&'static str>,
+    /// Where the plants are scattered (only for plants).
+    pub vegetation: Option<Vegetation>,
+    /// Slopes where the animals can walk.
+    pub terrain: Terrain,
+    /// Cells where the species lives (the animals never leave it).
+    pub habitat: Habitat,
+}
+
+/// Terrain of the species that do not walk.
+const NO_TERRAIN: Terrain = Terrain {
+    max_slope: 0_f32,
+    climb_cost: 0_f32,
+    descent_cost: 0_f32,
+};
+
+const ANIMAL_BEHAVIOUR: &[Transition] = &[
+    Transition {
+        from: &EntityMode::ALL,
+        to: EntityMode::Fleeing,
+        condition: Condition::PredatorNear,
+    },
+    Transition {
+        from: &EntityMode::ALL,
+        to: EntityMode::Fleeing,
+        condition: Condition::FireNear,
+    },
+    Transition {
+        from: &[EntityMode::Fleeing],
+        to: EntityMode::Wandering,
+        condition: Condition::PredatorGone,
+    },
+    Transition {
+        from: &[EntityMode::Sleeping],
+        to: EntityMode::Wandering,
+        condition: Condition::Rested,
+    },
+    Transition {
+        from: &[EntityMode::Drinking],
+        to: EntityMode::Wandering,
+        condition: Condition::Quenched,
+    },
+    Transition {
+        from: &[
+            EntityMode::Idle,
+            EntityMode::Wandering,
+            EntityMode::Seeking(Need::Food),
+            EntityMode::Seeking(Need::Water),
+            EntityMode::Seeking(Need::Shelter),
+            EntityMode::Eating,
+        ],
+        to: EntityMode::Eating,
+        condition: Condition::FoodReached,
+    },
+    Transition {
+        from: &[
+            EntityMode::Idle,
+            EntityMode::Wandering,
+            EntityMode::Seeking(Need::Food),
+            EntityMode::Seeking(Need::Water),
+            EntityMode::Seeking(Need::Shelter),
+        ],
+        to: EntityMode::Drinking,
+        condition: Condition::WaterReached,
+    },
+    Transition {
+        from: &[EntityMode::Eating],
+        to: EntityMode::Wandering,
+        condition: Condition::Fed,
+    },
+    // Before the food: the thirsty animals drink first, or the hungry ones would chase the
+    // plants until they die of thirst.
+    Transition {
+        from: &[
+            EntityMode::Idle,
+            EntityMode::Wandering,
+            EntityMode::Seeking(Need::Food),
+            EntityMode::Seeking(Need::Water),
+            EntityMode::Seeking(Need::Shelter),
+        ],
+        to: EntityMode::Seeking(Need::Water),
+        condition: Condition::WaterSeen,
+    },
+    Transition {
+        from: &[
+            EntityMode::Idle,
+            EntityMode::Wandering,
+            EntityMode::Seeking(Need::Food),
+            EntityMode::Seeking(Need::Water),
+            EntityMode::Seeking(Need::Shelter),
+            EntityMode::Eating,
+        ],
+        to: EntityMode::Seeking(Need::Food),
+        condition: Condition::FoodSeen,
+    },
+    Transition {
+        from: &[EntityMode::Seeking(Need::Food)],
+        to: EntityMode::Wandering,
+        condition: Condition::Fed,
+    },
+    Transition {
+        from: &[EntityMode::Seeking(Need::Food), EntityMode::Eating],
+        to: EntityMode::Wandering,
+        condition: Condition::FoodLost,
+    },
+    Transition {
+        from: &[EntityMode::Seeking(Need::Water)],
+        to: EntityMode::Wandering,
+        condition: Condition::WaterLost,
+    },
+    Transition {
+        from: &[EntityMode::Seeking(Need::Water)],
+        to: EntityMode::Wandering,
+        condition: Condition::Quenched,
+    },
+    Transition {
+        from: &[EntityMode::Wandering, EntityMode::Seeking(Need::Shelter)],
+        to: EntityMode::Idle,
+        condition: Condition::Sheltered,
+    },
+    Transition {
+        from: &[
+            EntityMode::Idle,
+            EntityMode::Wandering,
+            EntityMode::Seeking(Need::Shelter),
+        ],
+        to: EntityMode::Seeking(Need::Shelter),
+        condition: Condition::ShelterSeen,
+    },
+    Transition {
+        from: &[EntityMode::Seeking(Need::Shelter)],
+        to: EntityMode::Wandering,
+        condition: Condition::ShelterLost,
+    },
+    Transition {
+        from: &[EntityMode::Wandering],
+        to: EntityMode::Sleeping,
+        condition: Condition::Tired,
+    },
+    Transition {
+        from: &[EntityMode::Idle, EntityMode::Wandering],
+        to: EntityMode::Sleeping,
+        condition: Condition::RestTime,
+    },
+    // Hold on purpose: the sheltered animals stay idle while it rains, because this transition
+    // matches before the `TimeInMode` one below that would send them wandering.
+    Transition {
+        from: &[EntityMode::Idle],
+        to: EntityMode::Idle,
+        condition: Condition::Sheltered,
+    },
+    Transition {
+        from: &[EntityMode::Wandering],
+        to: EntityMode::Idle,
+        condition: Condition::Blocked,
+    },
+    Transition {
+        from: &[EntityMode::Idle],
+        to: EntityMode::Wandering,
+        condition: Condition::TimeInMode(1),
+    },
+];
+
+const ANIMAL1: Species = Species {
+    diet: &[EntityType::Animal2],
+    sight_radius: 8_f32,
+    view_angle: 180_f32,
+    max_energy: 100_f32,
+    energy_cost: 0.05_f32,
+    plankton: 0_f32,
+    nutrition: 60_f32,
+    growth_rate: 0_f32,
+    spread_rate: 0_f32,
+    spread_radius: 0_f32,
+    max_density: 4_f32,
+    maturity_age: 600,
+    max_age: 9000,
+    mating_energy: 70_f32,
+    mating_cost: 30_f32,
+    flocking: Some(Flocking {
+        separation_distance: 1.5_f32,
+        separation_weight: 1.5_f32,
+        alignment_weight: 0.5_f32,
+        cohesion_weight: 1_f32,
+        leader_weight: 1_f32,
+    }),
+    max_hydration: 100_f32,
+    thirst_cost: 0.06_f32,
+    fatigue_rate: 0.005_f32,
+    rest_rate: 0.05_f32,
+    activity: Activity::Nocturnal,
+    shelter: false,
+    flammability: 0_f32,
+    breeding_seasons: &[Season::Spring, Season::Summer],
+    behaviour: ANIMAL_BEHAVIOUR,
+    script: None,
+    vegetation: None,
+    terrain: Terrain {
+        max_slope: 0.5_f32,
+        climb_cost: 2_f32,
+        descent_cost: 0.5_f32,
+    },
+    habitat: Habitat::Land,
+};
+
+const ANIMAL2: Species = Species {
+    diet: &[EntityType::Plant1, EntityType::Plant2],
+    sight_radius: 5_f32,
+    view_angle: 360_f32,
+    max_energy: 100_f32,
+    energy_cost: 0.2_f32,
+    plankton: 0_f32,
+    nutrition: 80_f32,
+    growth_rate: 0_f32,
+    spread_rate: 0_f32,
+    spread_radius: 0_f32,
+    max_density: 6_f32,
+    maturity_age: 400,
+    max_age: 7000,
+    mating_energy: 60_f32,
+    mating_cost: 40_f32,
+    flocking: Some(Flocking {
+        separation_distance: 1.5_f32,
+        separation_weight: 1.5_f32,
+        alignment_weight: 0.5_f32,
+        cohesion_weight: 1_f32,
+        leader_weight: 1_f32,
+    }),
+    max_hydration: 100_f32,
+    thirst_cost: 0.05_f32,
+    fatigue_rate: 0.004_f32,
+    rest_rate: 0.05_f32,
+    activity: Activity::Diurnal,
+    shelter: false,
+    flammability: 0_f32,
+    breeding_seasons: &[Season::Spring, Season::Summer],
+    behaviour: ANIMAL_BEHAVIOUR,
+    script: None,
+    vegetation: None,
+    terrain: Terrain {
+        max_slope: 0.35_f32,
+        climb_cost: 1.5_f32,
+        descent_cost: 0.3_f32,
+    },
+    habitat: Habitat::Land,
+};
+
+const PLANT1: Species = Species {
+    diet: &[],
+    sight_radius: 0_f32,
+    view_angle: 0_f32,
+    max_energy: 0_f32,
+    energy_cost: 0_f32,
+    plankton: 0_f32,
+    nutrition: 30_f32,
+    growth_rate: 0.02_f32,
+    spread_rate: 0.02_f32,
+    spread_radius: 3_f32,
+    max_density: 6_f32,
+    maturity_age: 0,
+    max_age: 0,
+    mating_energy: 0_f32,
+    mating_cost: 0_f32,
+    flocking: None,
+    max_hydration: 0_f32,
+    thirst_cost: 0_f32,
+    fatigue_rate: 0_f32,
+    rest_rate: 0_f32,
+    activity: Activity::Always,
+    shelter: false,
+    flammability: 0.9_f32,
+    breeding_seasons: &[],
+    behaviour: &[],
+    script: None,
+    vegetation: Some(Vegetation {
+        min_spacing: 5_f32,
+        density: 0.3_f32,
+        max_slope: 0.5_f32,
+        elevation: (1_f32, f32::MAX),
+        max_water_distance: 12,
+        clustering: 0.6_f32,
+    }),
+    terrain: NO_TERRAIN,
+    habitat: Habitat::Land,
+};
+
+const PLANT2: Species = Species {
+    diet: &[],
+    sight_radius: 0_f32,
+    view_angle: 0_f32,
+    max_energy: 0_f32,
+    energy_cost: 0_f32,
+    plankton: 0_f32,
+    nutrition: 50_f32,
+    growth_rate: 0.01_f32,
+    spread_rate: 0.01_f32,
+    spread_radius: 5_f32,
+    max_density: 10_f32,
+    maturity_age: 0,
+    max_age: 0,
+    mating_energy: 0_f32,
+    mating_cost: 0_f32,
+    flocking: None,
+    max_hydration: 0_f32,
+    thirst_cost: 0_f32,
+    fatigue_rate: 0_f32,
+    rest_rate: 0_f32,
+    activity: Activity::Always,
+    shelter: true,
+    flammability: 0.5_f32,
+    breeding_seasons: &[],
+    behaviour: &[],
+    script: None,
+    vegetation: Some(Vegetation {
+        min_spacing: 7_f32,
+        density: 0.25_f32,
+        max_slope: 1_f32,
+        elevation: (1_f32, f32::MAX),
+        max_water_distance: usize::MAX,
+        clustering: 0.3_f32,
+    }),
+    terrain: NO_TERRAIN,
+    habitat: Habitat::Land,
+};
+
+/// Fish live in schools on the lakes and the shallow water (they feed on plankton, so they
+/// do not hunt and gain back the energy that they spend).
+const FISH: Species = Species {
+    diet: &[],
+    sight_radius: 4_f32,
+    view_angle: 300_f32,
+    max_energy: 100_f32,
+    energy_cost: 0.1_f32,
+    plankton: 0.11_f32,
+    nutrition: 50_f32,
+    growth_rate: 0_f32,
+    spread_rate: 0_f32,
+    spread_radius: 0_f32,
+    max_density: 20_f32,
+    maturity_age: 500,
+    max_age: 6000,
+    mating_energy: 60_f32,
+    mating_cost: 30_f32,
+    flocking: Some(Flocking {
+        separation_distance: 1.5_f32,
+        separation_weight: 1_f32,
+        alignment_weight: 1_f32,
+        cohesion_weight: 1_f32,
+        leader_weight: 0_f32,
+    }),
+    max_hydration: 100_f32,
+    thirst_cost: 0_f32,
+    fatigue_rate: 0.003_f32,
+    rest_rate: 0.05_f32,
+    activity: Activity::Always,
+    shelter: false,
+    flammability: 0_f32,
+    breeding_seasons: &[Season::Spring, Season::Summer],
+    behaviour: ANIMAL_BEHAVIOUR,
+    script: None,
+    vegetation: None,
+    terrain: NO_TERRAIN,
+    habitat: Habitat::Water {
+        min_depth: 0.3_f32,
+        max_depth: 2.5_f32,
+        swim_depth: 0.3_f32,
+    },
+};
+
+/// Dolphins live offshore and hunt the fish that swim to the deep water.
+const DOLPHIN: Species = Species {
+    diet: &[EntityType::Fish],
+    sight_radius: 12_f32,
+    view_angle: 240_f32,
+    max_energy: 100_f32,
+    energy_cost: 0.025_f32,
+    plankton: 0_f32,
+    nutrition: 60_f32,
+    growth_rate: 0_f32,
+    spread_rate: 0_f32,
+    spread_radius: 0_f32,
+    max_density: 3_f32,
+    maturity_age: 1000,
+    max_age: 9000,
+    mating_energy: 70_f32,
+    mating_cost: 30_f32,
+    flocking: Some(Flocking {
+        separation_distance: 1.5_f32,
+        separation_weight: 1.5_f32,
+        alignment_weight: 0.5_f32,
+        cohesion_weight: 0.5_f32,
+        leader_weight: 1_f32,
+    }),
+    max_hydration: 100_f32,
+    thirst_cost: 0_f32,
+    fatigue_rate: 0.004_f32,
+    rest_rate: 0.05_f32,
+    activity: Activity::Always,
+    shelter: false,
+    flammability: 0_f32,
+    breeding_seasons: &Season::ALL,
+    behaviour: ANIMAL_BEHAVIOUR,
+    script: None,
+    vegetation: None,
+    terrain: NO_TERRAIN,
+    habitat: Habitat::Water {
+        min_depth: 1_f32,
+        max_depth: f32::MAX,
+        swim_depth: 0.5_f32,
+    },
+};
+
+/// Birds rest on the plants and fly between them (they eat the fruits of the `Plant1`).
+const BIRD: Species = Species {
+    diet: &[EntityType::Plant1],
+    sight_radius: 10_f32,
+    view_angle: 300_f32,
+    max_energy: 100_f32,
+    energy_cost: 0.15_f32,
+    plankton: 0_f32,
+    nutrition: 20_f32,
+    growth_rate: 0_f32,
+    spread_rate: 0_f32,
+    spread_radius: 0_f32,
+    max_density: 6_f32,
+    maturity_age: 600,
+    max_age: 8000,
+    mating_energy: 70_f32,
+    mating_cost: 40_f32,
+    flocking: None,
+    max_hydration: 100_f32,
+    thirst_cost: 0_f32,
+    fatigue_rate: 0.006_f32,
+    rest_rate: 0.05_f32,
+    activity: Activity::Diurnal,
+    shelter: false,
+    flammability: 0_f32,
+    breeding_seasons: &[Season::Spring],
+    behaviour: ANIMAL_BEHAVIOUR,
+    script: None,
+    vegetation: None,
+    terrain: NO_TERRAIN,
+    habitat: Habitat::Air {
+        altitude: 3_f32,
+        perch_height: 1.5_f32,
+    },
+};
+
+impl EntityType {
+    pub const ALL: [EntityType; 7] = [
+        EntityType::Animal1,
+        EntityType::Animal2,
+        EntityType::Plant1,
+        EntityType::Plant2,
+        EntityType::Fish,
+        EntityType::Dolphin,
+        EntityType::Bird,
+    ];
+
+    pub fn species(&self) -> &'static Species {
+        match self {
+            EntityType::Animal1 => &ANIMAL1,
+            EntityType::Animal2 => &ANIMAL2,
+            EntityType::Plant1 => &PLANT1,
+            EntityType::Plant2 => &PLANT2,
+            EntityType::Fish => &FISH,
+            EntityType::Dolphin => &DOLPHIN,
+            EntityType::Bird => &BIRD,
+        }
+    }
+
+    /// Name used on the command line, the scripts and the exported files.
+    pub fn get_name(&self) -> String {
+        format!("{self:?}").to_lowercase()
+    }
+
+    /// Find the entity type by its name.
+    ///
+    /// # Arguments
+    /// * `name` - Name of the type (case insensitive).
+    pub fn from_name(name: &str) -> Option<EntityType> {
+        Self::ALL
+            .into_iter()
+            .find(|entity_type| entity_type.get_name() == name.to_lowercase())
+    }
+
+    pub fn is_plant(&self) -> bool {
+        *self == EntityType::Plant1 || *self == EntityType::Plant2
+    }
+
+    /// Indicate if this species eats the `other` species.
+    ///
+    /// # Arguments
+    /// * `other` - The possible food.
+    pub fn hunts(&self, other: EntityType) -> bool {
+        self.species().diet.contains(&other)
+    }
+}
@@ -24,11 +24,11 @@ mod new {
     }
 
     #[test]
-    fn should_set_rotaion_randomly_if_is_a_plant() {
-        let plant1 = Entity::new([0_f32; 3], EntityType::Plant1);
-        let plant2 = Entity::new([0_f32; 3], EntityType::Plant2);
+    fn should_face_the_given_rotation() {
+        let plant = Entity::with_rotation([0_f32; 3], 45_f32, EntityType::Plant1);
 
-        assert_ne!(plant1.rotation, plant2.rotation);
+        assert_eq!(plant.rotation, 45_f32);
+        assert_eq!(plant.last_rotation, 45_f32);
     }
 }
 
@@ -83,13 +83,14 @@ mod get_valid_position {
         ];
 
         assert_eq!(
-            Entity::get_valid_position(
-                &valid_possible_position,
-                &height_map,
-                &tree_plants,
-                &animals
-            )
-            .len(),
+            Entity::new([0_f32; 3], EntityType::Animal1)
+                .get_valid_position(
+                    &valid_possible_position,
+                    &height_map,
+                    &tree_plants,
+                    &animals
+                )
+                .len(),
             valid_possible_position.len(),
         );
 
@@ -105,13 +106,14 @@ mod get_valid_position {
         ];
 
         assert_eq!(
-            Entity::get_valid_position(
-                &invalid_possible_position,
-                &height_map,
-                &tree_plants,
-                &animals
-            )
-            .len(),
+            Entity::new([0_f32; 3], EntityType::Animal1)
+                .get_valid_position(
+                    &invalid_possible_position,
+                    &height_map,
+                    &tree_plants,
+                    &animals
+                )
+                .len(),
             0
         );
     }
@@ -135,26 +137,28 @@ mod get_valid_position {
         ];
 
         assert_eq!(
-            Entity::get_valid_position(
-                &possible_position,
-                &invalid_height_map,
-                &tree_plants,
-                &animals
-            )
-            .len(),
+            Entity::new([0_f32; 3], EntityType::Animal1)
+                .get_valid_position(
+                    &possible_position,
+                    &invalid_height_map,
+                    &tree_plants,
+                    &animals
+                )
+                .len(),
             0
         );
 
         let valid_height_map = init_height_map::<5, 5>(1_f32);
 
         assert_eq!(
-            Entity::get_valid_position(
-                &possible_position,
-                &valid_height_map,
-                &tree_plants,
-                &animals
-            )
-            .len(),
+            Entity::new([0_f32; 3], EntityType::Animal1)
+                .get_valid_position(
+                    &possible_position,
+                    &valid_height_map,
+                    &tree_plants,
+                    &animals
+                )
+                .len(),
             8
         );
     }
@@ -178,7 +182,8 @@ mod get_valid_position {
         ];
 
         assert_eq!(
-            Entity::get_valid_position(&possible_position, &height_map, &tree_plants, &animals)
+            Entity::new([0_f32; 3], EntityType::Animal1)
+                .get_valid_position(&possible_position, &height_map, &tree_plants, &animals)
                 .len(),
             7
         );
@@ -203,13 +208,268 @@ mod get_valid_position {
         ];
 
         assert_eq!(
-            Entity::get_valid_position(&possible_position, &height_map, &tree_plants, &animals)
+            Entity::new([0_f32; 3], EntityType::Animal1)
+                .get_valid_position(&possible_position, &height_map, &tree_plants, &animals)
                 .len(),
             7
         );
     }
 }
 
+mod get_move_cost {
+    use crate::utils::height_map::init_height_map;
+
+    use super::*;
+
+    #[test]
+    fn should_not_climb_a_cliff() {
+        let mut height_map = init_height_map::<10, 10>(1.2_f32);
+        height_map[6][5] = 2_f32;
+        let animal = Entity::new([5_f32, 1.2_f32, 5_f32], EntityType::Animal1);
+
+        assert_eq!(animal.get_move_cost((6, 5), &height_map), None);
+        assert_eq!(animal.get_move_cost((4, 5), &height_map), Some(0_f32));
+    }
+
+    #[test]
+    fn should_spend_energy_to_climb_and_descend() {
+        let mut height_map = init_height_map::<10, 10>(1.2_f32);
+        height_map[6][5] = 1.5_f32;
+        height_map[4][5] = 1_f32;
+        let animal = Entity::new([5_f32, 1.2_f32, 5_f32], EntityType::Animal1);
+        let terrain = EntityType::Animal1.species().terrain;
+
+        let climb = animal.get_move_cost((6, 5), &height_map).unwrap();
+        let descent = animal.get_move_cost((4, 5), &height_map).unwrap();
+
+        assert!((climb - 0.3_f32 * terrain.climb_cost).abs() < 1e-5);
+        assert!((descent - 0.2_f32 * terrain.descent_cost).abs() < 1e-5);
+        assert!(climb > descent);
+    }
+
+    #[test]
+    fn should_use_the_distance_of_the_diagonals() {
+        let max_slope = EntityType::Animal2.species().terrain.max_slope;
+        let mut height_map = init_height_map::<10, 10>(1.2_f32);
+        height_map[6][6] = 1.2_f32 + max_slope * 1.2_f32;
+        height_map[6][5] = 1.2_f32 + max_slope * 1.2_f32;
+        let animal = Entity::new([5_f32, 1.2_f32, 5_f32], EntityType::Animal2);
+
+        assert!(animal.get_move_cost((6, 6), &height_map).is_some());
+        assert_eq!(animal.get_move_cost((6, 5), &height_map), None);
+    }
+
+    #[test]
+    fn should_not_walk_on_the_water() {
+        let mut height_map = init_height_map::<10, 10>(1.2_f32);
+        height_map[4][5] = 0_f32;
+        let animal = Entity::new([5_f32, 1.2_f32, 5_f32], EntityType::Animal1);
+
+        assert_eq!(animal.get_move_cost((4, 5), &height_map), None);
+        assert_eq!(animal.get_move_cost((10, 5), &height_map), None);
+    }
+}
+
+mod habitat {
+    use crate::utils::height_map::init_height_map;
+
+    use super::*;
+
+    #[test]
+    fn should_keep_the_fish_on_the_water() {
+        let mut height_map = init_height_map::<10, 10>(0_f32);
+        height_map[6][5] = 1.2_f32;
+        height_map[4][5] = -3_f32;
+        let fish = Entity::new([5_f32, 0.7_f32, 5_f32], EntityType::Fish);
+
+        assert_eq!(fish.get_move_cost((5, 6), &height_map), Some(0_f32));
+        assert_eq!(fish.get_move_cost((6, 5), &height_map), None);
+        assert_eq!(fish.get_move_cost((4, 5), &height_map), None);
+    }
+
+    #[test]
+    fn should_keep_the_dolphins_offshore() {
+        let habitat = EntityType::Dolphin.species().habitat;
+
+        assert!(habitat.contains(-2_f32));
+        assert!(!habitat.contains(0.5_f32));
+        assert!(!habitat.contains(1.2_f32));
+    }
+
+    #[test]
+    fn should_swim_below_the_surface() {
+        let habitat = EntityType::Fish.species().habitat;
+
+        assert!(habitat.get_height(-2_f32) < WATER_LEVEL);
+        assert!(habitat.get_height(-2_f32) > -2_f32);
+        assert_eq!(habitat.get_height(0.8_f32), 0.8_f32);
+        assert_eq!(Habitat::Land.get_height(1.2_f32), 1.2_f32);
+    }
+
+    #[test]
+    fn should_feed_the_fish_on_the_plankton() {
+        let height_map = init_height_map::<10, 10>(-1_f32);
+        let tree_plants = TreeEntity::new(Entity::new([-100_f32; 3], EntityType::Plant1));
+        let mut fish = Entity::new([5_f32, -0.7_f32, 5_f32], EntityType::Fish);
+        fish.spend_energy(50_f32);
+        let energy = fish.energy;
+
+        for _ in 0..100 {
+            fish.change_mode(
+                &height_map,
+                &tree_plants,
+                &vec![],
+                &Environment::default(),
+                &mut rand::thread_rng(),
+            );
+        }
+
+        assert!(fish.energy > energy);
+    }
+}
+
+mod flight {
+    use crate::scene::entity::flight::Flight;
+    use crate::utils::height_map::init_height_map;
+
+    use super::*;
+
+    /// Height map with a hill between the cells 10 and 20 (on the x axis).
+    fn hill_map() -> [[f32; 30]; 30] {
+        let mut height_map = init_height_map::<30, 30>(1.2_f32);
+        height_map[15] = [6_f32; 30];
+
+        height_map
+    }
+
+    #[test]
+    fn should_start_and_end_on_the_perches() {
+        let flight = Flight::new([1_f32, 2_f32, 1_f32], [9_f32, 3_f32, 5_f32]);
+
+        assert_eq!(flight.get_point(0_f32, 8_f32), flight.from);
+        assert_eq!(flight.get_point(1_f32, 8_f32), flight.to);
+        assert!(flight.get_point(0.5_f32, 8_f32)[1] > 3_f32);
+    }
+
+    #[test]
+    fn should_fly_above_the_highest_ground() {
+        let flight = Flight::new([10_f32, 2.7_f32, 5_f32], [20_f32, 2.7_f32, 5_f32]);
+
+        assert!(flight.get_cruise_height(3_f32, &hill_map()) >= 9_f32);
+    }
+
+    #[test]
+    fn should_never_clip_the_terrain() {
+        let height_map = hill_map();
+        let tree_plants = TreeEntity::new(Entity::new([-100_f32; 3], EntityType::Plant1));
+        let mut bird = Entity::new([10_f32, 2.7_f32, 5_f32], EntityType::Bird);
+        bird.take_off([20_f32, 1.2_f32, 5_f32], &height_map);
+
+        while bird.is_flying() {
+            bird.change_mode(
+                &height_map,
+                &tree_plants,
+                &vec![],
+                &Environment::default(),
+                &mut rand::thread_rng(),
+            );
+
+            let [x, y, z] = bird.position;
+            for (cell_x, cell_z) in [(x.floor(), z.floor()), (x.ceil(), z.ceil())] {
+                assert!(y > height_map[cell_x as usize][cell_z as usize]);
+            }
+        }
+    }
+
+    #[test]
+    fn should_land_on_the_perch() {
+        let height_map = hill_map();
+        let tree_plants = TreeEntity::new(Entity::new([-100_f32; 3], EntityType::Plant1));
+        let mut bird = Entity::new([10_f32, 2.7_f32, 5_f32], EntityType::Bird);
+        bird.take_off([20_f32, 1.2_f32, 5_f32], &height_map);
+
+        for _ in 0..100 {
+            bird.change_mode(
+                &height_map,
+                &tree_plants,
+                &vec![],
+                &Environment::default(),
+                &mut rand::thread_rng(),
+            );
+            if !bird.is_flying() {
+                break;
+            }
+        }
+
+        let landing = EntityType::Bird.species().habitat.get_height(1.2_f32);
+        assert!(!bird.is_flying());
+        assert_eq!(bird.position, [20_f32, landing, 5_f32]);
+    }
+
+    #[test]
+    fn should_take_off_to_a_near_plant() {
+        let height_map = init_height_map::<30, 30>(1.2_f32);
+        let tree_plants =
+            TreeEntity::new(Entity::new([14_f32, 1.2_f32, 5_f32], EntityType::Plant2));
+        let mut bird = Entity::new([10_f32, 2.7_f32, 5_f32], EntityType::Bird);
+        bird.entity_mode = EntityMode::Wandering;
+
+        bird.change_mode(
+            &height_map,
+            &tree_plants,
+            &vec![],
+            &Environment::default(),
+            &mut rand::thread_rng(),
+        );
+
+        assert!(bird.is_flying());
+        assert_eq!(bird.flight.unwrap().to[0], 14_f32);
+        assert_eq!(
+            bird.get_free_neighbors(&height_map, &tree_plants, &vec![]),
+            vec![]
+        );
+    }
+}
+
+mod choose_position {
+    use crate::utils::height_map::init_height_map;
+
+    use super::*;
+
+    #[test]
+    fn should_prefer_the_gentle_route() {
+        let mut height_map = init_height_map::<10, 10>(1.2_f32);
+        height_map[6][5] = 1.5_f32;
+        let animal = Entity::new([5_f32, 1.2_f32, 5_f32], EntityType::Animal1);
+        let possible_position = animal.get_neighbors();
+        let valid_positions: Vec<usize> = (0..possible_position.len()).collect();
+
+        let index = animal.choose_position(
+            &possible_position,
+            &valid_positions,
+            [8_f32, 1.2_f32, 5_f32],
+            true,
+            &height_map,
+        );
+
+        assert_ne!(possible_position[index], (6, 5));
+        assert_eq!(possible_position[index].0, 6);
+    }
+
+    #[test]
+    fn should_never_walk_up_a_cliff() {
+        let mut height_map = init_height_map::<10, 10>(1.2_f32);
+        height_map[6] = [3_f32; 10];
+        let tree_plants = TreeEntity::new(Entity::new([-100_f32; 3], EntityType::Plant1));
+        let animal = Entity::new([5_f32, 1.2_f32, 5_f32], EntityType::Animal1);
+
+        let free_neighbors = animal.get_free_neighbors(&height_map, &tree_plants, &vec![]);
+
+        assert_eq!(free_neighbors.len(), 5);
+        assert!(free_neighbors.iter().all(|(x, _)| *x != 6));
+    }
+}
+
 mod have_another_animal {
     use super::*;
 
@@ -228,7 +488,7 @@ mod have_another_animal {
 
         let animals = vec![Entity::new([1_f32; 3], EntityType::Animal1)];
 
-        assert_eq!(Entity::have_another_animal(position, &animals), false);
+        assert!(!Entity::have_another_animal(position, &animals));
     }
 }
 
@@ -244,10 +504,22 @@ mod change_mode {
         let tree_plants = TreeEntity::new(Entity::new([2_f32; 3], EntityType::Plant1));
         let animals = Vec::<Entity>::new();
 
-        plant1.change_mode(&height_map, &tree_plants, &animals);
+        plant1.change_mode(
+            &height_map,
+            &tree_plants,
+            &animals,
+            &Environment::default(),
+            &mut rand::thread_rng(),
+        );
         assert_eq!(plant1.entity_mode, EntityMode::Idle);
 
-        plant2.change_mode(&height_map, &tree_plants, &animals);
+        plant2.change_mode(
+            &height_map,
+            &tree_plants,
+            &animals,
+            &Environment::default(),
+            &mut rand::thread_rng(),
+        );
         assert_eq!(plant2.entity_mode, EntityMode::Idle);
     }
 
@@ -259,7 +531,13 @@ mod change_mode {
         let tree_plants = TreeEntity::new(Entity::new([2_f32; 3], EntityType::Plant1));
         let animals = Vec::<Entity>::new();
 
-        animal.change_mode(&height_map, &tree_plants, &animals);
+        animal.change_mode(
+            &height_map,
+            &tree_plants,
+            &animals,
+            &Environment::default(),
+            &mut rand::thread_rng(),
+        );
 
         assert_ne!(animal.entity_mode, EntityMode::Idle);
     }
@@ -268,35 +546,1198 @@ mod change_mode {
     fn should_move_animals_position_when_walking() {
         const INITIAL_POSITION: [f32; 3] = [0_f32; 3];
         let mut animal = Entity::new(INITIAL_POSITION, EntityType::Animal1);
-        animal.entity_mode = EntityMode::Walking {
-            target: (1_f32, 1_f32),
-        };
+        animal.entity_mode = EntityMode::Wandering;
+        animal.target = Some((1_f32, 1_f32));
 
         let height_map = [[1_f32; 25]; 25];
         let tree_plants = TreeEntity::new(Entity::new([2_f32; 3], EntityType::Plant1));
         let animals = Vec::<Entity>::new();
 
-        animal.change_mode(&height_map, &tree_plants, &animals);
+        animal.change_mode(
+            &height_map,
+            &tree_plants,
+            &animals,
+            &Environment::default(),
+            &mut rand::thread_rng(),
+        );
 
-        assert_ne!(animal.entity_mode, EntityMode::Idle);
+        assert_eq!(animal.target, Some((1_f32, 1_f32)));
         assert_ne!(animal.position, INITIAL_POSITION);
     }
 
     #[test]
-    fn should_be_idle_if_the_target_is_actived() {
+    fn should_stop_if_the_target_is_reached() {
         const INITIAL_POSITION: [f32; 3] = [0_f32; 3];
         let mut animal = Entity::new(INITIAL_POSITION, EntityType::Animal1);
-        animal.entity_mode = EntityMode::Walking {
-            target: (INITIAL_POSITION[0], INITIAL_POSITION[2]),
-        };
+        animal.entity_mode = EntityMode::Wandering;
+        animal.target = Some((INITIAL_POSITION[0], INITIAL_POSITION[2]));
 
         let height_map = [[1_f32; 25]; 25];
         let tree_plants = TreeEntity::new(Entity::new([2_f32; 3], EntityType::Plant1));
         let animals = Vec::<Entity>::new();
 
-        animal.change_mode(&height_map, &tree_plants, &animals);
+        animal.change_mode(
+            &height_map,
+            &tree_plants,
+            &animals,
+            &Environment::default(),
+            &mut rand::thread_rng(),
+        );
 
-        assert_eq!(animal.entity_mode, EntityMode::Idle);
+        assert_eq!(animal.target, None);
+        assert_eq!(animal.entity_mode, EntityMode::Wandering);
         assert_eq!(animal.position, INITIAL_POSITION);
     }
 }
+
+mod hunts {
+    use super::*;
+
+    #[test]
+    fn should_hunt_the_species_in_the_diet() {
+        assert!(EntityType::Animal1.hunts(EntityType::Animal2));
+        assert!(!EntityType::Animal2.hunts(EntityType::Animal1));
+        assert!(!EntityType::Animal1.hunts(EntityType::Animal1));
+    }
+}
+
+mod predator_prey {
+    use super::*;
+
+    #[test]
+    fn should_flee_from_a_visible_predator() {
+        let mut prey = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal2);
+        let predator = Entity::new([3_f32, 0_f32, 5_f32], EntityType::Animal1);
+
+        let height_map = [[1_f32; 25]; 25];
+        let tree_plants = TreeEntity::new(Entity::new([-100_f32; 3], EntityType::Plant1));
+        let animals = vec![prey, predator];
+
+        prey.change_mode(
+            &height_map,
+            &tree_plants,
+            &animals,
+            &Environment::default(),
+            &mut rand::thread_rng(),
+        );
+
+        assert_eq!(prey.entity_mode, EntityMode::Fleeing);
+        assert_eq!(prey.target.unwrap().0, 6_f32);
+    }
+
+    #[test]
+    fn should_not_see_the_predators_while_sleeping() {
+        let mut prey = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal2);
+        prey.entity_mode = EntityMode::Sleeping;
+        prey.stamina = 0.1_f32;
+        let predator = Entity::new([3_f32, 0_f32, 5_f32], EntityType::Animal1);
+
+        let height_map = [[1_f32; 25]; 25];
+        let tree_plants = TreeEntity::new(Entity::new([-100_f32; 3], EntityType::Plant1));
+        let animals = vec![prey, predator];
+
+        prey.change_mode(
+            &height_map,
+            &tree_plants,
+            &animals,
+            &Environment::default(),
+            &mut rand::thread_rng(),
+        );
+
+        assert_eq!(prey.entity_mode, EntityMode::Sleeping);
+    }
+
+    #[test]
+    fn should_hunt_a_visible_prey_when_hungry() {
+        let mut predator = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal1);
+        predator.energy = 10_f32;
+        let prey = Entity::new([8_f32, 0_f32, 5_f32], EntityType::Animal2);
+
+        let height_map = [[1_f32; 25]; 25];
+        let tree_plants = TreeEntity::new(Entity::new([-100_f32; 3], EntityType::Plant1));
+        let animals = vec![predator, prey];
+
+        predator.change_mode(
+            &height_map,
+            &tree_plants,
+            &animals,
+            &Environment::default(),
+            &mut rand::thread_rng(),
+        );
+
+        assert_eq!(predator.entity_mode, EntityMode::Seeking(Need::Food));
+        assert_eq!(predator.target, Some((6_f32, 5_f32)));
+    }
+
+    #[test]
+    fn should_not_hunt_when_fed() {
+        let mut predator = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal1);
+        let prey = Entity::new([8_f32, 0_f32, 5_f32], EntityType::Animal2);
+
+        let height_map = [[1_f32; 25]; 25];
+        let tree_plants = TreeEntity::new(Entity::new([-100_f32; 3], EntityType::Plant1));
+        let animals = vec![predator, prey];
+
+        // The predator is nocturnal.
+        predator.change_mode(
+            &height_map,
+            &tree_plants,
+            &animals,
+            &Environment {
+                night: true,
+                ..Environment::default()
+            },
+            &mut rand::thread_rng(),
+        );
+
+        assert_eq!(predator.entity_mode, EntityMode::Wandering);
+    }
+
+    #[test]
+    fn should_spend_energy_on_each_tick() {
+        let mut predator = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal1);
+        let max_energy = EntityType::Animal1.species().max_energy;
+
+        let height_map = [[1_f32; 25]; 25];
+        let tree_plants = TreeEntity::new(Entity::new([-100_f32; 3], EntityType::Plant1));
+
+        predator.change_mode(
+            &height_map,
+            &tree_plants,
+            &vec![],
+            &Environment::default(),
+            &mut rand::thread_rng(),
+        );
+
+        assert!(predator.energy < max_energy);
+
+        predator.feed(max_energy);
+        assert_eq!(predator.energy, max_energy);
+    }
+}
+
+mod growth {
+    use super::*;
+
+    #[test]
+    fn should_scale_plants_by_growth() {
+        let mut plant = Entity::new([0_f32; 3], EntityType::Plant1);
+        assert_eq!(plant.get_scale(), 1_f32);
+
+        plant.set_growth(0_f32);
+        assert_eq!(plant.get_scale(), SEEDLING_SCALE);
+        assert!(!plant.is_edible());
+
+        plant.set_growth(2_f32);
+        assert_eq!(plant.get_growth(), 1_f32);
+        assert!(plant.is_edible());
+    }
+
+    #[test]
+    fn should_not_scale_animals_by_growth() {
+        let mut animal = Entity::new([0_f32; 3], EntityType::Animal2);
+        animal.set_growth(0_f32);
+
+        assert_eq!(animal.get_scale(), 1_f32);
+    }
+}
+
+mod foraging {
+    use super::*;
+
+    #[test]
+    fn should_forage_a_grown_plant_when_hungry() {
+        let mut animal = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal2);
+        animal.energy = 10_f32;
+
+        let height_map = [[1_f32; 25]; 25];
+        let tree_plants = TreeEntity::new(Entity::new([5_f32, 0_f32, 8_f32], EntityType::Plant1));
+
+        animal.change_mode(
+            &height_map,
+            &tree_plants,
+            &vec![],
+            &Environment::default(),
+            &mut rand::thread_rng(),
+        );
+
+        assert_eq!(animal.entity_mode, EntityMode::Seeking(Need::Food));
+        assert_eq!(animal.target, Some((5_f32, 6_f32)));
+    }
+
+    #[test]
+    fn should_ignore_seedlings() {
+        let mut animal = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal2);
+        animal.energy = 10_f32;
+
+        let height_map = [[1_f32; 25]; 25];
+        let mut plant = Entity::new([5_f32, 0_f32, 8_f32], EntityType::Plant1);
+        plant.set_growth(0_f32);
+        let tree_plants = TreeEntity::new(plant);
+
+        animal.change_mode(
+            &height_map,
+            &tree_plants,
+            &vec![],
+            &Environment::default(),
+            &mut rand::thread_rng(),
+        );
+
+        assert_eq!(animal.entity_mode, EntityMode::Wandering);
+    }
+}
+
+mod life_cycle {
+    use super::*;
+    use crate::scene::season::{Season, DEFAULT_SEASON_LENGTH};
+
+    #[test]
+    fn should_age_on_each_tick() {
+        let mut animal = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal2);
+
+        let height_map = [[1_f32; 25]; 25];
+        let tree_plants = TreeEntity::new(Entity::new([-100_f32; 3], EntityType::Plant1));
+
+        animal.change_mode(
+            &height_map,
+            &tree_plants,
+            &vec![],
+            &Environment::default(),
+            &mut rand::thread_rng(),
+        );
+
+        assert_eq!(animal.age, 1);
+    }
+
+    #[test]
+    fn should_be_too_old_after_max_age() {
+        let mut animal = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal2);
+        assert!(!animal.is_too_old());
+
+        animal.age = EntityType::Animal2.species().max_age;
+        assert!(animal.is_too_old());
+    }
+
+    #[test]
+    fn should_outlive_the_seasons_without_breeding() {
+        for entity_type in EntityType::ALL.into_iter().filter(|t| !t.is_plant()) {
+            let species = entity_type.species();
+            let off_seasons = (Season::ALL.len() - species.breeding_seasons.len()) as u64;
+            let off_season = DEFAULT_SEASON_LENGTH * off_seasons;
+
+            // The animals born at the end of the breeding season grow up before the next one.
+            assert!(species.max_age as u64 > off_season + species.maturity_age as u64);
+        }
+    }
+
+    #[test]
+    fn should_mate_adults_of_same_species_with_energy() {
+        let maturity_age = EntityType::Animal2.species().maturity_age;
+
+        let mut first = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal2);
+        let mut second = Entity::new([6_f32, 0_f32, 5_f32], EntityType::Animal2);
+        assert!(!first.can_mate(&second));
+
+        first.age = maturity_age;
+        second.age = maturity_age;
+        assert!(first.can_mate(&second));
+
+        second.spend_energy(100_f32);
+        assert!(!first.can_mate(&second));
+    }
+
+    #[test]
+    fn should_not_mate_with_another_species() {
+        let mut first = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal2);
+        let mut second = Entity::new([6_f32, 0_f32, 5_f32], EntityType::Animal1);
+        first.age = 1000;
+        second.age = 1000;
+
+        assert!(!first.can_mate(&second));
+    }
+}
+
+mod flocking {
+    use super::*;
+    use crate::scene::entity::species::Flocking;
+
+    const FLOCKING: Flocking = Flocking {
+        separation_distance: 1.5_f32,
+        separation_weight: 1_f32,
+        alignment_weight: 0_f32,
+        cohesion_weight: 1_f32,
+        leader_weight: 0_f32,
+    };
+
+    #[test]
+    fn should_steer_towards_the_herd() {
+        let animal = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal2);
+        let animals = vec![
+            animal,
+            Entity::new([8_f32, 0_f32, 5_f32], EntityType::Animal2),
+            Entity::new([8_f32, 0_f32, 6_f32], EntityType::Animal2),
+        ];
+
+        let steering = animal.get_flocking_steering(&animals, &FLOCKING);
+
+        assert!(steering[0] > 0_f32);
+    }
+
+    #[test]
+    fn should_keep_distance_from_flockmates() {
+        let animal = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal2);
+        let animals = vec![
+            animal,
+            Entity::new([6_f32, 0_f32, 5_f32], EntityType::Animal2),
+        ];
+
+        let flocking = Flocking {
+            cohesion_weight: 0_f32,
+            ..FLOCKING
+        };
+        let steering = animal.get_flocking_steering(&animals, &flocking);
+
+        assert!(steering[0] < 0_f32);
+    }
+
+    #[test]
+    fn should_ignore_other_species() {
+        let animal = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal2);
+        let animals = vec![
+            animal,
+            Entity::new([8_f32, 0_f32, 5_f32], EntityType::Animal1),
+        ];
+
+        assert_eq!(
+            animal.get_flocking_steering(&animals, &FLOCKING),
+            [0_f32; 2]
+        );
+    }
+
+    #[test]
+    fn should_follow_the_herd_leader() {
+        let mut leader = Entity::new([2_f32, 0_f32, 5_f32], EntityType::Animal2);
+        leader.age = 100;
+        let follower = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal2);
+        let animals = vec![leader, follower];
+
+        let flocking = Flocking {
+            cohesion_weight: 0_f32,
+            leader_weight: 1_f32,
+            ..FLOCKING
+        };
+
+        assert!(follower.get_flocking_steering(&animals, &flocking)[0] < 0_f32);
+        assert_eq!(
+            leader.get_flocking_steering(&animals, &flocking),
+            [0_f32; 2]
+        );
+    }
+
+    #[test]
+    fn should_walk_with_the_herd_on_valid_cells() {
+        let mut animal = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal2);
+        let mut leader = Entity::new([9_f32, 0_f32, 5_f32], EntityType::Animal2);
+        leader.age = 100;
+        let animals = vec![animal, leader];
+
+        let height_map = [[1_f32; 25]; 25];
+        let tree_plants = TreeEntity::new(Entity::new([6_f32, 0_f32, 5_f32], EntityType::Plant1));
+
+        animal.change_mode(
+            &height_map,
+            &tree_plants,
+            &animals,
+            &Environment::default(),
+            &mut rand::thread_rng(),
+        );
+
+        let target = animal.target.unwrap();
+        assert_eq!(animal.entity_mode, EntityMode::Wandering);
+        assert_eq!(target.0, 6_f32);
+        assert_ne!(target, (6_f32, 5_f32));
+    }
+}
+
+mod interpolation {
+    use super::*;
+
+    #[test]
+    fn should_interpolate_the_position_between_ticks() {
+        let mut animal = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal1);
+        animal.target = Some((6_f32, 5_f32));
+
+        let height_map = [[1_f32; 25]; 25];
+        let tree_plants = TreeEntity::new(Entity::new([-100_f32; 3], EntityType::Plant1));
+        animal.change_mode(
+            &height_map,
+            &tree_plants,
+            &vec![],
+            &Environment::default(),
+            &mut rand::thread_rng(),
+        );
+
+        assert_eq!(
+            animal.get_interpolated_position(0_f32),
+            [5_f32, 0_f32, 5_f32]
+        );
+        assert_eq!(
+            animal.get_interpolated_position(0.5_f32),
+            [5.25_f32, 0_f32, 5_f32]
+        );
+        assert_eq!(animal.get_interpolated_position(1_f32), animal.position);
+    }
+
+    #[test]
+    fn should_interpolate_the_rotation_by_the_shortest_turn() {
+        let mut animal = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal1);
+        animal.last_rotation = 315_f32;
+        animal.rotation = 45_f32;
+
+        assert_eq!(animal.get_interpolated_rotation(0.5_f32), 360_f32);
+    }
+}
+
+mod move_report {
+    use super::*;
+
+    #[test]
+    fn should_count_the_cells_blocked_by_other_entities() {
+        let mut animal = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal2);
+        let height_map = [[1_f32; 25]; 25];
+        let tree_plants = TreeEntity::new(Entity::new([5_f32, 0_f32, 6_f32], EntityType::Plant1));
+        let animals = vec![Entity::new([4_f32, 0_f32, 5_f32], EntityType::Animal2)];
+
+        let report = animal.change_mode(
+            &height_map,
+            &tree_plants,
+            &animals,
+            &Environment::default(),
+            &mut rand::thread_rng(),
+        );
+
+        assert_eq!(report.collisions_avoided, 2);
+        assert!(!report.failed);
+    }
+
+    #[test]
+    fn should_fail_when_there_is_no_walkable_cell() {
+        let mut animal = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal2);
+        let mut height_map = [[0_f32; 25]; 25];
+        height_map[5][5] = 1_f32;
+        let tree_plants = TreeEntity::new(Entity::new([-1000_f32; 3], EntityType::Plant1));
+
+        let report = animal.change_mode(
+            &height_map,
+            &tree_plants,
+            &vec![],
+            &Environment::default(),
+            &mut rand::thread_rng(),
+        );
+
+        assert_eq!(report.collisions_avoided, 0);
+        assert!(report.failed);
+    }
+}
+
+mod behaviour {
+    use super::*;
+    use crate::scene::entity::behaviour::{Condition, Senses};
+
+    fn create_height_map_with_water(x: usize, z: usize) -> [[f32; 25]; 25] {
+        let mut height_map = [[1_f32; 25]; 25];
+        height_map[x][z] = 0_f32;
+        height_map
+    }
+
+    #[test]
+    fn should_seek_visible_water_when_thirsty() {
+        let mut animal = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal2);
+        animal.hydration = 10_f32;
+
+        let height_map = create_height_map_with_water(9, 5);
+        let tree_plants = TreeEntity::new(Entity::new([-100_f32; 3], EntityType::Plant1));
+
+        let report = animal.change_mode(
+            &height_map,
+            &tree_plants,
+            &vec![],
+            &Environment::default(),
+            &mut rand::thread_rng(),
+        );
+
+        assert_eq!(animal.entity_mode, EntityMode::Seeking(Need::Water));
+        assert_eq!(animal.target, Some((6_f32, 5_f32)));
+        assert_eq!(report.mode_change.unwrap().reason, Condition::WaterSeen);
+    }
+
+    #[test]
+    fn should_drink_before_looking_for_food() {
+        let mut animal = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal2);
+        animal.entity_mode = EntityMode::Seeking(Need::Food);
+        animal.energy = 10_f32;
+        animal.hydration = 10_f32;
+
+        let height_map = create_height_map_with_water(9, 5);
+        let tree_plants = TreeEntity::new(Entity::new([5_f32, 0_f32, 8_f32], EntityType::Plant1));
+
+        animal.change_mode(
+            &height_map,
+            &tree_plants,
+            &vec![],
+            &Environment::default(),
+            &mut rand::thread_rng(),
+        );
+
+        assert_eq!(animal.entity_mode, EntityMode::Seeking(Need::Water));
+        assert_eq!(animal.target, Some((6_f32, 5_f32)));
+    }
+
+    #[test]
+    fn should_drink_until_quenched() {
+        let mut animal = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal2);
+        animal.hydration = 10_f32;
+
+        let height_map = create_height_map_with_water(6, 5);
+        let tree_plants = TreeEntity::new(Entity::new([-100_f32; 3], EntityType::Plant1));
+
+        animal.change_mode(
+            &height_map,
+            &tree_plants,
+            &vec![],
+            &Environment::default(),
+            &mut rand::thread_rng(),
+        );
+
+        assert_eq!(animal.entity_mode, EntityMode::Drinking);
+        assert_eq!(animal.target, None);
+        assert_eq!(animal.rotation, 0_f32);
+        assert!(animal.hydration > 10_f32);
+
+        for _ in 0..10 {
+            animal.change_mode(
+                &height_map,
+                &tree_plants,
+                &vec![],
+                &Environment::default(),
+                &mut rand::thread_rng(),
+            );
+        }
+
+        assert_eq!(animal.entity_mode, EntityMode::Wandering);
+        assert!(!animal.is_thirsty());
+    }
+
+    #[test]
+    fn should_sleep_when_tired_and_wake_up_when_rested() {
+        let mut animal = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal2);
+        animal.entity_mode = EntityMode::Wandering;
+        animal.stamina = 0.1_f32;
+
+        let height_map = [[1_f32; 25]; 25];
+        let tree_plants = TreeEntity::new(Entity::new([-100_f32; 3], EntityType::Plant1));
+
+        animal.change_mode(
+            &height_map,
+            &tree_plants,
+            &vec![],
+            &Environment::default(),
+            &mut rand::thread_rng(),
+        );
+        assert_eq!(animal.entity_mode, EntityMode::Sleeping);
+        assert_eq!(animal.position, [5_f32, 0_f32, 5_f32]);
+
+        for _ in 0..20 {
+            animal.change_mode(
+                &height_map,
+                &tree_plants,
+                &vec![],
+                &Environment::default(),
+                &mut rand::thread_rng(),
+            );
+        }
+        assert_eq!(animal.entity_mode, EntityMode::Wandering);
+    }
+
+    #[test]
+    fn should_wait_when_blocked() {
+        let mut animal = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal2);
+        animal.entity_mode = EntityMode::Wandering;
+
+        let senses = Senses {
+            blocked: true,
+            ..Senses::default()
+        };
+
+        assert_eq!(
+            animal.next_mode(&senses),
+            Some((EntityMode::Idle, Condition::Blocked))
+        );
+
+        animal.set_mode(EntityMode::Idle, Condition::Blocked, &senses);
+        assert_eq!(animal.next_mode(&senses), None);
+
+        animal.mode_ticks = 1;
+        assert_eq!(
+            animal.next_mode(&senses),
+            Some((EntityMode::Wandering, Condition::TimeInMode(1)))
+        );
+    }
+
+    #[test]
+    fn should_keep_the_mode_when_its_transition_matches() {
+        let mut animal = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal2);
+        animal.entity_mode = EntityMode::Fleeing;
+
+        let senses = Senses {
+            predator: Some([3_f32, 0_f32, 5_f32]),
+            blocked: true,
+            ..Senses::default()
+        };
+
+        assert_eq!(animal.next_mode(&senses), None);
+    }
+
+    #[test]
+    fn should_describe_the_mode_change() {
+        let mut animal = Entity::new([5_f32, 0_f32, 7_f32], EntityType::Animal2);
+
+        let change = animal.set_mode(
+            EntityMode::Fleeing,
+            Condition::PredatorNear,
+            &Senses::default(),
+        );
+
+        assert_eq!(animal.mode_ticks, 0);
+        assert_eq!(
+            change.to_string(),
+            "Animal2 at (5, 7): Idle -> Fleeing (PredatorNear)"
+        );
+    }
+
+    #[test]
+    fn should_dehydrate_without_water() {
+        let mut animal = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal1);
+        assert!(!animal.is_dehydrated());
+
+        animal.hydration = 0_f32;
+        assert!(animal.is_dehydrated());
+
+        animal.drink(1000_f32);
+        assert_eq!(
+            animal.hydration,
+            EntityType::Animal1.species().max_hydration
+        );
+    }
+
+    #[test]
+    fn should_find_the_mode_by_name() {
+        for mode in EntityMode::ALL {
+            assert_eq!(EntityMode::from_name(mode.get_name()), Some(mode));
+        }
+        assert_eq!(EntityMode::from_name("Flying"), None);
+    }
+}
+
+mod day_cycle {
+    use super::*;
+
+    const NIGHT: Environment = Environment {
+        night: true,
+        raining: false,
+        fires: &[],
+    };
+    const DAY: Environment = Environment {
+        night: false,
+        raining: false,
+        fires: &[],
+    };
+
+    fn tick(animal: &mut Entity, environment: &Environment) {
+        let height_map = [[1.2_f32; 10]; 10];
+        let tree_plants = TreeEntity::new(Entity::new([-100_f32; 3], EntityType::Plant1));
+
+        animal.change_mode(
+            &height_map,
+            &tree_plants,
+            &vec![*animal],
+            environment,
+            &mut rand::thread_rng(),
+        );
+    }
+
+    #[test]
+    fn should_sleep_out_of_the_active_time() {
+        let mut diurnal = Entity::new([5_f32, 1.2_f32, 5_f32], EntityType::Animal2);
+        let mut nocturnal = Entity::new([5_f32, 1.2_f32, 5_f32], EntityType::Animal1);
+        diurnal.entity_mode = EntityMode::Wandering;
+        nocturnal.entity_mode = EntityMode::Wandering;
+
+        tick(&mut diurnal, &NIGHT);
+        tick(&mut nocturnal, &NIGHT);
+
+        assert_eq!(diurnal.entity_mode, EntityMode::Sleeping);
+        assert_ne!(nocturnal.entity_mode, EntityMode::Sleeping);
+
+        let mut nocturnal = Entity::new([5_f32, 1.2_f32, 5_f32], EntityType::Animal1);
+        nocturnal.entity_mode = EntityMode::Wandering;
+        tick(&mut nocturnal, &DAY);
+        assert_eq!(nocturnal.entity_mode, EntityMode::Sleeping);
+    }
+
+    #[test]
+    fn should_never_sleep_when_always_active() {
+        let mut fish = Entity::new([5_f32, 0.5_f32, 5_f32], EntityType::Fish);
+        fish.entity_mode = EntityMode::Wandering;
+
+        tick(&mut fish, &NIGHT);
+
+        assert_ne!(fish.entity_mode, EntityMode::Sleeping);
+    }
+
+    #[test]
+    fn should_wake_up_on_the_active_time() {
+        let mut animal = Entity::new([5_f32, 1.2_f32, 5_f32], EntityType::Animal2);
+        animal.entity_mode = EntityMode::Sleeping;
+
+        tick(&mut animal, &NIGHT);
+        assert_eq!(animal.entity_mode, EntityMode::Sleeping);
+
+        tick(&mut animal, &DAY);
+        assert_eq!(animal.entity_mode, EntityMode::Wandering);
+    }
+
+    #[test]
+    fn should_wake_up_hungry_at_night() {
+        let mut animal = Entity::new([5_f32, 1.2_f32, 5_f32], EntityType::Animal2);
+        animal.entity_mode = EntityMode::Sleeping;
+        animal.spend_energy(50_f32);
+
+        tick(&mut animal, &NIGHT);
+
+        assert_eq!(animal.entity_mode, EntityMode::Wandering);
+    }
+
+    #[test]
+    fn should_spend_less_energy_sleeping() {
+        let mut awake = Entity::new([5_f32, 1.2_f32, 5_f32], EntityType::Animal1);
+        let mut sleeping = awake;
+        awake.entity_mode = EntityMode::Eating;
+        sleeping.entity_mode = EntityMode::Sleeping;
+        sleeping.stamina = 0_f32;
+
+        tick(&mut awake, &NIGHT);
+        tick(&mut sleeping, &NIGHT);
+
+        assert!(sleeping.energy > awake.energy);
+    }
+}
+
+mod record {
+    use super::*;
+    use crate::scene::entity::behaviour::{Condition, Senses};
+
+    #[test]
+    fn should_read_the_written_record() {
+        let mut entity = Entity::new([1.5_f32, 0.25_f32, 3_f32], EntityType::Animal2);
+        entity.set_rotation(90_f32);
+        entity.set_mode(EntityMode::Sleeping, Condition::Tired, &Senses::default());
+
+        let record = entity.get_record();
+
+        assert_eq!(Entity::from_record(&record).unwrap().get_record(), record);
+    }
+
+    #[test]
+    fn should_read_the_genome() {
+        let genome =
+            Genome::from_traits([0.75_f32, 1.2_f32, 0.8_f32, 1.1_f32, 0.9_f32, 1_f32, 1.3_f32]);
+        let animal = Entity::with_genome([1_f32, 0_f32, 2_f32], EntityType::Animal1, genome);
+
+        let read = Entity::from_record(&animal.get_record()).unwrap();
+
+        assert_eq!(read.get_genome(), genome);
+    }
+
+    #[test]
+    fn should_read_the_flight_of_a_bird() {
+        let mut bird = Entity::new([2_f32, 2.7_f32, 2_f32], EntityType::Bird);
+        bird.take_off([8_f32, 1.2_f32, 4_f32], &[[1.2_f32; 10]; 10]);
+        bird.fly(&[[1.2_f32; 10]; 10]);
+
+        let read = Entity::from_record(&bird.get_record()).unwrap();
+
+        assert_eq!(read.flight, bird.flight);
+        assert_eq!(read.get_record(), bird.get_record());
+    }
+
+    #[test]
+    fn should_reject_an_invalid_record() {
+        assert!(Entity::from_record("animal1 1 2").is_err());
+        assert!(Entity::from_record(&"unknown 0".repeat(10)).is_err());
+    }
+}
+
+mod shelter {
+    use super::*;
+    use crate::scene::entity::behaviour::{Condition, Senses};
+
+    const RAIN: Environment = Environment {
+        night: false,
+        raining: true,
+        fires: &[],
+    };
+
+    #[test]
+    fn should_hide_under_a_tree_in_the_rain() {
+        let height_map = [[1.2_f32; 12]; 12];
+        let tree_plants = TreeEntity::new(Entity::new([6_f32, 1.2_f32, 5_f32], EntityType::Plant2));
+        let mut animal = Entity::new([2_f32, 1.2_f32, 5_f32], EntityType::Animal2);
+
+        let mut modes = vec![];
+        for _ in 0..40 {
+            let report = animal.change_mode(
+                &height_map,
+                &tree_plants,
+                &vec![animal],
+                &RAIN,
+                &mut rand::thread_rng(),
+            );
+            modes.extend(report.mode_change.map(|change| change.to));
+        }
+
+        assert!(modes.contains(&EntityMode::Seeking(Need::Shelter)));
+        assert_eq!(animal.entity_mode, EntityMode::Idle);
+        assert!(animal.distance([6_f32, 1.2_f32, 5_f32]) <= CATCH_DISTANCE);
+    }
+
+    #[test]
+    fn should_stay_under_the_tree_until_the_rain_stops() {
+        let mut animal = Entity::new([5_f32, 1.2_f32, 5_f32], EntityType::Animal2);
+        animal.mode_ticks = 5;
+
+        let sheltered = Senses {
+            shelter: Some([6_f32, 1.2_f32, 5_f32]),
+            ..Senses::default()
+        };
+        assert_eq!(animal.next_mode(&sheltered), None);
+
+        assert_eq!(
+            animal.next_mode(&Senses::default()),
+            Some((EntityMode::Wandering, Condition::TimeInMode(1)))
+        );
+    }
+
+    #[test]
+    fn should_leave_the_shelter_to_eat() {
+        let mut animal = Entity::new([5_f32, 1.2_f32, 5_f32], EntityType::Animal2);
+        animal.entity_mode = EntityMode::Seeking(Need::Shelter);
+        animal.spend_energy(50_f32);
+
+        let senses = Senses {
+            food: Some([2_f32, 1.2_f32, 5_f32]),
+            shelter: Some([9_f32, 1.2_f32, 5_f32]),
+            ..Senses::default()
+        };
+
+        assert_eq!(
+            animal.next_mode(&senses),
+            Some((EntityMode::Seeking(Need::Food), Condition::FoodSeen))
+        );
+    }
+
+    #[test]
+    fn should_not_look_for_a_shelter_without_rain() {
+        let height_map = [[1.2_f32; 12]; 12];
+        let tree_plants = TreeEntity::new(Entity::new([6_f32, 1.2_f32, 5_f32], EntityType::Plant2));
+        let animal = Entity::new([2_f32, 1.2_f32, 5_f32], EntityType::Animal2);
+
+        let dry = animal.sense(
+            &height_map,
+            &tree_plants,
+            &[],
+            &Environment::default(),
+            false,
+        );
+        let wet = animal.sense(&height_map, &tree_plants, &[], &RAIN, false);
+
+        assert_eq!(dry.shelter, None);
+        assert_eq!(wet.shelter, Some([6_f32, 1.2_f32, 5_f32]));
+    }
+}
+
+mod fire {
+    use super::*;
+    use crate::scene::entity::behaviour::Condition;
+
+    const FIRES: &[[f32; 3]] = &[[6_f32, 1.2_f32, 5_f32]];
+    const FIRE: Environment = Environment {
+        night: false,
+        raining: false,
+        fires: FIRES,
+    };
+
+    #[test]
+    fn should_flee_from_a_fire() {
+        let height_map = [[1.2_f32; 12]; 12];
+        let tree_plants =
+            TreeEntity::new(Entity::new([-10_f32, 1.2_f32, -10_f32], EntityType::Plant1));
+        let mut animal = Entity::new([4_f32, 1.2_f32, 5_f32], EntityType::Animal2);
+
+        let report = animal.change_mode(
+            &height_map,
+            &tree_plants,
+            &vec![animal],
+            &FIRE,
+            &mut rand::thread_rng(),
+        );
+
+        assert_eq!(report.mode_change.unwrap().reason, Condition::FireNear);
+        assert_eq!(animal.entity_mode, EntityMode::Fleeing);
+        assert!(animal.get_target().unwrap().0 < 4_f32);
+    }
+
+    #[test]
+    fn should_not_see_the_fires_from_the_water() {
+        let height_map = [[0.2_f32; 12]; 12];
+        let tree_plants =
+            TreeEntity::new(Entity::new([-10_f32, 1.2_f32, -10_f32], EntityType::Plant1));
+        let fish = Entity::new([4_f32, 0.2_f32, 5_f32], EntityType::Fish);
+        let animal = Entity::new([4_f32, 1.2_f32, 5_f32], EntityType::Animal2);
+
+        let sense = |entity: &Entity| entity.sense(&height_map, &tree_plants, &[], &FIRE, false);
+
+        assert_eq!(sense(&fish).fire, None);
+        assert_eq!(sense(&animal).fire, Some(FIRES[0]));
+    }
+}
+
+mod genome {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    /// Max change of a trait on a mutation.
+    const MUTATION: f32 = 0.1_f32;
+
+    #[test]
+    fn should_inherit_each_trait_from_a_parent() {
+        let first = Genome::from_traits([
+            0.25_f32, 0.5_f32, 0.5_f32, 0.5_f32, 0.6_f32, 0.6_f32, 0.6_f32,
+        ]);
+        let second =
+            Genome::from_traits([1_f32, 1.5_f32, 1.5_f32, 1.5_f32, 1.4_f32, 1.4_f32, 1.4_f32]);
+        let mut rand = ChaCha8Rng::seed_from_u64(1);
+
+        for _ in 0..100 {
+            let child = Genome::inherit(&first, &second, &mut rand).get_traits();
+
+            for (i, value) in child.iter().enumerate() {
+                let low = first.get_traits()[i];
+                let high = second.get_traits()[i];
+                assert!(*value >= low && *value <= high);
+                assert!(*value <= low * (1_f32 + MUTATION) || *value >= high * (1_f32 - MUTATION));
+            }
+        }
+    }
+
+    #[test]
+    fn should_mutate_the_offspring() {
+        let parent = Genome::default();
+        let mut rand = ChaCha8Rng::seed_from_u64(1);
+
+        let mutated = (0..100)
+            .map(|_| Genome::inherit(&parent, &parent, &mut rand))
+            .filter(|child| *child != parent)
+            .count();
+
+        assert!(mutated > 0);
+    }
+
+    #[test]
+    fn should_walk_with_the_speed_of_the_genome() {
+        let fast = Genome {
+            speed: 1_f32,
+            ..Genome::default()
+        };
+        let mut animal = Entity::with_genome([0_f32; 3], EntityType::Animal1, fast);
+        animal.target = Some((1_f32, 1_f32));
+
+        animal.walk();
+        assert_eq!(animal.position, [1_f32, 0_f32, 1_f32]);
+
+        let slow = Genome {
+            speed: 0.4_f32,
+            ..Genome::default()
+        };
+        let mut animal = Entity::with_genome([0_f32; 3], EntityType::Animal1, slow);
+        animal.target = Some((1_f32, 0_f32));
+
+        animal.walk();
+        animal.walk();
+        animal.walk();
+        assert_eq!(animal.position, [1_f32, 0_f32, 0_f32]);
+    }
+
+    #[test]
+    fn should_pay_for_the_costly_traits() {
+        let height_map = [[1_f32; 25]; 25];
+        let tree_plants = TreeEntity::new(Entity::new([-100_f32; 3], EntityType::Plant1));
+        let costly = Genome {
+            speed: 0.75_f32,
+            sight: 1.5_f32,
+            ..Genome::default()
+        };
+
+        let mut animals = [
+            Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal2),
+            Entity::with_genome([5_f32, 0_f32, 5_f32], EntityType::Animal2, costly),
+        ];
+        for animal in animals.iter_mut() {
+            animal.change_mode(
+                &height_map,
+                &tree_plants,
+                &vec![],
+                &Environment::default(),
+                &mut rand::thread_rng(),
+            );
+        }
+
+        let spent = animals.map(|animal| animal.get_max_energy() - animal.energy);
+        assert!(spent[1] > spent[0]);
+        assert!(animals[1].get_sight_radius() > animals[0].get_sight_radius());
+    }
+
+    #[test]
+    fn should_store_more_energy_when_bigger() {
+        let big = Genome {
+            size: 1.5_f32,
+            ..Genome::default()
+        };
+        let animal = Entity::with_genome([0_f32; 3], EntityType::Animal2, big);
+
+        assert_eq!(
+            animal.get_max_energy(),
+            EntityType::Animal2.species().max_energy * 1.5_f32
+        );
+        assert_eq!(animal.energy, animal.get_max_energy());
+        assert_eq!(animal.get_scale(), 1.5_f32);
+        assert!(Genome::default().get_energy_ratio() < big.get_energy_ratio());
+    }
+
+    #[test]
+    fn should_mature_and_get_old_sooner_with_a_faster_metabolism() {
+        let fast = Genome {
+            metabolism: 1.5_f32,
+            ..Genome::default()
+        };
+        let mut animal = Entity::with_genome([0_f32; 3], EntityType::Animal2, fast);
+        let species = EntityType::Animal2.species();
+
+        animal.age = (species.maturity_age * 2).div_ceil(3);
+        assert!(animal.is_adult());
+
+        animal.age = (species.max_age * 2).div_ceil(3);
+        assert!(animal.is_too_old());
+    }
+}
+
+mod perception {
+    use super::*;
+
+    fn create_tree_plants() -> TreeEntity {
+        TreeEntity::new(Entity::new([-100_f32; 3], EntityType::Plant1))
+    }
+
+    #[test]
+    fn should_not_see_behind_a_hill() {
+        let predator = Entity::new([5_f32, 1_f32, 5_f32], EntityType::Animal1);
+        let prey = Entity::new([10_f32, 1_f32, 5_f32], EntityType::Animal2);
+        let mut height_map = [[1_f32; 25]; 25];
+
+        assert!(predator.can_see(prey.position, &height_map));
+
+        height_map[7][5] = 4_f32;
+        assert!(!predator.can_see(prey.position, &height_map));
+
+        let perception = predator.perceive(&height_map, &create_tree_plants(), &[predator, prey]);
+        assert!(perception.entities.is_empty());
+        assert_eq!(perception.food, None);
+    }
+
+    #[test]
+    fn should_only_see_inside_the_view_cone() {
+        let mut predator = Entity::new([5_f32, 1_f32, 5_f32], EntityType::Animal1);
+        let behind = [3_f32, 1_f32, 5_f32];
+        let height_map = [[1_f32; 25]; 25];
+
+        assert!(!predator.can_see(behind, &height_map));
+        assert!(!predator.can_see([3_f32, 1_f32, 7_f32], &height_map));
+        assert!(predator.can_see([7_f32, 1_f32, 7_f32], &height_map));
+
+        predator.set_rotation(180_f32);
+        assert!(predator.can_see(behind, &height_map));
+
+        // The herbivores see all around them.
+        let prey = Entity::new([5_f32, 1_f32, 5_f32], EntityType::Animal2);
+        assert!(prey.can_see(behind, &height_map));
+    }
+
+    #[test]
+    fn should_list_the_visible_entities_nearest_first() {
+        let mut predator = Entity::new([5_f32, 1_f32, 5_f32], EntityType::Animal1);
+        predator.spend_energy(50_f32);
+        let far = Entity::new([9_f32, 1_f32, 5_f32], EntityType::Animal2);
+        let near = Entity::new([7_f32, 1_f32, 6_f32], EntityType::Animal2);
+        let height_map = [[1_f32; 25]; 25];
+
+        let perception =
+            predator.perceive(&height_map, &create_tree_plants(), &[predator, far, near]);
+
+        assert_eq!(perception.entities, vec![near, far]);
+        assert_eq!(perception.food, Some(near.position));
+    }
+
+    #[test]
+    fn should_not_look_for_food_when_fed() {
+        let predator = Entity::new([5_f32, 1_f32, 5_f32], EntityType::Animal1);
+        let prey = Entity::new([7_f32, 1_f32, 6_f32], EntityType::Animal2);
+        let height_map = [[1_f32; 25]; 25];
+
+        let perception = predator.perceive(&height_map, &create_tree_plants(), &[predator, prey]);
+
+        assert_eq!(perception.entities, vec![prey]);
+        assert_eq!(perception.food, None);
+    }
+
+    #[test]
+    fn should_find_the_visible_water() {
+        let animal = Entity::new([5_f32, 1_f32, 5_f32], EntityType::Animal2);
+        let mut height_map = [[1_f32; 25]; 25];
+        height_map[5][8] = 0.5_f32;
+        height_map[2][5] = 0.5_f32;
+        height_map[4][5] = 3_f32;
+
+        let perception = animal.perceive(&height_map, &create_tree_plants(), &[animal]);
+
+        assert_eq!(perception.water, Some([5_f32, WATER_LEVEL, 8_f32]));
+    }
+
+    #[test]
+    fn should_not_flee_from_a_hidden_predator() {
+        let mut prey = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal2);
+        let predator = Entity::new([9_f32, 0_f32, 5_f32], EntityType::Animal1);
+        let mut height_map = [[1_f32; 25]; 25];
+        height_map[7][5] = 4_f32;
+
+        prey.change_mode(
+            &height_map,
+            &create_tree_plants(),
+            &vec![prey, predator],
+            &Environment::default(),
+            &mut rand::thread_rng(),
+        );
+
+        assert_ne!(prey.entity_mode, EntityMode::Fleeing);
+    }
+}
@@ -1,9 +1,10 @@
 #[cfg(test)]
 mod test;
 
-use crate::scene::mesh_map::MeshMap;
+use super::Entity;
 
-use super::{Entity, EntityType};
+/// Margin added to the search radius when skipping the quadrants.
+const QUADRANT_MARGIN: f32 = 0.001_f32;
 
 #[derive(Debug, PartialEq)]
 pub struct TreeEntity {
@@ -20,15 +21,7 @@ impl TreeEntity {
     }
 
     pub fn add(&mut self, child: Entity) {
-        let leaf = match (
-            self.key.position[0] < child.position[0],
-            self.key.position[2] < child.position[2],
-        ) {
-            (true, true) => &mut self.leafs[0],
-            (true, false) => &mut self.leafs[1],
-            (false, true) => &mut self.leafs[2],
-            (false, false) => &mut self.leafs[3],
-        };
+        let leaf = &mut self.leafs[self.get_quadrant(child.position)];
 
         if let Some(c) = leaf {
             c.add(child);
@@ -37,40 +30,84 @@ impl TreeEntity {
         }
     }
 
-    pub fn draw(
-        &self,
-        mesh_map: &mut MeshMap,
-        frame: &mut glium::Frame,
-        uniforms: ([[f32; 4]; 4], [[f32; 4]; 4], [f32; 3]),
-        params: &glium::DrawParameters,
-    ) {
-        let mesh = match self.key.get_type() {
-            EntityType::Plant1 => &mut mesh_map.plant1,
-            EntityType::Plant2 => &mut mesh_map.plant2,
-            _ => return,
-        };
+    /// Get the index of the leaf where a position is stored.
+    ///
+    /// # Arguments
+    /// * `position` - The position.
+    fn get_quadrant(&self, position: [f32; 3]) -> usize {
+        match (
+            self.key.position[0] < position[0],
+            self.key.position[2] < position[2],
+        ) {
+            (true, true) => 0,
+            (true, false) => 1,
+            (false, true) => 2,
+            (false, false) => 3,
+        }
+    }
 
-        mesh.set_position(self.key.position);
-        mesh.set_rotation_y(self.key.rotation);
-        mesh.draw(
-            frame,
-            &glium::uniform! {
-                        view: uniforms.0,
-                        perspective: uniforms.1,
-                        u_light: uniforms.2,
-                        matrix: mesh.matrix,
-                        ambient_color: mesh.ambient,
-                        diffuse_color: mesh.diffuse,
-                        specular_color: mesh.specular,
-            },
-            params,
-        );
+    /// Remove the entity of a position that matches a filter (only the entities below it
+    /// are added again, in the same order, so the rest of the tree is kept).
+    ///
+    /// Returns the tree left (`None` when it had only the removed entity) and the removed
+    /// entity.
+    ///
+    /// # Arguments
+    /// * `position` - Position of the entity (ignoring the height).
+    /// * `filter` - Function to select the entity.
+    pub fn remove(
+        mut self,
+        position: [f32; 3],
+        filter: &dyn Fn(&Entity) -> bool,
+    ) -> (Option<TreeEntity>, Option<Entity>) {
+        let same_position =
+            self.key.position[0] == position[0] && self.key.position[2] == position[2];
 
-        for leaf in self.leafs.iter() {
-            if let Some(child) = leaf {
-                child.draw(mesh_map, frame, uniforms, params);
-            }
+        if same_position && filter(&self.key) {
+            let mut entities = self
+                .leafs
+                .iter()
+                .flatten()
+                .flat_map(|child| child.iter().copied());
+            let tree = entities.next().map(|first| {
+                let mut tree = TreeEntity::new(first);
+                for entity in entities {
+                    tree.add(entity);
+                }
+                tree
+            });
+
+            return (tree, Some(self.key));
         }
+
+        let quadrant = self.get_quadrant(position);
+        let removed = match self.leafs[quadrant].take() {
+            Some(child) => {
+                let (child, removed) = child.remove(position, filter);
+                self.leafs[quadrant] = child.map(Box::new);
+                removed
+            }
+            None => None,
+        };
+
+        (Some(self), removed)
+    }
+
+    /// Iterate over the entities of the tree (each node before its leafs).
+    pub fn iter(&self) -> impl Iterator<Item = &Entity> {
+        let mut nodes = vec![self];
+
+        std::iter::from_fn(move || {
+            let node = nodes.pop()?;
+            nodes.extend(
+                node.leafs
+                    .iter()
+                    .rev()
+                    .flatten()
+                    .map(|child| child.as_ref()),
+            );
+            Some(&node.key)
+        })
     }
 
     pub fn applly_function(&self, function: &dyn Fn(&TreeEntity)) {
@@ -89,14 +126,168 @@ impl TreeEntity {
             return true;
         }
 
-        for leaf in self.leafs.iter() {
-            if let Some(child) = leaf {
-                if child.collide(position) {
-                    return true;
+        self.get_leafs_near(position, 0_f32)
+            .any(|child| child.collide(position))
+    }
+
+    /// Apply a function that can change the entities of the tree.
+    ///
+    /// The function must not change the entity position, because the position is
+    /// used to order the tree.
+    ///
+    /// # Arguments
+    /// * `function` - Function applied on each entity.
+    pub fn for_each_mut(&mut self, function: &mut dyn FnMut(&mut Entity)) {
+        function(&mut self.key);
+
+        for child in self.leafs.iter_mut().flatten() {
+            child.for_each_mut(function);
+        }
+    }
+
+    /// Find the nearest entity (ignoring the height) that matches a filter.
+    ///
+    /// # Arguments
+    /// * `position` - Position used to compare the distances.
+    /// * `max_distance` - Entities farther than this distance are ignored.
+    /// * `filter` - Function to select the entities.
+    pub fn find_nearest(
+        &self,
+        position: [f32; 3],
+        max_distance: f32,
+        filter: &dyn Fn(&Entity) -> bool,
+    ) -> Option<Entity> {
+        let mut nearest = None;
+
+        if filter(&self.key) && self.key.distance(position) <= max_distance {
+            nearest = Some(self.key);
+        }
+
+        for child in self.get_leafs_near(position, max_distance) {
+            if let Some(entity) = child.find_nearest(position, max_distance, filter) {
+                let is_nearer = match nearest {
+                    Some(current) => entity.distance(position) < current.distance(position),
+                    None => true,
+                };
+
+                if is_nearer {
+                    nearest = Some(entity);
                 }
             }
         }
 
-        false
+        nearest
+    }
+
+    /// Find the nearest entity (ignoring the height) that matches a filter, to change it.
+    ///
+    /// # Arguments
+    /// * `position` - Position used to compare the distances.
+    /// * `max_distance` - Entities farther than this distance are ignored.
+    /// * `filter` - Function to select the entities.
+    pub fn find_nearest_mut(
+        &mut self,
+        position: [f32; 3],
+        max_distance: f32,
+        filter: &dyn Fn(&Entity) -> bool,
+    ) -> Option<&mut Entity> {
+        let nearest = self.find_nearest(position, max_distance, filter)?;
+
+        self.get_mut(nearest.position, &|entity| *entity == nearest)
+    }
+
+    /// Get the entity of a position that matches a filter (going down only through the
+    /// quadrants of the position).
+    ///
+    /// # Arguments
+    /// * `position` - Position of the entity (ignoring the height).
+    /// * `filter` - Function to select the entity.
+    fn get_mut(
+        &mut self,
+        position: [f32; 3],
+        filter: &dyn Fn(&Entity) -> bool,
+    ) -> Option<&mut Entity> {
+        let same_position =
+            self.key.position[0] == position[0] && self.key.position[2] == position[2];
+
+        if same_position && filter(&self.key) {
+            return Some(&mut self.key);
+        }
+
+        let quadrant = self.get_quadrant(position);
+        self.leafs[quadrant].as_mut()?.get_mut(position, filter)
+    }
+
+    /// Count the entities inside a radius (ignoring the height).
+    ///
+    /// # Arguments
+    /// * `position` - Center of the circle.
+    /// * `radius` - Radius of the circle.
+    pub fn count_near(&self, position: [f32; 3], radius: f32) -> usize {
+        let mut count = usize::from(self.key.distance(position) <= radius);
+
+        for child in self.get_leafs_near(position, radius) {
+            count += child.count_near(position, radius);
+        }
+
+        count
+    }
+
+    /// Get a copy of the entities inside a radius (ignoring the height).
+    ///
+    /// # Arguments
+    /// * `position` - Center of the circle.
+    /// * `radius` - Radius of the circle.
+    pub fn get_near(&self, position: [f32; 3], radius: f32) -> Vec<Entity> {
+        let mut entities = vec![];
+
+        if self.key.distance(position) <= radius {
+            entities.push(self.key);
+        }
+
+        for child in self.get_leafs_near(position, radius) {
+            entities.extend(child.get_near(position, radius));
+        }
+
+        entities
+    }
+
+    /// Get the children whose quadrant can have entities inside a radius (the others are skipped
+    /// by the searches).
+    ///
+    /// # Arguments
+    /// * `position` - Center of the circle.
+    /// * `radius` - Radius of the circle.
+    fn get_leafs_near(&self, position: [f32; 3], radius: f32) -> impl Iterator<Item = &TreeEntity> {
+        // Small margin for the rounding of the distances.
+        let radius = radius + QUADRANT_MARGIN;
+        let after_x = position[0] + radius > self.key.position[0];
+        let before_x = position[0] - radius <= self.key.position[0];
+        let after_z = position[2] + radius > self.key.position[2];
+        let before_z = position[2] - radius <= self.key.position[2];
+
+        // Same order of the quadrants used by `add`.
+        let quadrants = [
+            after_x && after_z,
+            after_x && before_z,
+            before_x && after_z,
+            before_x && before_z,
+        ];
+
+        self.leafs
+            .iter()
+            .zip(quadrants)
+            .filter(|(_, near)| *near)
+            .filter_map(|(leaf, _)| leaf.as_deref())
+    }
+
+    /// Count all the entities of the tree.
+    pub fn count(&self) -> usize {
+        1 + self
+            .leafs
+            .iter()
+            .flatten()
+            .map(|child| child.count())
+            .sum::<usize>()
     }
 }
@@ -1,4 +1,5 @@
 use super::*;
+use crate::scene::entity::EntityType;
 
 mod new {
     use super::*;
@@ -19,3 +20,209 @@ mod new {
         assert_eq!(tree.leafs, [None, None, None, None]);
     }
 }
+
+fn create_tree() -> TreeEntity {
+    let mut tree = TreeEntity::new(Entity::new([5_f32, 0_f32, 5_f32], EntityType::Plant1));
+    tree.add(Entity::new([1_f32, 0_f32, 1_f32], EntityType::Plant2));
+    tree.add(Entity::new([8_f32, 0_f32, 2_f32], EntityType::Plant1));
+    tree.add(Entity::new([9_f32, 0_f32, 9_f32], EntityType::Plant2));
+
+    tree
+}
+
+mod count {
+    use super::*;
+
+    #[test]
+    fn should_count_all_entities() {
+        assert_eq!(create_tree().count(), 4);
+    }
+
+    #[test]
+    fn should_count_entities_near_a_position() {
+        let tree = create_tree();
+
+        assert_eq!(tree.count_near([5_f32, 0_f32, 5_f32], 0_f32), 1);
+        assert_eq!(tree.count_near([8_f32, 0_f32, 8_f32], 5_f32), 2);
+        assert_eq!(tree.count_near([5_f32, 0_f32, 5_f32], 100_f32), 4);
+    }
+}
+
+mod find_nearest {
+    use super::*;
+
+    #[test]
+    fn should_find_nearest_entity() {
+        let tree = create_tree();
+
+        let nearest = tree.find_nearest([9_f32, 0_f32, 8_f32], 10_f32, &|_| true);
+
+        assert_eq!(nearest.unwrap().position, [9_f32, 0_f32, 9_f32]);
+    }
+
+    #[test]
+    fn should_use_the_filter() {
+        let tree = create_tree();
+
+        let nearest = tree.find_nearest([9_f32, 0_f32, 8_f32], 10_f32, &|entity| {
+            entity.get_type() == EntityType::Plant1
+        });
+
+        assert_eq!(nearest.unwrap().position, [5_f32, 0_f32, 5_f32]);
+    }
+
+    #[test]
+    fn should_ignore_far_entities() {
+        let tree = create_tree();
+
+        assert!(tree
+            .find_nearest([50_f32, 0_f32, 50_f32], 10_f32, &|_| true)
+            .is_none());
+    }
+}
+
+mod find_nearest_mut {
+    use super::*;
+
+    #[test]
+    fn should_change_the_nearest_entity() {
+        let mut tree = create_tree();
+
+        let entity = tree.find_nearest_mut([8_f32, 0_f32, 3_f32], 5_f32, &|_| true);
+        entity.unwrap().set_growth(0_f32);
+
+        let entity = tree.find_nearest([8_f32, 0_f32, 2_f32], 0_f32, &|_| true);
+        assert_eq!(entity.unwrap().get_growth(), 0_f32);
+        let entity = tree.find_nearest([5_f32, 0_f32, 5_f32], 0_f32, &|_| true);
+        assert_ne!(entity.unwrap().get_growth(), 0_f32);
+    }
+
+    #[test]
+    fn should_use_the_filter() {
+        let mut tree = create_tree();
+
+        let entity = tree.find_nearest_mut([9_f32, 0_f32, 8_f32], 10_f32, &|entity| {
+            entity.get_type() == EntityType::Plant1
+        });
+
+        assert_eq!(entity.unwrap().position, [5_f32, 0_f32, 5_f32]);
+    }
+
+    #[test]
+    fn should_ignore_far_entities() {
+        let mut tree = create_tree();
+
+        assert!(tree
+            .find_nearest_mut([50_f32, 0_f32, 50_f32], 10_f32, &|_| true)
+            .is_none());
+    }
+}
+
+mod get_near {
+    use super::*;
+
+    #[test]
+    fn should_find_the_same_entities_of_a_full_search() {
+        let positions: Vec<[f32; 3]> = (0..200)
+            .map(|i| [((i * 37) % 50) as f32, 0_f32, ((i * 53) % 40) as f32])
+            .collect();
+        let mut tree = TreeEntity::new(Entity::new(positions[0], EntityType::Plant1));
+        for position in positions.iter().skip(1) {
+            tree.add(Entity::new(*position, EntityType::Plant2));
+        }
+
+        for center in [
+            [10_f32, 0_f32, 10_f32],
+            [25_f32, 0_f32, 3_f32],
+            [49_f32, 0_f32, 39_f32],
+        ] {
+            let mut expected: Vec<[f32; 3]> = tree
+                .iter()
+                .filter(|entity| entity.distance(center) <= 6_f32)
+                .map(|entity| entity.position)
+                .collect();
+            let mut near: Vec<[f32; 3]> = tree
+                .get_near(center, 6_f32)
+                .iter()
+                .map(|entity| entity.position)
+                .collect();
+            expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            near.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+            assert_eq!(near, expected);
+            assert_eq!(tree.count_near(center, 6_f32), expected.len());
+        }
+    }
+}
+
+mod remove {
+    use super::*;
+
+    #[test]
+    fn should_remove_a_node_and_keep_the_entities_below_it() {
+        let tree = create_tree();
+
+        let (tree, removed) = tree.remove([1_f32, 0_f32, 1_f32], &|_| true);
+        let tree = tree.unwrap();
+
+        assert_eq!(removed.unwrap().position, [1_f32, 0_f32, 1_f32]);
+        assert_eq!(tree.count(), 3);
+        assert!(!tree.collide([1_f32, 0_f32, 1_f32]));
+        assert!(tree.collide([9_f32, 0_f32, 9_f32]));
+    }
+
+    #[test]
+    fn should_remove_the_root() {
+        let (tree, removed) = create_tree().remove([5_f32, 0_f32, 5_f32], &|_| true);
+        let tree = tree.unwrap();
+
+        assert_eq!(removed.unwrap().position, [5_f32, 0_f32, 5_f32]);
+        assert_eq!(tree.count(), 3);
+        for position in [
+            [1_f32, 0_f32, 1_f32],
+            [8_f32, 0_f32, 2_f32],
+            [9_f32, 0_f32, 9_f32],
+        ] {
+            assert!(tree.collide(position));
+        }
+    }
+
+    #[test]
+    fn should_use_the_filter() {
+        let (tree, removed) = create_tree().remove([9_f32, 0_f32, 9_f32], &|entity| {
+            entity.get_type() == EntityType::Plant1
+        });
+
+        assert_eq!(removed, None);
+        assert_eq!(tree.unwrap().count(), 4);
+    }
+
+    #[test]
+    fn should_leave_an_empty_tree() {
+        let tree = TreeEntity::new(Entity::new([0_f32; 3], EntityType::Plant1));
+
+        let (tree, removed) = tree.remove([0_f32; 3], &|_| true);
+
+        assert!(tree.is_none());
+        assert!(removed.is_some());
+    }
+}
+
+mod iter {
+    use super::*;
+
+    #[test]
+    fn should_visit_each_node_before_its_leafs() {
+        let positions: Vec<[f32; 3]> = create_tree().iter().map(|entity| entity.position).collect();
+
+        assert_eq!(
+            positions,
+            [
+                [5_f32, 0_f32, 5_f32],
+                [9_f32, 0_f32, 9_f32],
+                [8_f32, 0_f32, 2_f32],
+                [1_f32, 0_f32, 1_f32],
+            ]
+        );
+    }
+}
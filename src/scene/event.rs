@@ -0,0 +1,162 @@
+use std::fmt;
+
+use super::entity::behaviour::Condition;
+use super::entity::{EntityId, EntityMode};
+use super::{DeathCause, Entity, EntityType};
+
+/// What happened to an entity.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum EventKind {
+    /// The entity was added to the scene.
+    EntitySpawned,
+    /// The entity was removed from the scene.
+    EntityDespawned,
+    ModeChanged {
+        from: EntityMode,
+        to: EntityMode,
+        reason: Condition,
+    },
+    /// The animal had no cell to move to.
+    MoveBlocked,
+    Ate {
+        food: EntityType,
+    },
+    Drank,
+    /// The animal was born from two parents (it is also spawned).
+    Born,
+    /// The animal died (it is also despawned).
+    Died {
+        cause: DeathCause,
+    },
+}
+
+impl fmt::Display for EventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EventKind::ModeChanged { from, to, reason } => {
+                write!(f, "{from:?} -> {to:?} ({reason:?})")
+            }
+            EventKind::Ate { food } => write!(f, "Ate {food:?}"),
+            EventKind::Died { cause } => write!(f, "Died ({cause:?})"),
+            _ => write!(f, "{self:?}"),
+        }
+    }
+}
+
+/// An event of the simulation.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Event {
+    pub kind: EventKind,
+    pub entity_id: EntityId,
+    pub entity_type: EntityType,
+    /// Position of the entity when the event happened.
+    pub position: [f32; 3],
+    /// Tick of the scene when the event happened.
+    pub tick: u64,
+}
+
+impl Event {
+    /// Create an event of an entity.
+    ///
+    /// # Arguments
+    /// * `kind` - What happened.
+    /// * `entity` - The entity.
+    /// * `tick` - Tick of the scene.
+    pub fn new(kind: EventKind, entity: &Entity, tick: u64) -> Self {
+        Self {
+            kind,
+            entity_id: entity.get_id(),
+            entity_type: entity.get_type(),
+            position: entity.position,
+            tick,
+        }
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Tick {} - {:?} #{} at ({}, {}): {}",
+            self.tick,
+            self.entity_type,
+            self.entity_id,
+            self.position[0],
+            self.position[2],
+            self.kind
+        )
+    }
+}
+
+/// Identifier of a subscription to the events.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Subscription(usize);
+
+/// Filter of the events of a subscription.
+pub type EventFilter = Box<dyn Fn(&Event) -> bool>;
+
+struct Subscriber {
+    filter: EventFilter,
+    /// Events published since the last drain.
+    events: Vec<Event>,
+}
+
+/// Delivers the events of the simulation to the subscribers.
+///
+/// Each subscriber keeps the events that pass its filter until they are drained (usually
+/// once per frame) or until it unsubscribes, and the events are dropped when there is no
+/// subscriber.
+#[derive(Default)]
+pub struct EventBus {
+    /// Subscribers by subscription (`None` after unsubscribing, so the ids are not reused).
+    subscribers: Vec<Option<Subscriber>>,
+}
+
+impl EventBus {
+    /// Start keeping the events that pass a filter.
+    ///
+    /// # Arguments
+    /// * `filter` - Returns if the subscriber wants an event.
+    pub fn subscribe(&mut self, filter: impl Fn(&Event) -> bool + 'static) -> Subscription {
+        self.subscribers.push(Some(Subscriber {
+            filter: Box::new(filter),
+            events: vec![],
+        }));
+
+        Subscription(self.subscribers.len() - 1)
+    }
+
+    /// Stop keeping the events of a subscription (the events not drained are dropped).
+    ///
+    /// # Arguments
+    /// * `subscription` - The subscription.
+    pub fn unsubscribe(&mut self, subscription: Subscription) {
+        if let Some(subscriber) = self.subscribers.get_mut(subscription.0) {
+            *subscriber = None;
+        }
+    }
+
+    /// Send an event to the subscribers that want it.
+    ///
+    /// # Arguments
+    /// * `event` - The event.
+    pub fn publish(&mut self, event: Event) {
+        for subscriber in self.subscribers.iter_mut().flatten() {
+            if (subscriber.filter)(&event) {
+                subscriber.events.push(event);
+            }
+        }
+    }
+
+    /// Take the events of a subscription published since the last drain (in the order they
+    /// happened).
+    ///
+    /// # Arguments
+    /// * `subscription` - The subscription.
+    pub fn drain(&mut self, subscription: Subscription) -> Vec<Event> {
+        match self.subscribers.get_mut(subscription.0) {
+            Some(Some(subscriber)) => std::mem::take(&mut subscriber.events),
+            _ => vec![],
+        }
+    }
+}
@@ -0,0 +1,285 @@
+use rand::Rng;
+
+use super::entity::species::Habitat;
+use super::{DeathCause, Entity, EntityType, Scene};
+use crate::utils::height_map::LAND_VALUE;
+
+#[cfg(test)]
+mod test;
+
+/// Ticks that a plant burns before turning to ash.
+const BURN_TICKS: u32 = 5;
+/// Ticks until a plant of the same species grows again on the ash.
+const ASH_TICKS: u32 = 600;
+/// Max distance (in cells) where the fire jumps from a burning plant to another one.
+const SPREAD_RADIUS: f32 = 3_f32;
+/// Chance (between 0 and 1) of the fire jumping to each plant around a burning one on a tick
+/// (on dry soil, without wind and for a plant with flammability 1).
+const SPREAD_CHANCE: f32 = 0.1_f32;
+/// How much the wind helps the fire downwind (and holds it back upwind).
+const WIND_EFFECT: f32 = 0.8_f32;
+
+/// State of a cell on the fire process.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub enum FireCell {
+    #[default]
+    Unburnt,
+    /// The plant of the cell is burning.
+    Burning { ticks_left: u32 },
+    /// The plant burned, and a plant of the same species grows when the ash is gone.
+    Ash {
+        entity_type: EntityType,
+        ticks_left: u32,
+    },
+}
+
+impl<const W: usize, const H: usize> Scene<W, H> {
+    /// Start a fire on the plant of a cell.
+    ///
+    /// Returns if the fire started (there is a plant on the cell and it is not burnt).
+    ///
+    /// # Arguments
+    /// * `(x, z)` - The cell.
+    pub fn ignite(&mut self, (x, z): (usize, usize)) -> bool {
+        if x >= W || z >= H || self.fire_map[x][z] != FireCell::Unburnt {
+            return false;
+        }
+
+        if self.find_plant((x, z)).is_none() {
+            return false;
+        }
+
+        self.fire_map[x][z] = FireCell::Burning {
+            ticks_left: BURN_TICKS,
+        };
+        true
+    }
+
+    /// Strike a lightning on a random plant (starting a fire when the plant is not burnt).
+    ///
+    /// Returns the cell of the plant that caught fire.
+    pub fn strike_lightning(&mut self) -> Option<(usize, usize)> {
+        let count = self.get_plant_count();
+        if count == 0 {
+            return None;
+        }
+
+        let index = self.rng.gen_range(0..count);
+        let plant = self.iter_plants().nth(index)?;
+        let cell = Self::get_cell(plant.position);
+
+        self.ignite(cell).then_some(cell)
+    }
+
+    /// Run a tick of the fire: the lightnings start new fires, the fire spreads between the
+    /// plants, the burnt plants turn to ash and the plants grow again where the ash is gone.
+    ///
+    /// Returns the animals that burned.
+    pub(super) fn update_fire(&mut self) -> Vec<(EntityType, DeathCause)> {
+        if self.rng.gen_bool(self.weather.kind.get_lightning_chance()) {
+            self.strike_lightning();
+        }
+
+        self.spread_fire();
+        self.burn_plants();
+
+        self.burn_animals()
+    }
+
+    /// Set the fire on the plants around the burning ones (based on their flammability, the
+    /// soil moisture and the wind).
+    fn spread_fire(&mut self) {
+        let plants = match &self.plants {
+            Some(plants) => plants,
+            None => return,
+        };
+
+        let mut ignited = vec![];
+        for x in 0..W {
+            for z in 0..H {
+                if !matches!(self.fire_map[x][z], FireCell::Burning { .. }) {
+                    continue;
+                }
+
+                let from = [x as f32, 0_f32, z as f32];
+                for plant in plants.get_near(from, SPREAD_RADIUS) {
+                    let (plant_x, plant_z) = Self::get_cell(plant.position);
+                    if self.fire_map[plant_x][plant_z] != FireCell::Unburnt {
+                        continue;
+                    }
+
+                    let chance = get_spread_chance(
+                        from,
+                        plant.position,
+                        plant.get_type().species().flammability,
+                        self.moisture_map[plant_x][plant_z],
+                        self.weather.wind,
+                    );
+                    if self.rng.gen::<f32>() < chance {
+                        ignited.push((plant_x, plant_z));
+                    }
+                }
+            }
+        }
+
+        for cell in ignited {
+            self.ignite(cell);
+        }
+    }
+
+    /// Burn the plants of the burning cells (they turn to ash when the fire ends) and grow the
+    /// plants again where the ash is gone.
+    fn burn_plants(&mut self) {
+        let mut burnt = vec![];
+        let mut regrown = vec![];
+
+        for x in 0..W {
+            for z in 0..H {
+                self.fire_map[x][z] = match self.fire_map[x][z] {
+                    FireCell::Burning { ticks_left } if ticks_left > 1 => FireCell::Burning {
+                        ticks_left: ticks_left - 1,
+                    },
+                    FireCell::Burning { .. } => {
+                        burnt.push((x, z));
+                        FireCell::Unburnt
+                    }
+                    FireCell::Ash {
+                        entity_type,
+                        ticks_left,
+                    } if ticks_left > 1 => FireCell::Ash {
+                        entity_type,
+                        ticks_left: ticks_left - 1,
+                    },
+                    FireCell::Ash { entity_type, .. } => {
+                        regrown.push((x, z, entity_type));
+                        FireCell::Unburnt
+                    }
+                    FireCell::Unburnt => FireCell::Unburnt,
+                };
+            }
+        }
+
+        for (x, z) in burnt {
+            // The plant may be gone already (under a lake that filled).
+            if let Some(plant) = self.find_plant((x, z)) {
+                self.despawn(plant.get_id());
+                self.fire_map[x][z] = FireCell::Ash {
+                    entity_type: plant.get_type(),
+                    ticks_left: ASH_TICKS,
+                };
+            }
+        }
+
+        for (x, z, entity_type) in regrown {
+            if self.height_map[x][z] >= LAND_VALUE && !self.collides((x, z)) {
+                let mut seedling = self.create_entity((x as isize, z as isize), entity_type);
+                seedling.set_growth(0_f32);
+                self.add_entity(seedling);
+            }
+        }
+    }
+
+    /// Kill the animals on or next to the burning cells (the ones flying and the aquatic ones
+    /// are safe).
+    fn burn_animals(&mut self) -> Vec<(EntityType, DeathCause)> {
+        let causes: Vec<Option<DeathCause>> = self
+            .animals
+            .iter()
+            .map(|animal| {
+                let aquatic = matches!(animal.get_type().species().habitat, Habitat::Water { .. });
+                let burning = !animal.is_flying() && !aquatic && self.is_near_fire(animal);
+
+                burning.then_some(DeathCause::Burned)
+            })
+            .collect();
+
+        self.remove_animals(&causes)
+    }
+
+    /// Indicate if the cell of an animal or one of its neighbor cells is burning.
+    ///
+    /// # Arguments
+    /// * `animal` - The animal.
+    fn is_near_fire(&self, animal: &Entity) -> bool {
+        let (x, z) = Self::get_cell(animal.position);
+
+        animal
+            .get_neighbors()
+            .into_iter()
+            .filter(|(n_x, n_z)| *n_x >= 0 && *n_z >= 0 && *n_x < W as isize && *n_z < H as isize)
+            .map(|(n_x, n_z)| (n_x as usize, n_z as usize))
+            .chain([(x, z)])
+            .any(|(x, z)| matches!(self.fire_map[x][z], FireCell::Burning { .. }))
+    }
+
+    /// Find the plant of a cell.
+    ///
+    /// # Arguments
+    /// * `(x, z)` - The cell.
+    fn find_plant(&self, (x, z): (usize, usize)) -> Option<Entity> {
+        self.plants
+            .as_ref()?
+            .find_nearest([x as f32, 0_f32, z as f32], 0_f32, &|_| true)
+    }
+
+    /// Indicate if the cell of a position is burning.
+    ///
+    /// # Arguments
+    /// * `position` - The position.
+    pub fn is_burning(&self, position: [f32; 3]) -> bool {
+        let (x, z) = Self::get_cell(position);
+
+        matches!(self.fire_map[x][z], FireCell::Burning { .. })
+    }
+
+    /// Positions (on the ground) of the cells with a state of the fire.
+    ///
+    /// # Arguments
+    /// * `filter` - Returns if the state of a cell is wanted.
+    pub fn get_fire_cells(&self, filter: impl Fn(&FireCell) -> bool) -> Vec<[f32; 3]> {
+        let mut cells = vec![];
+        for x in 0..W {
+            for z in 0..H {
+                if filter(&self.fire_map[x][z]) {
+                    cells.push([x as f32, self.height_map[x][z], z as f32]);
+                }
+            }
+        }
+
+        cells
+    }
+
+    /// Positions (on the ground) of the burning cells.
+    pub fn get_burning_cells(&self) -> Vec<[f32; 3]> {
+        self.get_fire_cells(|cell| matches!(cell, FireCell::Burning { .. }))
+    }
+}
+
+/// Chance (between 0 and 1) of the fire jumping from a burning plant to another one on a tick.
+///
+/// # Arguments
+/// * `from` - Position of the burning plant.
+/// * `to` - Position of the plant that may catch fire.
+/// * `flammability` - Flammability of the species of the plant.
+/// * `moisture` - Soil moisture (between 0 and 1) of the plant cell.
+/// * `wind` - Wind of the weather.
+fn get_spread_chance(
+    from: [f32; 3],
+    to: [f32; 3],
+    flammability: f32,
+    moisture: f32,
+    wind: [f32; 2],
+) -> f32 {
+    let delta_x = to[0] - from[0];
+    let delta_z = to[2] - from[2];
+    let distance = (delta_x * delta_x + delta_z * delta_z).sqrt();
+    if distance == 0_f32 {
+        return 0_f32;
+    }
+
+    // Positive when the plant is downwind of the fire.
+    let downwind = (delta_x * wind[0] + delta_z * wind[1]) / distance;
+    let wind_ratio = (1_f32 + WIND_EFFECT * downwind).max(0_f32);
+
+    (SPREAD_CHANCE * flammability * (1_f32 - moisture) * wind_ratio).clamp(0_f32, 1_f32)
+}
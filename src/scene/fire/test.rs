@@ -0,0 +1,170 @@
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use super::*;
+use crate::utils::height_map::init_height_map;
+
+/// Dry land (without wind) with a plant on each cell.
+///
+/// # Arguments
+/// * `cells` - Cells of the plants.
+fn dry_scene(cells: &[(usize, usize)]) -> Scene<12, 12> {
+    let mut scene = Scene::from_height_map(init_height_map(1.2_f32), ChaCha8Rng::seed_from_u64(1));
+    scene.moisture_map = [[0_f32; 12]; 12];
+    for (x, z) in cells {
+        scene.add_entity(Entity::new(
+            [*x as f32, 1.2_f32, *z as f32],
+            EntityType::Plant1,
+        ));
+    }
+
+    scene
+}
+
+mod ignite {
+    use super::*;
+
+    #[test]
+    fn should_only_set_fire_to_a_plant() {
+        let mut scene = dry_scene(&[(4, 4)]);
+
+        assert!(!scene.ignite((5, 5)));
+        assert!(scene.ignite((4, 4)));
+        assert!(!scene.ignite((4, 4)));
+        assert!(scene.is_burning([4_f32, 1.2_f32, 4_f32]));
+    }
+
+    #[test]
+    fn should_strike_a_plant() {
+        let mut scene = dry_scene(&[(4, 4)]);
+
+        assert_eq!(scene.strike_lightning(), Some((4, 4)));
+        assert_eq!(dry_scene(&[]).strike_lightning(), None);
+    }
+}
+
+mod update_fire {
+    use super::*;
+
+    #[test]
+    fn should_burn_the_plant_to_ash_and_grow_it_again() {
+        let mut scene = dry_scene(&[(4, 4)]);
+        scene.ignite((4, 4));
+
+        for _ in 0..BURN_TICKS {
+            scene.update_fire();
+        }
+
+        assert_eq!(scene.get_plant_count(), 0);
+        assert_eq!(
+            scene.fire_map[4][4],
+            FireCell::Ash {
+                entity_type: EntityType::Plant1,
+                ticks_left: ASH_TICKS,
+            }
+        );
+
+        for _ in 0..ASH_TICKS {
+            scene.update_fire();
+        }
+
+        let plants: Vec<Entity> = scene.iter_plants().copied().collect();
+        assert_eq!(plants.len(), 1);
+        assert_eq!(plants[0].get_growth(), 0_f32);
+        assert_eq!(scene.fire_map[4][4], FireCell::Unburnt);
+    }
+
+    #[test]
+    fn should_spread_to_the_dry_plants_nearby() {
+        let mut scene = dry_scene(&[(4, 4), (6, 3), (6, 4), (6, 5), (6, 10)]);
+        scene.weather.wind = [1_f32, 0_f32];
+        scene.ignite((4, 4));
+
+        for _ in 0..BURN_TICKS {
+            scene.update_fire();
+        }
+
+        assert!((3..=5).any(|z| scene.fire_map[6][z] != FireCell::Unburnt));
+        assert_eq!(scene.fire_map[6][10], FireCell::Unburnt);
+    }
+
+    #[test]
+    fn should_not_spread_on_wet_soil() {
+        let mut scene = dry_scene(&[(4, 4), (6, 4)]);
+        scene.moisture_map = [[1_f32; 12]; 12];
+        scene.ignite((4, 4));
+
+        for _ in 0..BURN_TICKS {
+            scene.update_fire();
+        }
+
+        assert_eq!(scene.fire_map[6][4], FireCell::Unburnt);
+        assert_eq!(scene.get_plant_count(), 1);
+    }
+
+    #[test]
+    fn should_burn_the_animals_on_the_fire() {
+        let mut scene = dry_scene(&[(4, 4)]);
+        scene.add_entity(Entity::new([4_f32, 1.2_f32, 4_f32], EntityType::Animal2));
+        scene.add_entity(Entity::new([8_f32, 1.2_f32, 8_f32], EntityType::Animal2));
+        scene.ignite((4, 4));
+
+        let deaths = scene.update_fire();
+
+        assert_eq!(deaths, vec![(EntityType::Animal2, DeathCause::Burned)]);
+        assert_eq!(scene.get_population(EntityType::Animal2), 1);
+    }
+
+    #[test]
+    fn should_burn_the_land_animals_next_to_the_fire() {
+        let mut scene = dry_scene(&[(4, 4)]);
+        scene.add_entity(Entity::new([5_f32, 1.2_f32, 5_f32], EntityType::Animal1));
+        scene.add_entity(Entity::new([3.5_f32, 1.2_f32, 4_f32], EntityType::Animal2));
+        scene.add_entity(Entity::new([6_f32, 1.2_f32, 4_f32], EntityType::Animal2));
+        scene.ignite((4, 4));
+
+        let deaths = scene.update_fire();
+
+        assert_eq!(
+            deaths,
+            vec![
+                (EntityType::Animal1, DeathCause::Burned),
+                (EntityType::Animal2, DeathCause::Burned),
+            ]
+        );
+        assert_eq!(scene.get_animals()[0].position, [6_f32, 1.2_f32, 4_f32]);
+    }
+
+    #[test]
+    fn should_not_burn_the_aquatic_animals() {
+        let mut scene = dry_scene(&[(4, 4)]);
+        scene.height_map[4][5] = -1_f32;
+        scene.add_entity(Entity::new([4_f32, 0_f32, 5_f32], EntityType::Fish));
+        scene.ignite((4, 4));
+
+        assert!(scene.update_fire().is_empty());
+        assert_eq!(scene.get_population(EntityType::Fish), 1);
+    }
+}
+
+mod get_spread_chance {
+    use super::*;
+
+    #[test]
+    fn should_spread_faster_downwind_and_on_dry_soil() {
+        let from = [4_f32, 1_f32, 4_f32];
+        let east = [6_f32, 1_f32, 4_f32];
+        let west = [2_f32, 1_f32, 4_f32];
+        let wind = [1_f32, 0_f32];
+
+        let downwind = get_spread_chance(from, east, 1_f32, 0_f32, wind);
+        let upwind = get_spread_chance(from, west, 1_f32, 0_f32, wind);
+        let calm = get_spread_chance(from, east, 1_f32, 0_f32, [0_f32; 2]);
+
+        assert!(downwind > calm && calm > upwind);
+        assert_eq!(calm, SPREAD_CHANCE);
+        assert!(get_spread_chance(from, east, 0.5_f32, 0_f32, wind) < downwind);
+        assert!(get_spread_chance(from, east, 1_f32, 0.5_f32, wind) < downwind);
+        assert_eq!(get_spread_chance(from, east, 1_f32, 1_f32, wind), 0_f32);
+    }
+}
@@ -0,0 +1,50 @@
+use super::EntityType;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DeathCause {
+    Eaten,
+    Starved,
+    Dehydrated,
+    OldAge,
+    /// A land animal caught by a lake that filled.
+    Drowned,
+    /// A water animal left on a lake that dried.
+    Stranded,
+    /// An animal caught by a fire.
+    Burned,
+}
+
+/// Births and deaths of a simulation step.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct LifeReport {
+    pub births: Vec<EntityType>,
+    pub deaths: Vec<(EntityType, DeathCause)>,
+}
+
+impl LifeReport {
+    /// Count the births of a type.
+    ///
+    /// # Arguments
+    /// * `entity_type` - Type of the entities.
+    pub fn count_births(&self, entity_type: EntityType) -> usize {
+        self.births
+            .iter()
+            .filter(|birth| **birth == entity_type)
+            .count()
+    }
+
+    /// Count the deaths of a type.
+    ///
+    /// # Arguments
+    /// * `entity_type` - Type of the entities.
+    pub fn count_deaths(&self, entity_type: EntityType) -> usize {
+        self.deaths
+            .iter()
+            .filter(|(death, _)| *death == entity_type)
+            .count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.births.is_empty() && self.deaths.is_empty()
+    }
+}
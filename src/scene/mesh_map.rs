@@ -1,8 +0,0 @@
-use crate::render::glium::mesh::Mesh;
-
-pub struct MeshMap {
-    pub plant1: Mesh,
-    pub plant2: Mesh,
-    pub animal1: Mesh,
-    pub animal2: Mesh,
-}
@@ -1,90 +1,286 @@
+mod cycle;
+mod day_cycle;
 mod entity;
-mod mesh_map;
+mod event;
+mod fire;
+mod life_report;
+mod movement_report;
+mod placement_error;
+mod replay;
+mod save;
+mod script;
+mod season;
 #[cfg(test)]
 mod test;
+mod vegetation;
+mod weather;
 
-use crate::render::glium::mesh::Mesh;
-use crate::render::obj_reader;
-use crate::utils::height_map::{create_land, init_height_map, smooth_height_map};
-use rand::Rng;
+use crate::utils::height_map::{
+    create_land, create_moisture_map, find_lakes, init_height_map, smooth_height_map,
+};
+use crate::utils::spatial_grid::SpatialGrid;
+use entity::behaviour::{Condition, Environment, Senses};
+use entity::genome::Genome;
+use entity::species::Habitat;
+use entity::{EntityMode, MoveReport};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rayon::prelude::*;
+use script::Scripts;
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
 
-pub use entity::{Entity, EntityType};
+pub use day_cycle::DayCycle;
+pub use entity::behaviour::ModeChange;
+pub use entity::genome::{TRAIT_COUNT, TRAIT_NAMES};
+pub use entity::{Entity, EntityId, EntityType, WATER_LEVEL};
+pub use event::{Event, EventBus, EventKind, Subscription};
+pub use fire::FireCell;
+pub use life_report::{DeathCause, LifeReport};
+pub use movement_report::MovementReport;
+pub use placement_error::PlacementError;
+pub use replay::{Input, Replay, ReplaySession};
+pub use season::{Season, SeasonCycle};
+pub use weather::{Weather, WeatherKind};
 
-use self::mesh_map::MeshMap;
+/// Distance (in cells) from the water where the soil becomes dry.
+const MOISTURE_DISTANCE: usize = 10;
+/// Fraction of the way to the moisture of the weather that the soil moves on each tick.
+const MOISTURE_CHANGE: f32 = 0.02_f32;
+/// Depth (below the water level) of the lake beds that are still under the water when the
+/// lakes are empty.
+const LAKE_DEPTH: f32 = 3_f32;
+/// Fraction of the plant growth kept in a dry cell.
+const DRY_GROWTH_RATIO: f32 = 0.25_f32;
+/// Size (in cells) of the buckets of the spatial index of the animals.
+const GRID_CELL_SIZE: f32 = 8_f32;
+/// Min radius of the neighbours of an animal (enough to check the cells around it).
+const NEIGHBOUR_RADIUS: f32 = 3_f32;
+/// Parameters of the FNV-1a hash used in the checksum.
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Where an entity is stored in the scene.
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum EntitySlot {
+    /// Index in the animals.
+    Animal(usize),
+    /// Position of the plant in the tree (the plants never move).
+    Plant([f32; 3]),
+}
 
 pub struct Scene<const W: usize, const H: usize> {
     width: usize,
     height: usize,
+    /// Height of each cell (the beds of the dry lake cells are raised to the water level).
     height_map: [[f32; H]; W],
-    mesh_map: MeshMap,
+    /// Cells of the lakes and the height of their beds.
+    lake_beds: Vec<((usize, usize), f32)>,
+    /// Soil moisture (between 0 and 1) on clear weather, based on the distance to the water.
+    base_moisture: [[f32; H]; W],
+    /// Soil moisture (between 0 and 1) of each cell, changed by the weather.
+    moisture_map: [[f32; H]; W],
+    weather: Weather,
+    /// State of each cell on the fire process.
+    fire_map: [[FireCell; H]; W],
+    /// Incremented each time the lakes change the height map.
+    terrain_version: u64,
     plants: Option<entity::tree_entity::TreeEntity>,
     animals: Vec<entity::Entity>,
+    last_movement: MovementReport,
+    events: EventBus,
+    /// Identifier of the next entity added to the scene.
+    next_id: EntityId,
+    /// Where each entity of the scene is stored.
+    slots: HashMap<EntityId, EntitySlot>,
+    scripts: Scripts,
+    /// Random generator of the simulation (the same seed creates the same island).
+    rng: ChaCha8Rng,
+    /// Simulation steps run since the scene was created.
+    tick: u64,
+    day_cycle: DayCycle,
+    season_cycle: SeasonCycle,
+}
+
+impl<const W: usize, const H: usize> Default for Scene<W, H> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<const W: usize, const H: usize> Scene<W, H> {
-    pub fn new(display: &glium::Display) -> Self {
+    pub fn new() -> Self {
+        Self::with_seed(rand::random())
+    }
+
+    /// Create a scene where all the random choices come from a seed.
+    ///
+    /// # Arguments
+    /// * `seed` - Seed of the random generator.
+    pub fn with_seed(seed: u64) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
         let mut height_map = init_height_map::<W, H>(-2_f32);
-        create_land(&mut height_map, (W as f32 * H as f32 * 0.75_f32) as usize);
+        create_land(
+            &mut height_map,
+            (W as f32 * H as f32 * 0.75_f32) as usize,
+            &mut rng,
+        );
+        let height_map = smooth_height_map(height_map);
+
+        Self::from_height_map(height_map, rng)
+    }
+
+    /// Create a scene without entities on a height map.
+    ///
+    /// # Arguments
+    /// * `height_map` - Height map of the island.
+    /// * `rng` - Random generator of the simulation.
+    fn from_height_map(height_map: [[f32; H]; W], rng: ChaCha8Rng) -> Self {
+        let mut scripts = Scripts::new(&height_map);
+        for entity_type in EntityType::ALL {
+            if let Some(path) = entity_type.species().script {
+                if let Err(err) = scripts.load_file(entity_type, path) {
+                    eprintln!("{err}");
+                }
+            }
+        }
+
+        let lake_beds = find_lakes(&height_map)
+            .into_iter()
+            .map(|(x, z)| ((x, z), height_map[x][z]))
+            .collect();
+        let base_moisture = create_moisture_map(&height_map, MOISTURE_DISTANCE);
 
         Self {
             width: W,
             height: H,
-            height_map: smooth_height_map(height_map),
+            height_map,
+            lake_beds,
+            base_moisture,
+            moisture_map: base_moisture,
+            weather: Weather::default(),
+            fire_map: [[FireCell::Unburnt; H]; W],
+            terrain_version: 0,
             plants: None,
             animals: vec![],
-            mesh_map: Self::load_mesh_map(display),
+            last_movement: MovementReport::default(),
+            events: EventBus::default(),
+            next_id: 1,
+            slots: HashMap::new(),
+            scripts,
+            rng,
+            tick: 0,
+            day_cycle: DayCycle::default(),
+            season_cycle: SeasonCycle::default(),
         }
     }
 
-    fn load_mesh_map(display: &glium::Display) -> MeshMap {
-        let plant1_obj = obj_reader::ObjReader::new("assets/plant1.obj").unwrap();
-        let plant2_obj = obj_reader::ObjReader::new("assets/plant2.obj").unwrap();
-        let animal1_obj = obj_reader::ObjReader::new("assets/animal1.obj").unwrap();
-        let animal2_obj = obj_reader::ObjReader::new("assets/animal2.obj").unwrap();
+    /// Load a script that runs on each behaviour tick of a species.
+    ///
+    /// # Arguments
+    /// * `entity_type` - The species.
+    /// * `path` - Path of the script file.
+    pub fn load_script(&mut self, entity_type: EntityType, path: &str) -> Result<(), String> {
+        self.scripts.load_file(entity_type, path)
+    }
 
-        // HACK: obj_reader does not read the diffuse and specular colors
-        // so i'm hard coding here.
-        let mut plant1 = Mesh::from_obj(plant1_obj.get_obj(), display);
-        const PLANT1_DIFFUSE: [f32; 3] = [0.237683, 0.640000, 0.010366];
-        const PLANT1_SPECULAR: [f32; 3] = [1.0, 1.0, 1.0];
-        plant1.set_diffuse(PLANT1_DIFFUSE);
-        plant1.set_specular(PLANT1_SPECULAR);
+    /// Create entities on random free cells (the plants follow the vegetation rules of the
+    /// species, spaced when possible and packed on the suitable cells left otherwise, and
+    /// the animals get a random age up to their maturity).
+    ///
+    /// Returns the amount of entities created, or an error with the amount created when
+    /// there is no space for all of them.
+    ///
+    /// # Arguments
+    /// * `quantity` - Amount of entities.
+    /// * `entity_type` - Type of the entities.
+    pub fn create_entities(
+        &mut self,
+        quantity: usize,
+        entity_type: EntityType,
+    ) -> Result<usize, PlacementError> {
+        let mut placed = 0;
+        if entity_type.is_plant() {
+            placed = self.place_plants(entity_type, quantity, false);
+        }
+
+        let mut free_cells = self.get_free_cells(entity_type);
+        if entity_type.is_plant() {
+            free_cells = self.get_suitable_cells(entity_type, free_cells);
+        }
 
-        let mut plant2 = Mesh::from_obj(plant2_obj.get_obj(), display);
-        const PLANT2_DIFFUSE: [f32; 3] = [0.009721, 0.479320, 0.003347];
-        const PLANT2_SPECULAR: [f32; 3] = [1.0, 1.0, 1.0];
-        plant2.set_diffuse(PLANT2_DIFFUSE);
-        plant2.set_specular(PLANT2_SPECULAR);
+        while placed < quantity && !free_cells.is_empty() {
+            let (x, z) = free_cells.swap_remove(self.rng.gen_range(0..free_cells.len()));
 
-        MeshMap {
-            plant1,
-            plant2,
-            animal1: Mesh::from_obj(animal1_obj.get_obj(), display),
-            animal2: Mesh::from_obj(animal2_obj.get_obj(), display),
+            let mut entity = self.create_entity((x as isize, z as isize), entity_type);
+            if !entity_type.is_plant() {
+                // The animals created together do not grow up (and old) on the same tick.
+                entity.set_age(self.rng.gen_range(0..=entity_type.species().maturity_age));
+            }
+            self.add_entity(entity);
+            placed += 1;
+        }
+
+        if placed < quantity {
+            return Err(PlacementError {
+                entity_type,
+                requested: quantity,
+                placed,
+            });
         }
+
+        Ok(placed)
     }
 
-    pub fn create_entities(&mut self, quantity: usize, entity_type: EntityType) {
-        for _ in 0..quantity {
-            let (x, z) = self.get_avaliable_position();
+    /// Create an entity on a cell (plants get a random rotation).
+    ///
+    /// # Arguments
+    /// * `(x, z)` - The cell.
+    /// * `entity_type` - Type of the entity.
+    fn create_entity(&mut self, (x, z): (isize, isize), entity_type: EntityType) -> Entity {
+        let rotation = if entity_type.is_plant() {
+            self.rng.gen_range(0_f32..360_f32)
+        } else {
+            0_f32
+        };
 
-            self.add_entity(Entity::new([x as f32, 0_f32, z as f32], entity_type));
-        }
+        Entity::with_rotation([x as f32, 0_f32, z as f32], rotation, entity_type)
     }
 
-    fn get_avaliable_position(&self) -> (usize, usize) {
-        let mut rand = rand::thread_rng();
+    /// Get the cells of the habitat of a species without any entity (the flying species get
+    /// the plants without an animal, where they perch).
+    ///
+    /// # Arguments
+    /// * `entity_type` - The species.
+    fn get_free_cells(&self, entity_type: EntityType) -> Vec<(usize, usize)> {
+        let habitat = entity_type.species().habitat;
+        let cell = |position: [f32; 3]| Self::get_cell(position);
+        let mut occupied: HashSet<(usize, usize)> = self
+            .animals
+            .iter()
+            .map(|animal| cell(animal.position))
+            .collect();
+        if let Habitat::Air { .. } = habitat {
+            return self
+                .iter_plants()
+                .map(|plant| cell(plant.position))
+                .filter(|perch| !occupied.contains(perch))
+                .collect();
+        }
 
-        let mut x = rand.gen_range(0..self.width);
-        let mut z = rand.gen_range(0..self.height);
+        occupied.extend(self.iter_plants().map(|plant| cell(plant.position)));
 
-        while self.height_map[x][z] < crate::utils::height_map::LAND_VALUE && !self.collides((x, z))
-        {
-            x = rand.gen_range(0..self.width);
-            z = rand.gen_range(0..self.height);
+        let mut free_cells = vec![];
+        for x in 0..W {
+            for z in 0..H {
+                if habitat.contains(self.height_map[x][z]) && !occupied.contains(&(x, z)) {
+                    free_cells.push((x, z));
+                }
+            }
         }
 
-        (x, z)
+        free_cells
     }
 
     fn collides(&self, (x, z): (usize, usize)) -> bool {
@@ -103,13 +299,94 @@ impl<const W: usize, const H: usize> Scene<W, H> {
         false
     }
 
-    pub fn add_entity(&mut self, mut entity: Entity) {
+    /// Add an entity to the scene (with a new identifier).
+    ///
+    /// # Arguments
+    /// * `entity` - The entity.
+    pub fn add_entity(&mut self, mut entity: Entity) -> EntityId {
+        let id = self.next_id;
+        self.next_id += 1;
+        entity.set_id(id);
+
         self.fix_position(&mut entity);
-        match entity.get_type() {
-            EntityType::Plant1 | EntityType::Plant2 => {
-                self.add_plant(entity);
+        self.events
+            .publish(Event::new(EventKind::EntitySpawned, &entity, self.tick));
+        self.insert_entity(entity);
+
+        id
+    }
+
+    /// Store an entity that already has an identifier.
+    ///
+    /// # Arguments
+    /// * `entity` - The entity.
+    fn insert_entity(&mut self, entity: Entity) {
+        if entity.get_type().is_plant() {
+            self.slots
+                .insert(entity.get_id(), EntitySlot::Plant(entity.position));
+            self.add_plant(entity);
+        } else {
+            self.slots
+                .insert(entity.get_id(), EntitySlot::Animal(self.animals.len()));
+            self.animals.push(entity);
+        }
+    }
+
+    /// Get a copy of an entity.
+    ///
+    /// # Arguments
+    /// * `id` - Identifier of the entity.
+    pub fn get_entity(&self, id: EntityId) -> Option<Entity> {
+        match self.slots.get(&id)? {
+            EntitySlot::Animal(index) => Some(self.animals[*index]),
+            EntitySlot::Plant(position) => {
+                self.plants
+                    .as_ref()?
+                    .find_nearest(*position, 0_f32, &|plant| plant.get_id() == id)
             }
-            EntityType::Animal1 | EntityType::Animal2 => self.animals.push(entity),
+        }
+    }
+
+    /// Remove an entity from the scene.
+    ///
+    /// Returns the removed entity, or `None` when there is no entity with the identifier.
+    ///
+    /// # Arguments
+    /// * `id` - Identifier of the entity.
+    pub fn despawn(&mut self, id: EntityId) -> Option<Entity> {
+        let entity = match self.slots.remove(&id)? {
+            EntitySlot::Animal(index) => {
+                let animal = self.animals.remove(index);
+                self.index_animals_slots();
+                animal
+            }
+            EntitySlot::Plant(position) => {
+                let (plants, plant) = self
+                    .plants
+                    .take()?
+                    .remove(position, &|plant| plant.get_id() == id);
+                self.plants = plants;
+                plant?
+            }
+        };
+
+        self.events
+            .publish(Event::new(EventKind::EntityDespawned, &entity, self.tick));
+        Some(entity)
+    }
+
+    /// Iterate over all the entities (the plants and then the animals).
+    pub fn iter_entities(&self) -> impl Iterator<Item = &Entity> + '_ {
+        self.iter_plants().chain(self.animals.iter())
+    }
+
+    /// Update the slots of the animals (after the animals were removed or reordered).
+    fn index_animals_slots(&mut self) {
+        self.slots
+            .retain(|_, slot| matches!(slot, EntitySlot::Plant(_)));
+        for (index, animal) in self.animals.iter().enumerate() {
+            self.slots
+                .insert(animal.get_id(), EntitySlot::Animal(index));
         }
     }
 
@@ -119,7 +396,8 @@ impl<const W: usize, const H: usize> Scene<W, H> {
         let valid_x = x >= 0_f32 && x < self.width as f32;
         let valid_z = z >= 0_f32 && z < self.height as f32;
         if valid_x && valid_z {
-            entity.position[1] = self.height_map[x as usize][z as usize];
+            let habitat = entity.get_type().species().habitat;
+            entity.set_height(habitat.get_height(self.height_map[x as usize][z as usize]));
         }
     }
 
@@ -131,63 +409,787 @@ impl<const W: usize, const H: usize> Scene<W, H> {
         }
     }
 
-    pub fn get_height_map_mesh(&self, display: &glium::Display) -> Mesh {
-        let mesh_colors = (
-            [0.25_f32, 0.25_f32, 0.25_f32],
-            [1_f32, 0_f32, 0_f32],
-            [0_f32, 0_f32, 0_f32],
-        );
-        crate::render::glium::util::height_map_to_mesh(self.height_map, mesh_colors, display)
+    pub fn get_height_map(&self) -> &[[f32; H]; W] {
+        &self.height_map
+    }
+
+    pub fn get_animals(&self) -> &[Entity] {
+        &self.animals
+    }
+
+    /// Iterate over the plants (in the tree order), without copying the tree.
+    pub fn iter_plants(&self) -> impl Iterator<Item = &Entity> + '_ {
+        self.plants.iter().flat_map(|plants| plants.iter())
+    }
+
+    /// Run a simulation step (animals and plants).
+    pub fn update(&mut self) -> LifeReport {
+        self.tick += 1;
+
+        let mut deaths = self.update_weather();
+        deaths.extend(self.update_fire());
+        deaths.extend(self.move_animals());
+        self.resolve_grazing();
+        let births = self.resolve_mating();
+        self.grow_plants();
+
+        LifeReport { births, deaths }
+    }
+
+    /// Run the behaviour tick of all the animals.
+    ///
+    /// The animals read the state of the last tick (and the neighbours from a spatial index)
+    /// and write their new state on another buffer, so they are updated in parallel. Each
+    /// animal has its own random generator (from a seed of the tick), so the result does not
+    /// depend on the threads.
+    pub fn move_animals(&mut self) -> Vec<(EntityType, DeathCause)> {
+        let plants = Self::plants_or_empty(&self.plants);
+
+        let animals = std::mem::take(&mut self.animals);
+        let grid = Self::index_animals(&animals);
+        let height_map = &self.height_map;
+        let fires = self.get_burning_cells();
+        let environment = self.get_environment(&fires);
+        let tick_seed: u64 = self.rng.gen();
+
+        let (mut next_animals, mut reports): (Vec<Entity>, Vec<MoveReport>) = animals
+            .par_iter()
+            .enumerate()
+            .map(|(index, animal)| {
+                let mut rng = ChaCha8Rng::seed_from_u64(tick_seed);
+                rng.set_stream(index as u64);
+
+                let neighbours = Self::get_nearby_animals(&animals, &grid, animal);
+                let mut next = *animal;
+                let report =
+                    next.change_mode(height_map, plants, &neighbours, &environment, &mut rng);
+
+                (next, report)
+            })
+            .unzip();
+
+        // The scripts are not thread safe, so they run after the parallel update.
+        let mut failed_scripts = vec![];
+        let mut script_events = vec![];
+        for (animal, previous) in next_animals.iter_mut().zip(animals.iter()) {
+            if failed_scripts.contains(&animal.get_type()) {
+                continue;
+            }
+
+            match self.scripts.run(animal, height_map, &animals, plants) {
+                Ok(Some(action)) => {
+                    if let Some(mode) = action.mode.filter(|mode| *mode != animal.get_mode()) {
+                        let change = animal.set_mode(mode, Condition::Script, &Senses::default());
+                        script_events.push(Event::new(
+                            Self::get_mode_changed(change),
+                            animal,
+                            self.tick,
+                        ));
+                    }
+                    // A walk already started is finished first; the walk chosen on this tick
+                    // is replaced (and its climb energy given back) by the one of the script.
+                    let walking = previous.get_target().is_some();
+                    if let Some(target) = action.target.filter(|_| !walking) {
+                        let neighbours = Self::get_nearby_animals(&animals, &grid, previous);
+                        animal.cancel_move();
+                        animal.steer_to(target, height_map, plants, &neighbours);
+                    }
+                }
+                Ok(None) => (),
+                Err(err) => {
+                    eprintln!("{err}");
+                    failed_scripts.push(animal.get_type());
+                }
+            }
+        }
+
+        // A broken script is disabled to keep the simulation running.
+        for entity_type in failed_scripts {
+            self.scripts.remove(entity_type);
+        }
+
+        Self::resolve_conflicts(&animals, &mut next_animals, &mut reports);
+
+        let mut movement = MovementReport::default();
+        for (animal, report) in next_animals.iter().zip(reports) {
+            movement.add(report, animal.get_travelled_distance());
+
+            let kinds = [
+                report.mode_change.map(Self::get_mode_changed),
+                report.failed.then_some(EventKind::MoveBlocked),
+                report.drank.then_some(EventKind::Drank),
+            ];
+            for kind in kinds.into_iter().flatten() {
+                self.events.publish(Event::new(kind, animal, self.tick));
+            }
+        }
+        self.last_movement = movement;
+
+        for event in script_events {
+            self.events.publish(event);
+        }
+
+        self.animals = next_animals;
+        let deaths = Self::resolve_deaths(&mut self.animals, &mut self.events, self.tick);
+        self.index_animals_slots();
+
+        deaths
+    }
+
+    /// Get the event of a mode change.
+    ///
+    /// # Arguments
+    /// * `change` - The mode change.
+    fn get_mode_changed(change: ModeChange) -> EventKind {
+        EventKind::ModeChanged {
+            from: change.from,
+            to: change.to,
+            reason: change.reason,
+        }
+    }
+
+    /// State of the world seen by the animals on the current tick.
+    ///
+    /// # Arguments
+    /// * `fires` - Positions of the burning cells.
+    fn get_environment<'a>(&self, fires: &'a [[f32; 3]]) -> Environment<'a> {
+        Environment {
+            night: self.is_night(),
+            raining: self.weather.is_raining(),
+            fires,
+        }
+    }
+
+    /// Create a spatial index with the positions of the animals.
+    ///
+    /// # Arguments
+    /// * `animals` - The animals.
+    fn index_animals(animals: &[Entity]) -> SpatialGrid {
+        SpatialGrid::from_positions(
+            W,
+            H,
+            GRID_CELL_SIZE,
+            animals.iter().map(|animal| animal.position),
+        )
+    }
+
+    /// Get the tree of the plants, or a tree with only a plant far away from the island when
+    /// there is no plant (the tree can not be empty).
+    ///
+    /// # Arguments
+    /// * `plants` - Plants of the scene.
+    fn plants_or_empty(
+        plants: &Option<entity::tree_entity::TreeEntity>,
+    ) -> &entity::tree_entity::TreeEntity {
+        static EMPTY: OnceLock<entity::tree_entity::TreeEntity> = OnceLock::new();
+
+        plants.as_ref().unwrap_or_else(|| {
+            EMPTY.get_or_init(|| {
+                entity::tree_entity::TreeEntity::new(Entity::new(
+                    [-1000_f32; 3],
+                    EntityType::Plant1,
+                ))
+            })
+        })
+    }
+
+    /// Get a copy of the animals that an animal can see or bump into (including itself).
+    ///
+    /// # Arguments
+    /// * `animals` - Animals of the scene.
+    /// * `grid` - Spatial index of the animals.
+    /// * `animal` - The animal.
+    fn get_nearby_animals(animals: &[Entity], grid: &SpatialGrid, animal: &Entity) -> Vec<Entity> {
+        let radius = animal.get_sight_radius().max(NEIGHBOUR_RADIUS);
+
+        // Only the animals in range are sorted, so they keep the order of the scene.
+        let mut near: Vec<usize> = grid.iter_in_radius(animal.position, radius).collect();
+        near.sort_unstable();
+
+        near.into_iter().map(|index| animals[index]).collect()
+    }
+
+    /// Cancel the walks to the cells already taken on this tick.
+    ///
+    /// The animals that were already walking keep their cells, and the new walks are kept
+    /// in the order of the animals (the first one gets the cell).
+    ///
+    /// # Arguments
+    /// * `animals` - Animals on the last tick.
+    /// * `next_animals` - Animals on the new tick.
+    /// * `reports` - Move reports of the new tick.
+    fn resolve_conflicts(
+        animals: &[Entity],
+        next_animals: &mut [Entity],
+        reports: &mut [MoveReport],
+    ) {
+        let cell = |(x, z): (f32, f32)| (x as isize, z as isize);
+        let mut taken = HashSet::new();
+
+        for (animal, next) in animals.iter().zip(next_animals.iter()) {
+            if let (Some(_), Some(target)) = (animal.get_target(), next.get_target()) {
+                taken.insert(cell(target));
+            }
+        }
+
+        for (index, next) in next_animals.iter_mut().enumerate() {
+            if animals[index].get_target().is_some() {
+                continue;
+            }
+
+            if let Some(target) = next.get_target() {
+                if !taken.insert(cell(target)) {
+                    next.cancel_move();
+                    reports[index].failed = true;
+                    reports[index].collisions_avoided += 1;
+                }
+            }
+        }
+    }
+
+    /// Remove the animals caught by a predator, the ones that starved or dehydrated and the old ones.
+    ///
+    /// # Arguments
+    /// * `animals` - Animals of the scene.
+    /// * `events` - Where the meals and the deaths are published.
+    /// * `tick` - Tick of the scene.
+    fn resolve_deaths(
+        animals: &mut Vec<Entity>,
+        events: &mut EventBus,
+        tick: u64,
+    ) -> Vec<(EntityType, DeathCause)> {
+        let grid = Self::index_animals(animals);
+
+        let mut causes: Vec<Option<DeathCause>> = animals
+            .iter()
+            .map(|animal| {
+                if animal.is_starving() {
+                    Some(DeathCause::Starved)
+                } else if animal.is_dehydrated() {
+                    Some(DeathCause::Dehydrated)
+                } else if animal.is_too_old() {
+                    Some(DeathCause::OldAge)
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        for predator in 0..animals.len() {
+            // A predator only catches a prey when it is hungry.
+            if causes[predator].is_some() || !animals[predator].is_hungry() {
+                continue;
+            }
+
+            let near = grid.get_near(animals[predator].position, entity::CATCH_DISTANCE);
+            let prey = near.into_iter().find(|&prey| {
+                causes[prey].is_none()
+                    && animals[predator].get_type().hunts(animals[prey].get_type())
+                    && animals[predator].distance(animals[prey].position) <= entity::CATCH_DISTANCE
+            });
+
+            if let Some(prey) = prey {
+                causes[prey] = Some(DeathCause::Eaten);
+                let food = animals[prey].get_type();
+                animals[predator].feed(food.species().nutrition);
+                events.publish(Event::new(
+                    EventKind::Ate { food },
+                    &animals[predator],
+                    tick,
+                ));
+            }
+        }
+
+        Self::remove_dead(animals, &causes, events, tick)
+    }
+
+    /// Remove the dead animals, publishing their deaths.
+    ///
+    /// # Arguments
+    /// * `animals` - The animals.
+    /// * `causes` - Cause of the death of each animal (`None` for the ones alive).
+    /// * `events` - Where the deaths are published.
+    /// * `tick` - Tick of the scene.
+    fn remove_dead(
+        animals: &mut Vec<Entity>,
+        causes: &[Option<DeathCause>],
+        events: &mut EventBus,
+        tick: u64,
+    ) -> Vec<(EntityType, DeathCause)> {
+        let mut deaths = vec![];
+        for (animal, cause) in animals.iter().zip(causes.iter()) {
+            if let Some(cause) = *cause {
+                events.publish(Event::new(EventKind::Died { cause }, animal, tick));
+                events.publish(Event::new(EventKind::EntityDespawned, animal, tick));
+                deaths.push((animal.get_type(), cause));
+            }
+        }
+
+        let mut index = 0;
+        animals.retain(|_| {
+            let alive = causes[index].is_none();
+            index += 1;
+            alive
+        });
+
+        deaths
     }
 
-    pub fn move_animals(&mut self) {
-        let mut plants =
-            &entity::tree_entity::TreeEntity::new(Entity::new([-1000_f32; 3], EntityType::Plant1));
+    /// Remove the dead animals of the scene, publishing their deaths and updating the slots.
+    ///
+    /// # Arguments
+    /// * `causes` - Cause of the death of each animal (`None` for the ones alive).
+    fn remove_animals(&mut self, causes: &[Option<DeathCause>]) -> Vec<(EntityType, DeathCause)> {
+        let deaths = Self::remove_dead(&mut self.animals, causes, &mut self.events, self.tick);
+        self.index_animals_slots();
 
-        if let Some(p) = &self.plants {
-            plants = p
+        deaths
+    }
+
+    /// Create the offspring of the adults (of the same species) that met with enough energy
+    /// (the offspring inherit the traits of the parents, with mutations).
+    fn resolve_mating(&mut self) -> Vec<EntityType> {
+        let plants = Self::plants_or_empty(&self.plants);
+
+        let season = self.get_season();
+        let parents_count = self.animals.len();
+        let grid = Self::index_animals(&self.animals);
+        let mut mated = vec![false; parents_count];
+        let mut offspring = vec![];
+
+        for first in 0..parents_count {
+            let near = grid.get_near(self.animals[first].position, entity::CATCH_DISTANCE);
+
+            for second in near.into_iter().filter(|second| *second > first) {
+                if mated[first] || mated[second] {
+                    continue;
+                }
+
+                let breeding_seasons = self.animals[first].get_type().species().breeding_seasons;
+                if !breeding_seasons.contains(&season) {
+                    continue;
+                }
+
+                if !self.animals[first].can_mate(&self.animals[second]) {
+                    continue;
+                }
+
+                let parent = &self.animals[first];
+                let neighbours = Self::get_nearby_animals(&self.animals, &grid, parent);
+                if Self::is_crowded(parent, &neighbours) {
+                    continue;
+                }
+
+                let mut free_cells =
+                    if let Habitat::Air { .. } = parent.get_type().species().habitat {
+                        // The flying animals are born on the plants near their parents.
+                        plants
+                            .get_near(parent.position, parent.get_sight_radius())
+                            .into_iter()
+                            .map(|plant| (plant.position[0] as isize, plant.position[2] as isize))
+                            .collect()
+                    } else {
+                        parent.get_free_neighbors(&self.height_map, plants, &neighbours)
+                    };
+                free_cells.retain(|cell| !offspring.iter().any(|(x, z, _, _)| (*x, *z) == *cell));
+
+                if free_cells.is_empty() {
+                    continue;
+                }
+
+                let (x, z) = free_cells[self.rng.gen_range(0..free_cells.len())];
+                let entity_type = self.animals[first].get_type();
+                let genome = Genome::inherit(
+                    &self.animals[first].get_genome(),
+                    &self.animals[second].get_genome(),
+                    &mut self.rng,
+                );
+                offspring.push((x, z, entity_type, genome));
+
+                mated[first] = true;
+                mated[second] = true;
+            }
+        }
+
+        for (index, parent) in self.animals.iter_mut().enumerate() {
+            if mated[index] {
+                parent.spend_energy(parent.get_type().species().mating_cost);
+            }
+        }
+
+        let mut births = vec![];
+        for (x, z, entity_type, genome) in offspring {
+            // The offspring starts with the energy spent by its parents to mate.
+            let mut child = Entity::with_genome([x as f32, 0_f32, z as f32], entity_type, genome);
+            let mating_cost = entity_type.species().mating_cost;
+            child.spend_energy((child.get_max_energy() - 2_f32 * mating_cost).max(0_f32));
+            let id = self.add_entity(child);
+            if let Some(child) = self.get_entity(id) {
+                self.events
+                    .publish(Event::new(EventKind::Born, &child, self.tick));
+            }
+            births.push(entity_type);
+        }
+
+        births
+    }
+
+    /// Indicate if there are too many animals of the species around an animal for it to mate
+    /// (more than the species max density inside its sight radius).
+    ///
+    /// # Arguments
+    /// * `animal` - The animal.
+    /// * `neighbours` - Animals near it.
+    fn is_crowded(animal: &Entity, neighbours: &[Entity]) -> bool {
+        let sight_radius = animal.get_sight_radius();
+        let crowd = neighbours
+            .iter()
+            .filter(|other| {
+                other.get_type() == animal.get_type()
+                    && animal.distance(other.position) <= sight_radius
+            })
+            .count();
+
+        crowd as f32 > animal.get_type().species().max_density
+    }
+
+    /// Feed the herbivores that are eating a grown plant (a bite of the plant on each tick).
+    fn resolve_grazing(&mut self) {
+        let plants = match &mut self.plants {
+            Some(plants) => plants,
+            None => return,
         };
 
-        let animals = self.animals.clone();
+        let season = self.season_cycle.get_season(self.tick);
 
         for animal in self.animals.iter_mut() {
-            animal.change_mode(&self.height_map, plants, &animals);
+            if animal.get_mode() != EntityMode::Eating || animal.is_flying() {
+                continue;
+            }
+
+            let animal_type = animal.get_type();
+            let position = animal.position;
+            let plant = plants.find_nearest_mut(position, entity::CATCH_DISTANCE, &|plant| {
+                animal_type.hunts(plant.get_type()) && plant.is_edible()
+            });
+
+            // The plant is eaten a bite on each tick, until the animal is fed or the plant is
+            // not edible anymore (then the animal leaves the eating mode).
+            if let Some(plant) = plant {
+                let food = plant.get_type();
+                let bite = plant.get_growth().min(entity::BITE_GROWTH);
+                plant.set_growth(plant.get_growth() - bite);
+                animal.feed(food.species().nutrition * bite * season.get_nutrition_ratio());
+                self.events
+                    .publish(Event::new(EventKind::Ate { food }, animal, self.tick));
+            }
         }
     }
 
-    pub fn draw_entities(
-        &mut self,
-        frame: &mut glium::Frame,
-        uniforms: ([[f32; 4]; 4], [[f32; 4]; 4], [f32; 3]),
-        params: &glium::DrawParameters,
-    ) {
-        if let Some(plants) = &self.plants {
-            plants.draw(&mut self.mesh_map, frame, uniforms, params);
+    /// Run a tick of the weather: the lakes fill or dry, and the soil moisture moves to the
+    /// moisture of the weather.
+    fn update_weather(&mut self) -> Vec<(EntityType, DeathCause)> {
+        self.weather.update(&mut self.rng);
+        let deaths = self.update_lakes();
+
+        for x in 0..W {
+            for z in 0..H {
+                let target = self.weather.kind.get_moisture(self.base_moisture[x][z]);
+                self.moisture_map[x][z] += (target - self.moisture_map[x][z]) * MOISTURE_CHANGE;
+            }
         }
 
-        for animal in self.animals.iter() {
-            let mesh = match animal.get_type() {
-                EntityType::Animal1 => &mut self.mesh_map.animal1,
-                EntityType::Animal2 => &mut self.mesh_map.animal2,
-                _ => break,
-            };
-
-            mesh.set_position(animal.position);
-            mesh.set_rotation_y(animal.rotation);
-
-            mesh.draw(
-                frame,
-                &glium::uniform! {
-                    view: uniforms.0,
-                    perspective: uniforms.1,
-                    u_light: uniforms.2,
-                    matrix: mesh.matrix,
-                    ambient_color: mesh.ambient,
-                    diffuse_color: mesh.diffuse,
-                    specular_color: mesh.specular,
-                },
-                params,
-            )
+        deaths
+    }
+
+    /// Fill or dry the lake cells based on the lake fill, removing the plants under the new
+    /// water and the animals left out of their habitat.
+    fn update_lakes(&mut self) -> Vec<(EntityType, DeathCause)> {
+        let changed = self.apply_lake_fill();
+        if changed.is_empty() {
+            return vec![];
+        }
+
+        let flooded_plants: Vec<EntityId> = self
+            .iter_plants()
+            .filter(|plant| {
+                let (x, z) = Self::get_cell(plant.position);
+                changed.contains(&(x, z)) && self.height_map[x][z] < WATER_LEVEL
+            })
+            .map(|plant| plant.get_id())
+            .collect();
+        for id in flooded_plants {
+            self.despawn(id);
+        }
+
+        let causes: Vec<Option<DeathCause>> = self
+            .animals
+            .iter()
+            .map(|animal| {
+                let (x, z) = Self::get_cell(animal.position);
+                let habitat = animal.get_type().species().habitat;
+                if animal.is_flying()
+                    || !changed.contains(&(x, z))
+                    || habitat.contains(self.height_map[x][z])
+                {
+                    return None;
+                }
+
+                match habitat {
+                    Habitat::Land => Some(DeathCause::Drowned),
+                    _ => Some(DeathCause::Stranded),
+                }
+            })
+            .collect();
+
+        self.remove_animals(&causes)
+    }
+
+    /// Put the water of the lake fill on the lake cells (the beds above the water are raised
+    /// to the water level, so they become land).
+    ///
+    /// Returns the cells that changed.
+    fn apply_lake_fill(&mut self) -> HashSet<(usize, usize)> {
+        let level = WATER_LEVEL - (1_f32 - self.weather.lake_fill) * LAKE_DEPTH;
+
+        let mut changed = HashSet::new();
+        for &((x, z), bed) in self.lake_beds.iter() {
+            let height = if bed < level { bed } else { WATER_LEVEL };
+            if self.height_map[x][z] != height {
+                self.height_map[x][z] = height;
+                changed.insert((x, z));
+            }
+        }
+
+        if !changed.is_empty() {
+            self.base_moisture = create_moisture_map(&self.height_map, MOISTURE_DISTANCE);
+            self.scripts.set_height_map(&self.height_map);
+            self.terrain_version += 1;
+        }
+
+        changed
+    }
+
+    /// Height map with all the lakes full (used to save the scene).
+    fn get_bed_map(&self) -> [[f32; H]; W] {
+        let mut bed_map = self.height_map;
+        for &((x, z), bed) in self.lake_beds.iter() {
+            bed_map[x][z] = bed;
+        }
+
+        bed_map
+    }
+
+    /// Grow the plants (based on the soil moisture) and spread the seeds of the grown ones.
+    fn grow_plants(&mut self) {
+        let plants = match &mut self.plants {
+            Some(plants) => plants,
+            None => return,
+        };
+
+        let season = self.season_cycle.get_season(self.tick);
+        let moisture_map = &self.moisture_map;
+        let rand = &mut self.rng;
+        let mut seeds = vec![];
+
+        plants.for_each_mut(&mut |plant| {
+            let species = plant.get_type().species();
+            let moisture = Self::get_cell_value(moisture_map, plant.position);
+
+            if plant.get_growth() < 1_f32 {
+                let growth_ratio = DRY_GROWTH_RATIO + (1_f32 - DRY_GROWTH_RATIO) * moisture;
+                let growth_ratio = growth_ratio * season.get_growth_ratio();
+                plant.set_growth(plant.get_growth() + species.growth_rate * growth_ratio);
+            } else if rand
+                .gen_bool((species.spread_rate * season.get_spread_ratio()).min(1_f32) as f64)
+            {
+                let radius = species.spread_radius as isize;
+                let x = plant.position[0] as isize + rand.gen_range(-radius..=radius);
+                let z = plant.position[2] as isize + rand.gen_range(-radius..=radius);
+                seeds.push((x, z, plant.get_type()));
+            }
+        });
+
+        for (x, z, plant_type) in seeds {
+            if self.can_sprout((x, z), plant_type) {
+                let mut seedling = self.create_entity((x, z), plant_type);
+                seedling.set_growth(0_f32);
+                self.add_entity(seedling);
+            }
+        }
+    }
+
+    /// Indicate if a seed can sprout in a cell (free land that did not reach the carrying capacity).
+    ///
+    /// # Arguments
+    /// * `(x, z)` - Cell of the seed.
+    /// * `plant_type` - Type of the plant.
+    fn can_sprout(&self, (x, z): (isize, isize), plant_type: EntityType) -> bool {
+        let valid_x = x >= 0 && x < self.width as isize;
+        let valid_z = z >= 0 && z < self.height as isize;
+
+        if !valid_x || !valid_z {
+            return false;
+        }
+
+        let (x, z) = (x as usize, z as usize);
+        if self.height_map[x][z] < crate::utils::height_map::LAND_VALUE || self.collides((x, z)) {
+            return false;
+        }
+
+        let species = plant_type.species();
+        let position = [x as f32, 0_f32, z as f32];
+        let capacity = species.max_density * self.moisture_map[x][z];
+        let density = match &self.plants {
+            Some(plants) => plants.count_near(position, species.spread_radius),
+            None => 0,
+        };
+
+        (density as f32) < capacity
+    }
+
+    /// Get the cell of a position (truncated, like the neighbor cells of the animals, and
+    /// clamped to the map).
+    ///
+    /// # Arguments
+    /// * `position` - The position.
+    fn get_cell(position: [f32; 3]) -> (usize, usize) {
+        (
+            (position[0].max(0_f32) as usize).min(W - 1),
+            (position[2].max(0_f32) as usize).min(H - 1),
+        )
+    }
+
+    /// Get the value of a map in the cell of a position.
+    ///
+    /// # Arguments
+    /// * `map` - Map with a value per cell.
+    /// * `position` - The position (outside positions are clamped to the map).
+    fn get_cell_value(map: &[[f32; H]; W], position: [f32; 3]) -> f32 {
+        let (x, z) = Self::get_cell(position);
+
+        map[x][z]
+    }
+
+    /// Count the plants (of all types).
+    pub fn get_plant_count(&self) -> usize {
+        match &self.plants {
+            Some(plants) => plants.count(),
+            None => 0,
+        }
+    }
+
+    /// Count the entities of a type.
+    ///
+    /// # Arguments
+    /// * `entity_type` - Type of the entities.
+    pub fn get_population(&self, entity_type: EntityType) -> usize {
+        if entity_type.is_plant() {
+            return self
+                .iter_plants()
+                .filter(|plant| plant.get_type() == entity_type)
+                .count();
+        }
+
+        self.animals
+            .iter()
+            .filter(|animal| animal.get_type() == entity_type)
+            .count()
+    }
+
+    /// Movement of the animals in the last simulation step.
+    pub fn get_last_movement(&self) -> MovementReport {
+        self.last_movement
+    }
+
+    pub fn get_weather(&self) -> Weather {
+        self.weather
+    }
+
+    /// Counter of the changes of the height map (the lakes fill and dry with the weather).
+    pub fn get_terrain_version(&self) -> u64 {
+        self.terrain_version
+    }
+
+    pub fn get_tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub fn get_day_cycle(&self) -> DayCycle {
+        self.day_cycle
+    }
+
+    /// Change the length of the days.
+    ///
+    /// # Arguments
+    /// * `day_length` - Ticks of a day.
+    pub fn set_day_length(&mut self, day_length: u64) {
+        self.day_cycle = DayCycle::new(day_length);
+    }
+
+    pub fn get_season_cycle(&self) -> SeasonCycle {
+        self.season_cycle
+    }
+
+    /// Change the length of the seasons.
+    ///
+    /// # Arguments
+    /// * `season_length` - Ticks of a season.
+    pub fn set_season_length(&mut self, season_length: u64) {
+        self.season_cycle = SeasonCycle::new(season_length);
+    }
+
+    pub fn get_season(&self) -> Season {
+        self.season_cycle.get_season(self.tick)
+    }
+
+    /// Fraction of the day that already passed (0 is the sunrise and 0.5 the sunset).
+    pub fn get_time_of_day(&self) -> f32 {
+        self.day_cycle.get_time_of_day(self.tick)
+    }
+
+    pub fn is_night(&self) -> bool {
+        self.day_cycle.is_night(self.tick)
+    }
+
+    /// Start keeping the events of the simulation that pass a filter (they are kept until
+    /// drained).
+    ///
+    /// # Arguments
+    /// * `filter` - Returns if the subscriber wants an event.
+    pub fn subscribe(&mut self, filter: impl Fn(&Event) -> bool + 'static) -> Subscription {
+        self.events.subscribe(filter)
+    }
+
+    /// Stop keeping the events of a subscription.
+    ///
+    /// # Arguments
+    /// * `subscription` - The subscription.
+    pub fn unsubscribe(&mut self, subscription: Subscription) {
+        self.events.unsubscribe(subscription);
+    }
+
+    /// Take the events of a subscription published since the last drain.
+    ///
+    /// # Arguments
+    /// * `subscription` - The subscription.
+    pub fn drain_events(&mut self, subscription: Subscription) -> Vec<Event> {
+        self.events.drain(subscription)
+    }
+
+    /// Hash of the tick and the state of all the entities (used to check that a replay
+    /// runs the same simulation).
+    pub fn get_checksum(&self) -> u64 {
+        let records = self.iter_entities().map(|entity| entity.get_record());
+
+        let mut hash = FNV_OFFSET;
+        for record in std::iter::once(self.tick.to_string()).chain(records) {
+            for byte in record.bytes().chain(std::iter::once(b'\n')) {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(FNV_PRIME);
+            }
         }
+
+        hash
     }
 }
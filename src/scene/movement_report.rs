@@ -0,0 +1,24 @@
+use super::entity::MoveReport;
+
+/// Movement of all the animals in a simulation step.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct MovementReport {
+    pub distance_travelled: f32,
+    /// Walkable cells rejected because another entity was there.
+    pub collisions_avoided: usize,
+    /// Times that an animal had no cell to move to.
+    pub failed_moves: usize,
+}
+
+impl MovementReport {
+    /// Add the movement of an animal.
+    ///
+    /// # Arguments
+    /// * `report` - What happened when the animal chose its next cell.
+    /// * `distance` - Distance travelled by the animal.
+    pub fn add(&mut self, report: MoveReport, distance: f32) {
+        self.distance_travelled += distance;
+        self.collisions_avoided += report.collisions_avoided;
+        self.failed_moves += usize::from(report.failed);
+    }
+}
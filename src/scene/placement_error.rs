@@ -0,0 +1,27 @@
+use std::fmt;
+
+use super::EntityType;
+
+/// There was no free cell for some of the entities.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PlacementError {
+    pub entity_type: EntityType,
+    /// Amount of entities asked.
+    pub requested: usize,
+    /// Amount of entities created before the free cells ran out.
+    pub placed: usize,
+}
+
+impl fmt::Display for PlacementError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "There is only space for {} of {} {}",
+            self.placed,
+            self.requested,
+            self.entity_type.get_name()
+        )
+    }
+}
+
+impl std::error::Error for PlacementError {}
@@ -0,0 +1,377 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+
+use super::entity::record::parse_field;
+use super::{EntityId, EntityType, Scene};
+
+#[cfg(test)]
+mod test;
+
+/// First word of a replay.
+const REPLAY_HEADER: &str = "rusty-island-replay";
+/// Version of the format (increase it when the format changes).
+const REPLAY_VERSION: u32 = 1;
+
+/// External input of a simulation run (anything that does not come from the seed).
+#[derive(Clone, PartialEq, Debug)]
+pub enum Input {
+    /// Create entities of a type on random cells.
+    Spawn(EntityType, usize),
+    /// Remove an entity.
+    Despawn(EntityId),
+    /// Scatter the plants of a species by its vegetation rules.
+    Scatter(EntityType),
+    /// Load the behaviour script of a species from a file.
+    Script(EntityType, String),
+    /// Change the length of the days (in ticks).
+    DayLength(u64),
+    /// Change the length of the seasons (in ticks).
+    SeasonLength(u64),
+    /// Strike a lightning on a random plant.
+    Lightning,
+    /// Change the simulation ticks per second (it only changes the scene speed).
+    TickRate(f32),
+}
+
+impl Input {
+    /// Apply the input to the scene.
+    ///
+    /// # Arguments
+    /// * `scene` - The scene.
+    pub fn apply<const W: usize, const H: usize>(
+        &self,
+        scene: &mut Scene<W, H>,
+    ) -> Result<(), String> {
+        match self {
+            Input::Spawn(entity_type, quantity) => {
+                // A full island only limits the spawn (the run goes on the same way when it
+                // is replayed).
+                if let Err(err) = scene.create_entities(*quantity, *entity_type) {
+                    eprintln!("{err}");
+                }
+            }
+            Input::Despawn(id) => {
+                if scene.despawn(*id).is_none() {
+                    eprintln!("There is no entity {id}");
+                }
+            }
+            Input::Scatter(entity_type) => {
+                scene.scatter_plants(*entity_type);
+            }
+            Input::Script(entity_type, path) => scene.load_script(*entity_type, path)?,
+            Input::DayLength(day_length) => scene.set_day_length(*day_length),
+            Input::SeasonLength(season_length) => scene.set_season_length(*season_length),
+            Input::Lightning => {
+                scene.strike_lightning();
+            }
+            Input::TickRate(_) => (),
+        }
+
+        Ok(())
+    }
+
+    fn to_line(&self) -> String {
+        match self {
+            Input::Spawn(entity_type, quantity) => {
+                format!("spawn {} {quantity}", entity_type.get_name())
+            }
+            Input::Despawn(id) => format!("despawn {id}"),
+            Input::Scatter(entity_type) => format!("scatter {}", entity_type.get_name()),
+            Input::Script(entity_type, path) => format!("script {} {path}", entity_type.get_name()),
+            Input::DayLength(day_length) => format!("day_length {day_length}"),
+            Input::SeasonLength(season_length) => format!("season_length {season_length}"),
+            Input::Lightning => "lightning".to_string(),
+            Input::TickRate(tick_rate) => format!("tick_rate {tick_rate}"),
+        }
+    }
+
+    fn from_line(line: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = line.splitn(3, ' ').collect();
+        let entity_type = || {
+            EntityType::from_name(fields[1]).ok_or(format!("Unknown entity type: {}", fields[1]))
+        };
+
+        match fields[..] {
+            ["spawn", _, quantity] => Ok(Input::Spawn(entity_type()?, parse_field(quantity)?)),
+            ["despawn", id] => Ok(Input::Despawn(parse_field(id)?)),
+            ["scatter", _] => Ok(Input::Scatter(entity_type()?)),
+            ["script", _, path] => Ok(Input::Script(entity_type()?, path.to_string())),
+            ["day_length", day_length] => Ok(Input::DayLength(parse_field(day_length)?)),
+            ["season_length", season_length] => {
+                Ok(Input::SeasonLength(parse_field(season_length)?))
+            }
+            ["lightning"] => Ok(Input::Lightning),
+            ["tick_rate", tick_rate] => Ok(Input::TickRate(parse_field(tick_rate)?)),
+            _ => Err(format!("Invalid input: {line}")),
+        }
+    }
+}
+
+/// Seed and external inputs of a run, with the checksum of each tick.
+///
+/// The run starts from a new scene created with the seed and the inputs are applied before
+/// the update of their tick, so replaying it runs the same simulation tick for tick.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Replay {
+    seed: u64,
+    /// Inputs with the tick where they were applied.
+    inputs: Vec<(u64, Input)>,
+    /// Checksum of the scene after each tick (starting on tick 1).
+    checksums: Vec<u64>,
+}
+
+impl Replay {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            inputs: vec![],
+            checksums: vec![],
+        }
+    }
+
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Amount of ticks recorded.
+    pub fn get_ticks(&self) -> u64 {
+        self.checksums.len() as u64
+    }
+
+    /// Record an input applied before the update of a tick.
+    ///
+    /// # Arguments
+    /// * `tick` - Tick of the scene when the input was applied.
+    /// * `input` - The input.
+    pub fn record_input(&mut self, tick: u64, input: Input) {
+        self.inputs.push((tick, input));
+    }
+
+    /// Record the checksum of the next tick.
+    ///
+    /// # Arguments
+    /// * `checksum` - Checksum of the scene after the tick.
+    pub fn record_checksum(&mut self, checksum: u64) {
+        self.checksums.push(checksum);
+    }
+
+    /// Inputs applied before the update of a tick.
+    ///
+    /// # Arguments
+    /// * `tick` - Tick of the scene.
+    pub fn get_inputs(&self, tick: u64) -> impl Iterator<Item = &Input> {
+        self.inputs
+            .iter()
+            .filter(move |(input_tick, _)| *input_tick == tick)
+            .map(|(_, input)| input)
+    }
+
+    /// Compare the checksum of a tick with the recorded one (ticks after the end are not checked).
+    ///
+    /// # Arguments
+    /// * `tick` - Tick of the scene.
+    /// * `checksum` - Checksum of the scene.
+    pub fn check(&self, tick: u64, checksum: u64) -> Result<(), String> {
+        let expected = match tick
+            .checked_sub(1)
+            .and_then(|i| self.checksums.get(i as usize))
+        {
+            Some(expected) => *expected,
+            None => return Ok(()),
+        };
+
+        if expected != checksum {
+            return Err(format!(
+                "The replay diverged on tick {tick} (checksum {checksum:016x}, expected {expected:016x})"
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Write the replay.
+    ///
+    /// # Arguments
+    /// * `writer` - Where the replay is written.
+    pub fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        writeln!(writer, "{REPLAY_HEADER} {REPLAY_VERSION}")?;
+        writeln!(writer, "seed {}", self.seed)?;
+
+        for (tick, input) in self.inputs.iter() {
+            writeln!(writer, "input {tick} {}", input.to_line())?;
+        }
+
+        for (i, checksum) in self.checksums.iter().enumerate() {
+            writeln!(writer, "checksum {} {checksum:016x}", i + 1)?;
+        }
+
+        Ok(())
+    }
+
+    /// Save the replay in a file.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the file.
+    pub fn save_file(&self, path: &str) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+
+        self.write(&mut writer)?;
+        writer.flush()
+    }
+
+    /// Read a replay written by `write`.
+    ///
+    /// # Arguments
+    /// * `reader` - Where the replay is read.
+    pub fn read(reader: impl BufRead) -> Result<Self, String> {
+        let mut lines = reader.lines();
+        let mut next_line = || lines.next().map(|line| line.map_err(|err| err.to_string()));
+
+        let header = next_line().ok_or("Empty replay")??;
+        let version: u32 = match header.split_once(' ') {
+            Some((REPLAY_HEADER, version)) => parse_field(version)?,
+            _ => return Err("Invalid replay header".to_string()),
+        };
+        if version != REPLAY_VERSION {
+            return Err(format!(
+                "Unsupported replay version {version} (expected {REPLAY_VERSION})"
+            ));
+        }
+
+        let seed = match next_line()
+            .ok_or("Missing the replay seed")??
+            .split_once(' ')
+        {
+            Some(("seed", seed)) => parse_field(seed)?,
+            _ => return Err("Missing the replay seed".to_string()),
+        };
+        let mut replay = Replay::new(seed);
+
+        while let Some(line) = next_line() {
+            let line = line?;
+            let fields: Vec<&str> = line.splitn(3, ' ').collect();
+
+            match fields[..] {
+                ["input", tick, input] => {
+                    replay.record_input(parse_field(tick)?, Input::from_line(input)?)
+                }
+                ["checksum", tick, checksum] => {
+                    if parse_field::<u64>(tick)? != replay.get_ticks() + 1 {
+                        return Err(format!("Missing the checksum before: {line}"));
+                    }
+
+                    let checksum = u64::from_str_radix(checksum, 16)
+                        .map_err(|_| format!("Invalid checksum: {line}"))?;
+                    replay.record_checksum(checksum);
+                }
+                [""] => (),
+                _ => return Err(format!("Invalid replay line: {line}")),
+            }
+        }
+
+        Ok(replay)
+    }
+
+    /// Load a replay from a file.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the file.
+    pub fn load_file(path: &str) -> Result<Self, String> {
+        let file =
+            File::open(path).map_err(|err| format!("Could not open the replay {path}: {err}"))?;
+
+        Self::read(BufReader::new(file)).map_err(|err| format!("Could not load {path}: {err}"))
+    }
+}
+
+/// Recording or replay of the current run.
+#[derive(Clone, PartialEq, Debug)]
+pub enum ReplaySession {
+    Off,
+    Recording(Replay),
+    Replaying(Replay),
+}
+
+impl ReplaySession {
+    pub fn is_replaying(&self) -> bool {
+        matches!(self, ReplaySession::Replaying(_))
+    }
+
+    /// Apply an external input (and record it). The inputs are ignored while replaying,
+    /// so the replay is not changed.
+    ///
+    /// Returns if the input was applied.
+    ///
+    /// # Arguments
+    /// * `scene` - The scene.
+    /// * `input` - The input.
+    pub fn input<const W: usize, const H: usize>(
+        &mut self,
+        scene: &mut Scene<W, H>,
+        input: Input,
+    ) -> Result<bool, String> {
+        if self.is_replaying() {
+            return Ok(false);
+        }
+
+        input.apply(scene)?;
+
+        if let ReplaySession::Recording(replay) = self {
+            replay.record_input(scene.get_tick(), input);
+        }
+
+        Ok(true)
+    }
+
+    /// Apply the replay inputs of the next tick (call it before the scene update).
+    ///
+    /// Returns the new tick rate (if it changed).
+    ///
+    /// # Arguments
+    /// * `scene` - The scene.
+    pub fn start_tick<const W: usize, const H: usize>(
+        &self,
+        scene: &mut Scene<W, H>,
+    ) -> Result<Option<f32>, String> {
+        let mut tick_rate = None;
+
+        if let ReplaySession::Replaying(replay) = self {
+            for input in replay.get_inputs(scene.get_tick()) {
+                input.apply(scene)?;
+
+                if let Input::TickRate(rate) = input {
+                    tick_rate = Some(*rate);
+                }
+            }
+        }
+
+        Ok(tick_rate)
+    }
+
+    /// Record or check the checksum of the tick (call it after the scene update).
+    ///
+    /// The replay ends after its last tick (and the session is turned off).
+    ///
+    /// # Arguments
+    /// * `scene` - The scene.
+    pub fn end_tick<const W: usize, const H: usize>(
+        &mut self,
+        scene: &Scene<W, H>,
+    ) -> Result<(), String> {
+        match self {
+            ReplaySession::Off => (),
+            ReplaySession::Recording(replay) => replay.record_checksum(scene.get_checksum()),
+            ReplaySession::Replaying(replay) => {
+                let result = replay.check(scene.get_tick(), scene.get_checksum());
+
+                if result.is_err() || scene.get_tick() >= replay.get_ticks() {
+                    *self = ReplaySession::Off;
+                }
+
+                result?;
+            }
+        }
+
+        Ok(())
+    }
+}
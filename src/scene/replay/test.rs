@@ -0,0 +1,149 @@
+use super::*;
+
+/// Record a run with inputs on the first tick and on the middle of the run.
+fn record_run(ticks: u64) -> Replay {
+    let mut scene = Scene::<20, 20>::with_seed(11);
+    let mut session = ReplaySession::Recording(Replay::new(11));
+
+    session
+        .input(&mut scene, Input::Spawn(EntityType::Animal1, 3))
+        .unwrap();
+    session
+        .input(&mut scene, Input::Spawn(EntityType::Plant1, 5))
+        .unwrap();
+
+    for tick in 0..ticks {
+        if tick == ticks / 2 {
+            session
+                .input(&mut scene, Input::Spawn(EntityType::Animal2, 2))
+                .unwrap();
+            session.input(&mut scene, Input::TickRate(8_f32)).unwrap();
+        }
+
+        session.start_tick(&mut scene).unwrap();
+        scene.update();
+        session.end_tick(&scene).unwrap();
+    }
+
+    match session {
+        ReplaySession::Recording(replay) => replay,
+        _ => panic!("The session should be recording"),
+    }
+}
+
+/// Replay a run and return the tick rates changed by it.
+fn replay_run(replay: Replay) -> Result<Vec<f32>, String> {
+    let mut scene = Scene::<20, 20>::with_seed(replay.get_seed());
+    let mut session = ReplaySession::Replaying(replay);
+    let mut tick_rates = vec![];
+
+    while session.is_replaying() {
+        tick_rates.extend(session.start_tick(&mut scene)?);
+        scene.update();
+        session.end_tick(&scene)?;
+    }
+
+    Ok(tick_rates)
+}
+
+mod end_tick {
+    use super::*;
+
+    #[test]
+    fn should_record_a_checksum_per_tick() {
+        let replay = record_run(10);
+
+        assert_eq!(replay.get_ticks(), 10);
+        assert_eq!(replay.get_inputs(0).count(), 2);
+        assert_eq!(replay.get_inputs(5).count(), 2);
+    }
+
+    #[test]
+    fn should_replay_the_same_run() {
+        assert_eq!(replay_run(record_run(20)), Ok(vec![8_f32]));
+    }
+
+    #[test]
+    fn should_find_the_diverged_tick() {
+        let mut replay = record_run(10);
+        replay.checksums[6] ^= 1;
+
+        let err = replay_run(replay).unwrap_err();
+
+        assert!(err.contains("tick 7"), "{err}");
+    }
+
+    #[test]
+    fn should_replay_the_scattered_plants() {
+        let mut scene = Scene::<20, 20>::with_seed(5);
+        let mut session = ReplaySession::Recording(Replay::new(5));
+
+        session
+            .input(&mut scene, Input::Scatter(EntityType::Plant2))
+            .unwrap();
+        for _ in 0..10 {
+            session.start_tick(&mut scene).unwrap();
+            scene.update();
+            session.end_tick(&scene).unwrap();
+        }
+
+        let replay = match session {
+            ReplaySession::Recording(replay) => replay,
+            _ => panic!("The session should be recording"),
+        };
+        assert_eq!(replay.get_inputs(0).count(), 1);
+        assert_eq!(replay_run(replay), Ok(vec![]));
+    }
+}
+
+mod input {
+    use super::*;
+
+    #[test]
+    fn should_ignore_the_inputs_while_replaying() {
+        let mut scene = Scene::<20, 20>::with_seed(3);
+        let mut session = ReplaySession::Replaying(Replay::new(3));
+
+        let applied = session
+            .input(&mut scene, Input::Spawn(EntityType::Animal1, 2))
+            .unwrap();
+
+        assert!(!applied);
+        assert!(scene.get_animals().is_empty());
+    }
+}
+
+mod read {
+    use super::*;
+
+    #[test]
+    fn should_read_the_written_replay() {
+        let mut replay = record_run(5);
+        replay.record_input(
+            5,
+            Input::Script(EntityType::Animal2, "scripts/my herbivore.rhai".to_string()),
+        );
+
+        replay.record_input(6, Input::Despawn(12));
+        replay.record_input(6, Input::DayLength(120));
+        replay.record_input(6, Input::SeasonLength(900));
+        replay.record_input(7, Input::Lightning);
+        replay.record_input(7, Input::Scatter(EntityType::Plant2));
+
+        let mut buffer = vec![];
+        replay.write(&mut buffer).unwrap();
+
+        assert_eq!(Replay::read(buffer.as_slice()), Ok(replay));
+    }
+
+    #[test]
+    fn should_reject_an_invalid_replay() {
+        assert!(Replay::read("rusty-island-replay 2\nseed 1".as_bytes()).is_err());
+        assert!(Replay::read("rusty-island-replay 1".as_bytes()).is_err());
+        assert!(Replay::read("rusty-island-replay 1\nseed 1\ninput 0 fly".as_bytes()).is_err());
+        assert!(Replay::read(
+            "rusty-island-replay 1\nseed 1\nchecksum 2 00000000000000ff".as_bytes()
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,354 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use super::entity::record::parse_field;
+use super::{Entity, EntityId, EntityType, FireCell, Scene, Weather, WeatherKind};
+
+#[cfg(test)]
+mod test;
+
+/// First word of a saved scene.
+const SAVE_HEADER: &str = "rusty-island-save";
+/// Version of the format (increase it when the format changes).
+const SAVE_VERSION: u32 = 1;
+
+/// Scene read from a save.
+pub struct SavedScene<const W: usize, const H: usize> {
+    pub scene: Scene<W, H>,
+    /// Simulation ticks per second when the scene was saved.
+    pub tick_rate: f32,
+}
+
+impl<const W: usize, const H: usize> Scene<W, H> {
+    /// Write the whole state of the scene (height map, weather, fires, entities, clock and
+    /// random generator).
+    ///
+    /// # Arguments
+    /// * `writer` - Where the scene is written.
+    /// * `tick_rate` - Simulation ticks per second.
+    pub fn save(&self, writer: &mut impl Write, tick_rate: f32) -> io::Result<()> {
+        writeln!(writer, "{SAVE_HEADER} {SAVE_VERSION}")?;
+        writeln!(writer, "size {W} {H}")?;
+        writeln!(writer, "tick {}", self.tick)?;
+        writeln!(writer, "tick_rate {tick_rate}")?;
+        writeln!(writer, "next_id {}", self.next_id)?;
+        writeln!(writer, "day_length {}", self.day_cycle.get_day_length())?;
+        writeln!(
+            writer,
+            "season_length {}",
+            self.season_cycle.get_season_length()
+        )?;
+        writeln!(
+            writer,
+            "weather {} {} {} {} {}",
+            self.weather.kind.get_name(),
+            self.weather.ticks_left,
+            self.weather.lake_fill,
+            self.weather.wind[0],
+            self.weather.wind[1]
+        )?;
+
+        let seed: String = self
+            .rng
+            .get_seed()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        writeln!(
+            writer,
+            "rng {seed} {} {}",
+            self.rng.get_stream(),
+            self.rng.get_word_pos()
+        )?;
+
+        // The lakes are saved full, and the lake fill dries them again on the load.
+        write_map(writer, "height_map", &self.get_bed_map())?;
+        write_map(writer, "moisture_map", &self.moisture_map)?;
+
+        let mut fire_cells = vec![];
+        for x in 0..W {
+            for z in 0..H {
+                match self.fire_map[x][z] {
+                    FireCell::Unburnt => (),
+                    FireCell::Burning { ticks_left } => {
+                        fire_cells.push(format!("{x} {z} burning {ticks_left}"))
+                    }
+                    FireCell::Ash {
+                        entity_type,
+                        ticks_left,
+                    } => fire_cells.push(format!(
+                        "{x} {z} ash {} {ticks_left}",
+                        entity_type.get_name()
+                    )),
+                }
+            }
+        }
+        writeln!(writer, "fire {}", fire_cells.len())?;
+        for cell in fire_cells.iter() {
+            writeln!(writer, "{cell}")?;
+        }
+
+        // The tree order is kept, so loading the plants creates the same tree.
+        writeln!(writer, "plants {}", self.get_plant_count())?;
+        for plant in self.iter_plants() {
+            writeln!(writer, "{}", plant.get_record())?;
+        }
+
+        writeln!(writer, "animals {}", self.animals.len())?;
+        for animal in self.animals.iter() {
+            writeln!(writer, "{}", animal.get_record())?;
+        }
+
+        Ok(())
+    }
+
+    /// Save the scene in a file.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the file.
+    /// * `tick_rate` - Simulation ticks per second.
+    pub fn save_file(&self, path: &str, tick_rate: f32) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+
+        self.save(&mut writer, tick_rate)?;
+        writer.flush()
+    }
+
+    /// Read a scene written by `save`.
+    ///
+    /// # Arguments
+    /// * `reader` - Where the scene is read.
+    pub fn load(reader: impl BufRead) -> Result<SavedScene<W, H>, String> {
+        let mut lines = reader.lines();
+        let mut next_line = || -> Result<String, String> {
+            match lines.next() {
+                Some(line) => line.map_err(|err| err.to_string()),
+                None => Err("Unexpected end of the save".to_string()),
+            }
+        };
+
+        let header = next_line()?;
+        let version: u32 = match header.split_once(' ') {
+            Some((SAVE_HEADER, version)) => parse_field(version)?,
+            _ => return Err("Invalid save header".to_string()),
+        };
+        if version != SAVE_VERSION {
+            return Err(format!(
+                "Unsupported save version {version} (expected {SAVE_VERSION})"
+            ));
+        }
+
+        let size = read_values(&next_line()?, "size", 2)?;
+        if size != [W.to_string(), H.to_string()] {
+            return Err(format!(
+                "The save size ({}x{}) does not match the island size ({W}x{H})",
+                size[0], size[1]
+            ));
+        }
+
+        let tick: u64 = parse_field(&read_values(&next_line()?, "tick", 1)?[0])?;
+        let tick_rate: f32 = parse_field(&read_values(&next_line()?, "tick_rate", 1)?[0])?;
+        let next_id: EntityId = parse_field(&read_values(&next_line()?, "next_id", 1)?[0])?;
+        let day_length: u64 = parse_field(&read_values(&next_line()?, "day_length", 1)?[0])?;
+        let season_length: u64 = parse_field(&read_values(&next_line()?, "season_length", 1)?[0])?;
+        let weather = read_weather(&read_values(&next_line()?, "weather", 5)?)?;
+        let rng = read_rng(&read_values(&next_line()?, "rng", 3)?)?;
+
+        let height_map = read_map(&mut next_line, "height_map")?;
+        let moisture_map = read_map(&mut next_line, "moisture_map")?;
+
+        let mut scene = Scene::from_height_map(height_map, rng);
+        scene.tick = tick;
+        scene.next_id = next_id;
+        scene.set_day_length(day_length);
+        scene.set_season_length(season_length);
+        scene.weather = weather;
+        scene.apply_lake_fill();
+        scene.moisture_map = moisture_map;
+
+        let fire_count: usize = parse_field(&read_values(&next_line()?, "fire", 1)?[0])?;
+        for _ in 0..fire_count {
+            let ((x, z), cell) = read_fire_cell::<W, H>(&next_line()?)?;
+            scene.fire_map[x][z] = cell;
+        }
+
+        let plant_count: usize = parse_field(&read_values(&next_line()?, "plants", 1)?[0])?;
+        for _ in 0..plant_count {
+            let plant = Entity::from_record(&next_line()?)?;
+            if !plant.get_type().is_plant() {
+                return Err(format!("Expected a plant, got {:?}", plant.get_type()));
+            }
+            scene.insert_entity(plant);
+        }
+
+        let animal_count: usize = parse_field(&read_values(&next_line()?, "animals", 1)?[0])?;
+        for _ in 0..animal_count {
+            let animal = Entity::from_record(&next_line()?)?;
+            if animal.get_type().is_plant() {
+                return Err(format!("Expected an animal, got {:?}", animal.get_type()));
+            }
+            scene.insert_entity(animal);
+        }
+
+        Ok(SavedScene { scene, tick_rate })
+    }
+
+    /// Load a scene from a file.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the file.
+    pub fn load_file(path: &str) -> Result<SavedScene<W, H>, String> {
+        let file =
+            File::open(path).map_err(|err| format!("Could not open the save {path}: {err}"))?;
+
+        Self::load(BufReader::new(file)).map_err(|err| format!("Could not load {path}: {err}"))
+    }
+}
+
+/// Read the values of a `<key> <values...>` line.
+///
+/// # Arguments
+/// * `line` - The line.
+/// * `key` - Expected key.
+/// * `count` - Expected amount of values.
+fn read_values(line: &str, key: &str, count: usize) -> Result<Vec<String>, String> {
+    let mut fields = line.split_whitespace();
+
+    if fields.next() != Some(key) {
+        return Err(format!("Expected {key}, got: {line}"));
+    }
+
+    let values: Vec<String> = fields.map(String::from).collect();
+    if values.len() != count {
+        return Err(format!("Invalid {key}: {line}"));
+    }
+
+    Ok(values)
+}
+
+/// Write a map with a value per cell (a line per column).
+///
+/// # Arguments
+/// * `writer` - Where the map is written.
+/// * `key` - Name of the map.
+/// * `map` - The map.
+fn write_map<const W: usize, const H: usize>(
+    writer: &mut impl Write,
+    key: &str,
+    map: &[[f32; H]; W],
+) -> io::Result<()> {
+    writeln!(writer, "{key}")?;
+    for column in map.iter() {
+        let values: Vec<String> = column.iter().map(|value| value.to_string()).collect();
+        writeln!(writer, "{}", values.join(" "))?;
+    }
+
+    Ok(())
+}
+
+/// Read a map written by `write_map`.
+///
+/// # Arguments
+/// * `next_line` - Reads the next line of the save.
+/// * `key` - Name of the map.
+fn read_map<const W: usize, const H: usize>(
+    next_line: &mut impl FnMut() -> Result<String, String>,
+    key: &str,
+) -> Result<[[f32; H]; W], String> {
+    read_values(&next_line()?, key, 0)?;
+
+    let mut map = [[0_f32; H]; W];
+    for column in map.iter_mut() {
+        let line = next_line()?;
+        let values: Vec<&str> = line.split_whitespace().collect();
+        if values.len() != H {
+            return Err(format!("Invalid {key} row"));
+        }
+
+        for (cell, value) in column.iter_mut().zip(values) {
+            *cell = parse_field(value)?;
+        }
+    }
+
+    Ok(map)
+}
+
+/// Restore the weather (kind, ticks left, lake fill and wind).
+///
+/// # Arguments
+/// * `values` - The saved values.
+fn read_weather(values: &[String]) -> Result<Weather, String> {
+    let kind = WeatherKind::from_name(&values[0])
+        .ok_or_else(|| format!("Unknown weather: {}", values[0]))?;
+
+    Ok(Weather {
+        kind,
+        ticks_left: parse_field(&values[1])?,
+        lake_fill: parse_field(&values[2])?,
+        wind: [parse_field(&values[3])?, parse_field(&values[4])?],
+    })
+}
+
+/// Read a cell of the fire (`<x> <z> burning <ticks left>` or
+/// `<x> <z> ash <plant type> <ticks left>`).
+///
+/// # Arguments
+/// * `line` - The line.
+fn read_fire_cell<const W: usize, const H: usize>(
+    line: &str,
+) -> Result<((usize, usize), FireCell), String> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+
+    let (x, z, cell) = match fields[..] {
+        [x, z, "burning", ticks_left] => (
+            x,
+            z,
+            FireCell::Burning {
+                ticks_left: parse_field(ticks_left)?,
+            },
+        ),
+        [x, z, "ash", name, ticks_left] => (
+            x,
+            z,
+            FireCell::Ash {
+                entity_type: EntityType::from_name(name)
+                    .ok_or(format!("Unknown entity type: {name}"))?,
+                ticks_left: parse_field(ticks_left)?,
+            },
+        ),
+        _ => return Err(format!("Invalid fire cell: {line}")),
+    };
+
+    let (x, z): (usize, usize) = (parse_field(x)?, parse_field(z)?);
+    if x >= W || z >= H {
+        return Err(format!("Fire cell outside the island: {line}"));
+    }
+
+    Ok(((x, z), cell))
+}
+
+/// Restore the random generator (seed, stream and position in the stream).
+///
+/// # Arguments
+/// * `values` - The saved values.
+fn read_rng(values: &[String]) -> Result<ChaCha8Rng, String> {
+    let invalid_seed = || format!("Invalid random seed: {}", values[0]);
+
+    if values[0].len() != 64 || !values[0].is_ascii() {
+        return Err(invalid_seed());
+    }
+
+    let mut seed = [0_u8; 32];
+    for (i, byte) in seed.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&values[0][i * 2..i * 2 + 2], 16).map_err(|_| invalid_seed())?;
+    }
+
+    let mut rng = ChaCha8Rng::from_seed(seed);
+    rng.set_stream(parse_field(&values[1])?);
+    rng.set_word_pos(parse_field(&values[2])?);
+
+    Ok(rng)
+}
@@ -0,0 +1,164 @@
+use super::*;
+use crate::scene::EntityType;
+
+fn create_scene() -> Scene<20, 20> {
+    let mut scene = Scene::<20, 20>::with_seed(7);
+    scene.set_day_length(30);
+    scene.set_season_length(45);
+    scene.create_entities(3, EntityType::Animal1).unwrap();
+    scene.create_entities(3, EntityType::Animal2).unwrap();
+    assert_eq!(scene.create_entities(6, EntityType::Plant1), Ok(6));
+
+    for _ in 0..10 {
+        scene.update();
+    }
+
+    scene
+}
+
+fn save_to_string(scene: &Scene<20, 20>, tick_rate: f32) -> String {
+    let mut buffer = vec![];
+    scene.save(&mut buffer, tick_rate).unwrap();
+    String::from_utf8(buffer).unwrap()
+}
+
+fn records<'a>(entities: impl IntoIterator<Item = &'a Entity>) -> Vec<String> {
+    entities
+        .into_iter()
+        .map(|entity| entity.get_record())
+        .collect()
+}
+
+mod load {
+    use super::*;
+
+    #[test]
+    fn should_restore_the_saved_state() {
+        let scene = create_scene();
+
+        let saved = Scene::<20, 20>::load(save_to_string(&scene, 4_f32).as_bytes()).unwrap();
+
+        assert_eq!(saved.tick_rate, 4_f32);
+        assert_eq!(saved.scene.get_day_cycle(), scene.get_day_cycle());
+        assert_eq!(saved.scene.get_season_cycle(), scene.get_season_cycle());
+        assert_eq!(saved.scene.get_tick(), scene.get_tick());
+        assert_eq!(saved.scene.get_height_map(), scene.get_height_map());
+        assert_eq!(
+            records(saved.scene.get_animals()),
+            records(scene.get_animals())
+        );
+        assert_eq!(
+            records(saved.scene.iter_plants()),
+            records(scene.iter_plants())
+        );
+    }
+
+    #[test]
+    fn should_continue_the_same_simulation() {
+        let mut scene = create_scene();
+        let mut loaded = Scene::<20, 20>::load(save_to_string(&scene, 2_f32).as_bytes())
+            .unwrap()
+            .scene;
+
+        for _ in 0..20 {
+            scene.update();
+            loaded.update();
+        }
+
+        assert_eq!(records(loaded.get_animals()), records(scene.get_animals()));
+        assert_eq!(records(loaded.iter_plants()), records(scene.iter_plants()));
+    }
+
+    #[test]
+    fn should_keep_the_weather_and_the_dry_lakes() {
+        let mut height_map = [[1.2_f32; 20]; 20];
+        height_map[5][5] = 0.5_f32;
+        let mut scene = Scene::<20, 20>::from_height_map(height_map, ChaCha8Rng::seed_from_u64(1));
+        scene.weather = Weather {
+            kind: WeatherKind::Drought,
+            ticks_left: 80,
+            lake_fill: 0_f32,
+            wind: [0.3_f32, -0.4_f32],
+        };
+        scene.update();
+
+        let loaded = Scene::<20, 20>::load(save_to_string(&scene, 2_f32).as_bytes())
+            .unwrap()
+            .scene;
+
+        assert_eq!(loaded.get_weather(), scene.get_weather());
+        assert_eq!(loaded.get_height_map()[5][5], 1_f32);
+        assert_eq!(loaded.lake_beds, vec![((5, 5), 0.5_f32)]);
+        assert_eq!(loaded.moisture_map, scene.moisture_map);
+    }
+
+    #[test]
+    fn should_keep_the_fires() {
+        let mut scene = create_scene();
+        let plant = *scene.iter_plants().next().unwrap();
+        let cell = (plant.position[0] as usize, plant.position[2] as usize);
+        assert!(scene.ignite(cell));
+        for _ in 0..8 {
+            scene.update();
+        }
+
+        let mut loaded = Scene::<20, 20>::load(save_to_string(&scene, 2_f32).as_bytes())
+            .unwrap()
+            .scene;
+
+        assert!(matches!(
+            loaded.fire_map[cell.0][cell.1],
+            FireCell::Ash { .. }
+        ));
+        assert_eq!(loaded.fire_map, scene.fire_map);
+
+        for _ in 0..10 {
+            scene.update();
+            loaded.update();
+        }
+        assert_eq!(loaded.get_checksum(), scene.get_checksum());
+    }
+
+    #[test]
+    fn should_keep_the_ids() {
+        let mut scene = create_scene();
+        let mut loaded = Scene::<20, 20>::load(save_to_string(&scene, 2_f32).as_bytes())
+            .unwrap()
+            .scene;
+        let id = scene.get_animals()[0].get_id();
+
+        assert_eq!(loaded.get_entity(id), scene.get_entity(id));
+        assert_eq!(
+            loaded.add_entity(Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal1)),
+            scene.add_entity(Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal1))
+        );
+    }
+
+    #[test]
+    fn should_reject_another_version() {
+        let save = save_to_string(&create_scene(), 2_f32).replacen(
+            &format!("{SAVE_HEADER} {SAVE_VERSION}"),
+            &format!("{SAVE_HEADER} 99"),
+            1,
+        );
+
+        assert!(Scene::<20, 20>::load(save.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn should_reject_another_size() {
+        let save = save_to_string(&create_scene(), 2_f32);
+
+        assert!(Scene::<10, 10>::load(save.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn should_reject_an_incomplete_save() {
+        let save = save_to_string(&create_scene(), 2_f32);
+        let lines: Vec<&str> = save.lines().collect();
+        let incomplete = lines[..lines.len() - 1].join("\n");
+
+        assert!(Scene::<20, 20>::load(incomplete.as_bytes()).is_err());
+        assert!(Scene::<20, 20>::load("not a save".as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,258 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{Array, Dynamic, Engine, EvalAltResult, Map, AST, FLOAT, INT};
+
+use super::entity::tree_entity::TreeEntity;
+use super::entity::{Entity, EntityId, EntityMode, EntityType};
+
+#[cfg(test)]
+mod test;
+
+/// Max operations of a script run (stops infinite loops).
+const MAX_OPERATIONS: u64 = 50_000;
+const MAX_CALL_LEVELS: usize = 16;
+const MAX_COLLECTION_SIZE: usize = 1_000;
+
+/// What a script asked the animal to do.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct ScriptAction {
+    pub mode: Option<EntityMode>,
+    /// Point where the animal should walk to.
+    pub target: Option<[f32; 3]>,
+}
+
+/// Data shared between the scene and the functions exposed to the scripts.
+#[derive(Default)]
+struct ScriptContext {
+    id: EntityId,
+    position: [f32; 3],
+    mode: EntityMode,
+    nearby: Array,
+    height_map: Vec<Vec<f32>>,
+    action: ScriptAction,
+}
+
+/// Sandboxed scripts (one per species) that customize the animal behaviour.
+///
+/// The scripts can only use the functions below (no file system or modules):
+/// * `id()` - Identifier of the animal.
+/// * `position()` - Position of the animal (`[x, y, z]`).
+/// * `mode()` - Name of the current mode.
+/// * `nearby_entities()` - Visible entities, the nearest first (maps with `id`, `kind`, `x`, `z`
+///   and `distance`).
+/// * `terrain_height(x, z)` - Height of a cell (the coordinates are clamped to the map).
+/// * `set_target(x, z)` - Walk to the free neighbor cell closest to the point.
+/// * `set_mode(name)` - Change the mode (`"Idle"`, `"Wandering"`, `"SeekingFood"`, ...).
+pub struct Scripts {
+    engine: Engine,
+    scripts: Vec<(EntityType, AST)>,
+    context: Rc<RefCell<ScriptContext>>,
+}
+
+impl Scripts {
+    /// Create the scripting engine (without any script).
+    ///
+    /// # Arguments
+    /// * `height_map` - Height map of the scene.
+    pub fn new<const W: usize, const H: usize>(height_map: &[[f32; H]; W]) -> Self {
+        let context = Rc::new(RefCell::new(ScriptContext {
+            height_map: height_map.iter().map(|column| column.to_vec()).collect(),
+            ..ScriptContext::default()
+        }));
+
+        let mut engine = Engine::new();
+        engine
+            .set_max_operations(MAX_OPERATIONS)
+            .set_max_call_levels(MAX_CALL_LEVELS)
+            .set_max_string_size(MAX_COLLECTION_SIZE)
+            .set_max_array_size(MAX_COLLECTION_SIZE)
+            .set_max_map_size(MAX_COLLECTION_SIZE)
+            .set_module_resolver(DummyModuleResolver::new())
+            .disable_symbol("eval");
+
+        Self::register_api(&mut engine, &context);
+
+        Self {
+            engine,
+            scripts: vec![],
+            context,
+        }
+    }
+
+    fn register_api(engine: &mut Engine, context: &Rc<RefCell<ScriptContext>>) {
+        let ctx = context.clone();
+        engine.register_fn("id", move || -> INT { ctx.borrow().id as INT });
+
+        let ctx = context.clone();
+        engine.register_fn("position", move || -> Array {
+            ctx.borrow()
+                .position
+                .iter()
+                .map(|coord| Dynamic::from_float(*coord as FLOAT))
+                .collect()
+        });
+
+        let ctx = context.clone();
+        engine.register_fn("mode", move || -> String {
+            ctx.borrow().mode.get_name().to_string()
+        });
+
+        let ctx = context.clone();
+        engine.register_fn("nearby_entities", move || -> Array {
+            ctx.borrow().nearby.clone()
+        });
+
+        let ctx = context.clone();
+        engine.register_fn(
+            "terrain_height",
+            move |x: Dynamic, z: Dynamic| -> Result<FLOAT, Box<EvalAltResult>> {
+                let ctx = ctx.borrow();
+                let width = ctx.height_map.len();
+                let height = ctx.height_map.first().map_or(0, |column| column.len());
+
+                if width == 0 || height == 0 {
+                    return Ok(0 as FLOAT);
+                }
+
+                let x = (to_float(x)?.round().max(0 as FLOAT) as usize).min(width - 1);
+                let z = (to_float(z)?.round().max(0 as FLOAT) as usize).min(height - 1);
+
+                Ok(ctx.height_map[x][z] as FLOAT)
+            },
+        );
+
+        let ctx = context.clone();
+        engine.register_fn(
+            "set_target",
+            move |x: Dynamic, z: Dynamic| -> Result<(), Box<EvalAltResult>> {
+                let target = [to_float(x)? as f32, 0_f32, to_float(z)? as f32];
+                ctx.borrow_mut().action.target = Some(target);
+                Ok(())
+            },
+        );
+
+        let ctx = context.clone();
+        engine.register_fn(
+            "set_mode",
+            move |name: &str| -> Result<(), Box<EvalAltResult>> {
+                let mode = EntityMode::from_name(name).ok_or(format!("Unknown mode: {name}"))?;
+                ctx.borrow_mut().action.mode = Some(mode);
+                Ok(())
+            },
+        );
+    }
+
+    /// Compile the script of a species (it replaces the previous one).
+    ///
+    /// # Arguments
+    /// * `entity_type` - Species that runs the script.
+    /// * `source` - Code of the script.
+    pub fn load(&mut self, entity_type: EntityType, source: &str) -> Result<(), String> {
+        let ast = self
+            .engine
+            .compile(source)
+            .map_err(|err| format!("Invalid script for {}: {err}", entity_type.get_name()))?;
+
+        self.remove(entity_type);
+        self.scripts.push((entity_type, ast));
+
+        Ok(())
+    }
+
+    /// Read and compile the script of a species.
+    ///
+    /// # Arguments
+    /// * `entity_type` - Species that runs the script.
+    /// * `path` - Path of the script file.
+    pub fn load_file(&mut self, entity_type: EntityType, path: &str) -> Result<(), String> {
+        let source = std::fs::read_to_string(path)
+            .map_err(|err| format!("Could not read the script {path}: {err}"))?;
+
+        self.load(entity_type, &source)
+    }
+
+    /// Update the height map seen by the scripts (after the lakes filled or dried).
+    ///
+    /// # Arguments
+    /// * `height_map` - Height map of the scene.
+    pub fn set_height_map<const W: usize, const H: usize>(&mut self, height_map: &[[f32; H]; W]) {
+        self.context.borrow_mut().height_map =
+            height_map.iter().map(|column| column.to_vec()).collect();
+    }
+
+    pub fn remove(&mut self, entity_type: EntityType) {
+        self.scripts
+            .retain(|(script_type, _)| *script_type != entity_type);
+    }
+
+    /// Run the script of the animal species (returns `None` when the species has no script).
+    ///
+    /// # Arguments
+    /// * `animal` - The animal.
+    /// * `height_map` - Height map of the scene.
+    /// * `animals` - Animals of the scene.
+    /// * `tree_plants` - Plants of the scene.
+    pub fn run<const W: usize, const H: usize>(
+        &self,
+        animal: &Entity,
+        height_map: &[[f32; H]; W],
+        animals: &[Entity],
+        tree_plants: &TreeEntity,
+    ) -> Result<Option<ScriptAction>, String> {
+        let ast = match self
+            .scripts
+            .iter()
+            .find(|(entity_type, _)| *entity_type == animal.get_type())
+        {
+            Some((_, ast)) => ast,
+            None => return Ok(None),
+        };
+
+        let nearby = animal
+            .perceive(height_map, tree_plants, animals)
+            .entities
+            .iter()
+            .map(|other| {
+                let mut entity = Map::new();
+                entity.insert("id".into(), Dynamic::from_int(other.get_id() as INT));
+                entity.insert("kind".into(), other.get_type().get_name().into());
+                entity.insert("x".into(), Dynamic::from_float(other.position[0] as FLOAT));
+                entity.insert("z".into(), Dynamic::from_float(other.position[2] as FLOAT));
+                entity.insert(
+                    "distance".into(),
+                    Dynamic::from_float(animal.distance(other.position) as FLOAT),
+                );
+                Dynamic::from_map(entity)
+            })
+            .take(MAX_COLLECTION_SIZE)
+            .collect();
+
+        {
+            let mut context = self.context.borrow_mut();
+            context.id = animal.get_id();
+            context.position = animal.position;
+            context.mode = animal.get_mode();
+            context.nearby = nearby;
+            context.action = ScriptAction::default();
+        }
+
+        self.engine
+            .run_ast(ast)
+            .map_err(|err| format!("Script of {} failed: {err}", animal.get_type().get_name()))?;
+
+        Ok(Some(self.context.borrow().action))
+    }
+}
+
+/// Convert a script number (integer or float) to a float.
+///
+/// # Arguments
+/// * `value` - The number.
+fn to_float(value: Dynamic) -> Result<FLOAT, Box<EvalAltResult>> {
+    value
+        .as_float()
+        .or_else(|_| value.as_int().map(|value| value as FLOAT))
+        .map_err(|type_name| format!("Expected a number, got {type_name}").into())
+}
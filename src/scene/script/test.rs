@@ -0,0 +1,179 @@
+use super::*;
+
+fn create_height_map() -> [[f32; 10]; 10] {
+    let mut height_map = [[1_f32; 10]; 10];
+    height_map[9][5] = 3_f32;
+    height_map
+}
+
+fn create_scripts(source: &str) -> Scripts {
+    let mut scripts = Scripts::new(&create_height_map());
+    scripts.load(EntityType::Animal2, source).unwrap();
+    scripts
+}
+
+fn create_tree_plants() -> TreeEntity {
+    TreeEntity::new(Entity::new([6_f32, 0_f32, 5_f32], EntityType::Plant1))
+}
+
+mod load {
+    use super::*;
+
+    #[test]
+    fn should_fail_with_an_invalid_script() {
+        let mut scripts = Scripts::new(&[[1_f32; 10]; 10]);
+
+        assert!(scripts.load(EntityType::Animal2, "let x = ;").is_err());
+        assert!(scripts
+            .load_file(EntityType::Animal2, "missing_script.rhai")
+            .is_err());
+    }
+
+    #[test]
+    fn should_compile_the_example_script() {
+        let mut scripts = Scripts::new(&[[1_f32; 10]; 10]);
+
+        assert_eq!(
+            scripts.load_file(EntityType::Animal2, "scripts/herbivore.rhai"),
+            Ok(())
+        );
+    }
+}
+
+mod run {
+    use super::*;
+
+    #[test]
+    fn should_ignore_species_without_script() {
+        let scripts = create_scripts("set_mode(\"Sleeping\");");
+        let animal = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal1);
+
+        assert_eq!(
+            scripts.run(&animal, &create_height_map(), &[], &create_tree_plants()),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn should_set_the_mode_and_the_target() {
+        let scripts = create_scripts(
+            "let p = position();
+             if mode() == \"Idle\" && terrain_height(9, 5) > terrain_height(p[0], p[2]) {
+                 set_mode(\"Wandering\");
+                 set_target(9, p[2]);
+             }",
+        );
+        let animal = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal2);
+
+        assert_eq!(
+            scripts.run(&animal, &create_height_map(), &[], &create_tree_plants()),
+            Ok(Some(ScriptAction {
+                mode: Some(EntityMode::Wandering),
+                target: Some([9_f32, 0_f32, 5_f32]),
+            }))
+        );
+    }
+
+    #[test]
+    fn should_list_the_nearby_entities() {
+        let scripts = create_scripts(
+            "let entities = nearby_entities();
+             if entities.len() == 2 && entities[0].kind == \"plant1\" && entities[1].kind == \"animal1\" {
+                 set_target(entities[0].x, entities[0].z);
+             }",
+        );
+        let animal = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal2);
+        let animals = [
+            animal,
+            Entity::new([3_f32, 0_f32, 5_f32], EntityType::Animal1),
+            Entity::new([50_f32, 0_f32, 5_f32], EntityType::Animal1),
+        ];
+
+        let action = scripts
+            .run(
+                &animal,
+                &create_height_map(),
+                &animals,
+                &create_tree_plants(),
+            )
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(action.target, Some([6_f32, 0_f32, 5_f32]));
+    }
+
+    #[test]
+    fn should_know_the_ids() {
+        let scripts = create_scripts(
+            "let entities = nearby_entities();
+             if id() == 4 && entities[1].id == 9 {
+                 set_mode(\"Sleeping\");
+             }",
+        );
+        let mut animal = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal2);
+        animal.set_id(4);
+        let mut neighbour = Entity::new([3_f32, 0_f32, 5_f32], EntityType::Animal1);
+        neighbour.set_id(9);
+
+        let action = scripts
+            .run(
+                &animal,
+                &create_height_map(),
+                &[animal, neighbour],
+                &create_tree_plants(),
+            )
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(action.mode, Some(EntityMode::Sleeping));
+    }
+
+    #[test]
+    fn should_fail_with_an_unknown_mode() {
+        let scripts = create_scripts("set_mode(\"Flying\");");
+        let animal = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal2);
+
+        assert!(scripts
+            .run(&animal, &create_height_map(), &[], &create_tree_plants())
+            .is_err());
+    }
+
+    #[test]
+    fn should_stop_infinite_loops() {
+        let scripts = create_scripts("loop { }");
+        let animal = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal2);
+
+        assert!(scripts
+            .run(&animal, &create_height_map(), &[], &create_tree_plants())
+            .is_err());
+    }
+
+    #[test]
+    fn should_not_import_modules() {
+        let scripts = create_scripts("import \"scripts/herbivore\" as herbivore;");
+        let animal = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal2);
+
+        assert!(scripts
+            .run(&animal, &create_height_map(), &[], &create_tree_plants())
+            .is_err());
+    }
+}
+
+mod mode_names {
+    use super::*;
+
+    #[test]
+    fn should_find_the_mode_by_name() {
+        for mode in EntityMode::ALL {
+            let scripts = create_scripts(&format!("set_mode(\"{}\");", mode.get_name()));
+            let animal = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal2);
+
+            let action = scripts
+                .run(&animal, &create_height_map(), &[], &create_tree_plants())
+                .unwrap()
+                .unwrap();
+
+            assert_eq!(action.mode, Some(mode));
+        }
+    }
+}
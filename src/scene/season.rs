@@ -0,0 +1,142 @@
+use super::cycle::Cycle;
+
+/// Ticks of a season when the length is not configured (4 days).
+pub const DEFAULT_SEASON_LENGTH: u64 = 1600;
+/// Fraction of the season (at its end) where the leaves change to the color of the next one.
+const LEAF_CHANGE: f32 = 0.25_f32;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    /// Seasons in the order of the year.
+    pub const ALL: [Season; 4] = [
+        Season::Spring,
+        Season::Summer,
+        Season::Autumn,
+        Season::Winter,
+    ];
+
+    /// Season after this one.
+    pub fn next(&self) -> Season {
+        Self::ALL[(*self as usize + 1) % Self::ALL.len()]
+    }
+
+    /// Ratio of the plant growth.
+    pub fn get_growth_ratio(&self) -> f32 {
+        match self {
+            Season::Spring => 1.25_f32,
+            Season::Summer => 1_f32,
+            Season::Autumn => 0.5_f32,
+            Season::Winter => 0.25_f32,
+        }
+    }
+
+    /// Ratio of the chance of the grown plants to spread a seed.
+    pub fn get_spread_ratio(&self) -> f32 {
+        match self {
+            Season::Spring => 1.5_f32,
+            Season::Summer => 1_f32,
+            Season::Autumn => 0.5_f32,
+            Season::Winter => 0_f32,
+        }
+    }
+
+    /// Ratio of the energy given by the plants (there are fruits in the autumn and only dry
+    /// leaves in the winter).
+    pub fn get_nutrition_ratio(&self) -> f32 {
+        match self {
+            Season::Spring | Season::Summer => 1_f32,
+            Season::Autumn => 1.25_f32,
+            Season::Winter => 0.5_f32,
+        }
+    }
+
+    /// Color of the plant leaves and how much of it (between 0 and 1) is mixed with the color
+    /// of the plant.
+    fn get_leaf_color(&self) -> ([f32; 3], f32) {
+        match self {
+            Season::Spring => ([0.45_f32, 0.85_f32, 0.2_f32], 0.3_f32),
+            Season::Summer => ([0_f32; 3], 0_f32),
+            Season::Autumn => ([0.85_f32, 0.45_f32, 0.08_f32], 0.7_f32),
+            Season::Winter => ([0.45_f32, 0.38_f32, 0.3_f32], 0.6_f32),
+        }
+    }
+
+    /// Tint the color of the plant leaves with the season color.
+    ///
+    /// # Arguments
+    /// * `diffuse` - Color of the plant.
+    pub fn tint_leaves(&self, diffuse: [f32; 3]) -> [f32; 3] {
+        let (color, amount) = self.get_leaf_color();
+
+        [0, 1, 2].map(|i| diffuse[i] + (color[i] - diffuse[i]) * amount)
+    }
+}
+
+/// Seasons of the simulation clock (the year starts on the spring).
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SeasonCycle {
+    seasons: Cycle,
+}
+
+impl Default for SeasonCycle {
+    fn default() -> Self {
+        Self::new(DEFAULT_SEASON_LENGTH)
+    }
+}
+
+impl SeasonCycle {
+    /// Create a cycle with a season length.
+    ///
+    /// # Arguments
+    /// * `season_length` - Ticks of a season (at least 1).
+    pub fn new(season_length: u64) -> Self {
+        Self {
+            seasons: Cycle::new(season_length, 1),
+        }
+    }
+
+    pub fn get_season_length(&self) -> u64 {
+        self.seasons.get_length()
+    }
+
+    /// Season of a tick.
+    ///
+    /// # Arguments
+    /// * `tick` - Tick of the scene.
+    pub fn get_season(&self, tick: u64) -> Season {
+        let index = self.seasons.get_count(tick) % Season::ALL.len() as u64;
+
+        Season::ALL[index as usize]
+    }
+
+    /// Fraction (between 0 and 1) of the season that already passed.
+    ///
+    /// # Arguments
+    /// * `tick` - Tick of the scene.
+    pub fn get_progress(&self, tick: u64) -> f32 {
+        self.seasons.get_progress(tick)
+    }
+
+    /// Tint the color of the plant leaves on a tick (the leaves change to the color of the
+    /// next season on the end of each season).
+    ///
+    /// # Arguments
+    /// * `tick` - Tick of the scene.
+    /// * `diffuse` - Color of the plant.
+    pub fn tint_leaves(&self, tick: u64, diffuse: [f32; 3]) -> [f32; 3] {
+        let season = self.get_season(tick);
+        let current = season.tint_leaves(diffuse);
+        let next = season.next().tint_leaves(diffuse);
+
+        let change = ((self.get_progress(tick) - (1_f32 - LEAF_CHANGE)) / LEAF_CHANGE).max(0_f32);
+
+        [0, 1, 2].map(|i| current[i] + (next[i] - current[i]) * change)
+    }
+}
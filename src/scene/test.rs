@@ -1,3 +1,1160 @@
 use super::*;
 
-mod new {}
+/// Flat scene with only land.
+fn land_scene() -> Scene<12, 12> {
+    let height_map = crate::utils::height_map::init_height_map(1.2_f32);
+    Scene::from_height_map(height_map, ChaCha8Rng::seed_from_u64(1))
+}
+
+/// Grown up animal.
+///
+/// # Arguments
+/// * `position` - Position of the animal.
+/// * `entity_type` - Species of the animal.
+fn adult(position: [f32; 3], entity_type: EntityType) -> Entity {
+    let mut animal = Entity::new(position, entity_type);
+    animal.set_age(entity_type.species().maturity_age);
+    animal
+}
+
+mod new {
+    use super::*;
+
+    #[test]
+    fn should_create_an_empty_scene() {
+        let scene = Scene::<10, 10>::new();
+
+        assert!(scene.get_animals().is_empty());
+        assert_eq!(scene.get_plant_count(), 0);
+    }
+}
+
+mod create_entities {
+    use super::*;
+    use crate::utils::height_map::init_height_map;
+
+    fn flat_scene<const W: usize, const H: usize>(height: f32) -> Scene<W, H> {
+        Scene::from_height_map(init_height_map(height), ChaCha8Rng::seed_from_u64(1))
+    }
+
+    #[test]
+    fn should_create_all_the_entities() {
+        let mut scene = flat_scene::<10, 10>(1.2_f32);
+
+        assert_eq!(scene.create_entities(5, EntityType::Animal1), Ok(5));
+        assert_eq!(scene.get_population(EntityType::Animal1), 5);
+    }
+
+    #[test]
+    fn should_report_the_entities_placed_when_the_island_is_full() {
+        let mut scene = flat_scene::<4, 4>(1.2_f32);
+
+        let err = scene.create_entities(20, EntityType::Animal2).unwrap_err();
+
+        assert_eq!(
+            err,
+            PlacementError {
+                entity_type: EntityType::Animal2,
+                requested: 20,
+                placed: 16,
+            }
+        );
+        assert_eq!(scene.get_animals().len(), 16);
+        assert!(scene.create_entities(1, EntityType::Animal1).is_err());
+    }
+
+    #[test]
+    fn should_not_place_entities_on_the_water() {
+        let mut scene = flat_scene::<10, 10>(0_f32);
+
+        let err = scene.create_entities(3, EntityType::Animal1).unwrap_err();
+
+        assert_eq!(err.placed, 0);
+        assert!(scene.get_animals().is_empty());
+    }
+
+    #[test]
+    fn should_place_the_aquatic_animals_on_their_habitat() {
+        let mut scene = flat_scene::<6, 6>(1.2_f32);
+        scene.height_map[0] = [0_f32; 6];
+        scene.height_map[5] = [-2_f32; 6];
+
+        assert_eq!(scene.create_entities(6, EntityType::Fish), Ok(6));
+        assert!(scene.create_entities(7, EntityType::Dolphin).is_err());
+
+        for animal in scene.get_animals() {
+            let x = animal.position[0] as usize;
+            let expected_x = if animal.get_type() == EntityType::Fish {
+                0
+            } else {
+                5
+            };
+            assert_eq!(x, expected_x);
+            assert!(animal.position[1] < WATER_LEVEL);
+        }
+    }
+
+    #[test]
+    fn should_perch_the_birds_on_the_plants() {
+        let mut scene = flat_scene::<10, 10>(1.2_f32);
+        scene.add_entity(Entity::new([2_f32, 0_f32, 2_f32], EntityType::Plant1));
+        scene.add_entity(Entity::new([7_f32, 0_f32, 5_f32], EntityType::Plant2));
+
+        let err = scene.create_entities(3, EntityType::Bird).unwrap_err();
+
+        assert_eq!(err.placed, 2);
+        for bird in scene.get_animals() {
+            assert!(scene
+                .iter_plants()
+                .any(|plant| plant.distance(bird.position) == 0_f32));
+            assert!(bird.position[1] > 1.2_f32);
+        }
+    }
+
+    #[test]
+    fn should_not_reuse_an_occupied_cell() {
+        let mut scene = flat_scene::<6, 6>(1.2_f32);
+        scene.create_entities(30, EntityType::Animal1).unwrap();
+        scene.create_entities(6, EntityType::Animal2).unwrap();
+
+        let cells: HashSet<(usize, usize)> = scene
+            .get_animals()
+            .iter()
+            .map(|animal| (animal.position[0] as usize, animal.position[2] as usize))
+            .collect();
+
+        assert_eq!(cells.len(), 36);
+    }
+
+    #[test]
+    fn should_fill_the_free_cells_when_the_plants_can_not_be_spaced() {
+        let mut scene = flat_scene::<6, 6>(1.2_f32);
+
+        assert_eq!(scene.create_entities(36, EntityType::Plant2), Ok(36));
+        assert_eq!(scene.get_plant_count(), 36);
+        assert_eq!(
+            scene.create_entities(1, EntityType::Plant2),
+            Err(PlacementError {
+                entity_type: EntityType::Plant2,
+                requested: 1,
+                placed: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn should_describe_the_error_with_the_species_name() {
+        let err = PlacementError {
+            entity_type: EntityType::Plant2,
+            requested: 4,
+            placed: 1,
+        };
+
+        assert_eq!(err.to_string(), "There is only space for 1 of 4 plant2");
+    }
+}
+
+mod update {
+    use super::*;
+
+    #[test]
+    fn should_run_without_a_display() {
+        let mut scene = Scene::<20, 20>::new();
+        scene.create_entities(2, EntityType::Animal1).unwrap();
+        scene.create_entities(4, EntityType::Animal2).unwrap();
+        assert_eq!(scene.create_entities(5, EntityType::Plant1), Ok(5));
+
+        for _ in 0..50 {
+            scene.update();
+        }
+
+        assert!(scene.get_animals().len() <= 6 + 50);
+        assert!(scene.get_plant_count() >= 5);
+    }
+}
+
+mod aquatic {
+    use super::*;
+
+    #[test]
+    fn should_never_leave_the_water() {
+        let mut scene = Scene::<40, 40>::with_seed(9);
+        scene.create_entities(10, EntityType::Fish).unwrap();
+        scene.create_entities(4, EntityType::Dolphin).unwrap();
+
+        for _ in 0..200 {
+            scene.update();
+
+            for animal in scene.get_animals() {
+                // The animals walking are between their last cell and the target.
+                let (x, z) = animal
+                    .get_target()
+                    .unwrap_or((animal.position[0], animal.position[2]));
+                let height = scene.height_map[x as usize][z as usize];
+                assert!(animal.get_type().species().habitat.contains(height));
+                assert!(animal.position[1] < WATER_LEVEL);
+            }
+        }
+    }
+}
+
+mod birds {
+    use super::*;
+
+    #[test]
+    fn should_never_fly_into_the_ground() {
+        let mut scene = Scene::<40, 40>::with_seed(3);
+        assert_eq!(scene.create_entities(30, EntityType::Plant1), Ok(30));
+        scene.create_entities(6, EntityType::Bird).unwrap();
+
+        for _ in 0..200 {
+            scene.update();
+
+            for bird in scene.get_animals() {
+                let [x, y, z] = bird.position;
+                let height = scene.height_map[x as usize][z as usize];
+                assert!(y > height.max(WATER_LEVEL));
+            }
+        }
+    }
+
+    /// Run with `cargo test --release -- --ignored` (the debug build is too slow).
+    #[test]
+    #[ignore]
+    fn should_keep_the_population_bounded() {
+        let mut scene = Scene::<{ crate::ISLAND_WIDTH }, { crate::ISLAND_HEIGHT }>::with_seed(1);
+        scene.scatter_plants(EntityType::Plant1);
+        scene.scatter_plants(EntityType::Plant2);
+        scene.create_entities(30, EntityType::Bird).unwrap();
+
+        let mut peak = 0;
+        for _ in 0..season::DEFAULT_SEASON_LENGTH * 4 {
+            scene.update();
+            peak = peak.max(scene.get_population(EntityType::Bird));
+        }
+
+        assert!(peak < 300);
+    }
+}
+
+mod entity_ids {
+    use super::*;
+
+    fn create_scene() -> Scene<30, 30> {
+        let mut scene = Scene::<30, 30>::with_seed(8);
+        assert_eq!(scene.create_entities(10, EntityType::Plant1), Ok(10));
+        scene.create_entities(5, EntityType::Animal2).unwrap();
+        scene
+    }
+
+    #[test]
+    fn should_give_a_different_id_to_each_entity() {
+        let mut scene = create_scene();
+        for _ in 0..30 {
+            scene.update();
+        }
+
+        let mut ids: Vec<EntityId> = scene
+            .iter_entities()
+            .map(|entity| entity.get_id())
+            .collect();
+        let count = ids.len();
+        ids.sort();
+        ids.dedup();
+
+        assert_eq!(ids.len(), count);
+        assert!(!ids.contains(&0));
+    }
+
+    #[test]
+    fn should_find_the_entities_by_id() {
+        let mut scene = create_scene();
+        for _ in 0..30 {
+            scene.update();
+        }
+
+        for entity in scene.iter_entities() {
+            assert_eq!(scene.get_entity(entity.get_id()), Some(*entity));
+        }
+        assert_eq!(scene.get_entity(1000), None);
+    }
+
+    #[test]
+    fn should_keep_the_id_of_an_animal_between_ticks() {
+        let mut scene = create_scene();
+        let animal = scene.get_animals()[3];
+
+        scene.update();
+
+        let moved = scene.get_entity(animal.get_id()).unwrap();
+        assert_eq!(moved.get_type(), animal.get_type());
+        assert!(moved.distance(animal.position) <= 1.5_f32);
+    }
+
+    #[test]
+    fn should_despawn_an_animal() {
+        let mut scene = create_scene();
+        let despawned = scene.subscribe(|event| event.kind == EventKind::EntityDespawned);
+        let animal = scene.get_animals()[1];
+        let last = scene.get_animals()[4];
+
+        assert_eq!(scene.despawn(animal.get_id()), Some(animal));
+
+        assert_eq!(scene.get_animals().len(), 4);
+        assert_eq!(scene.get_entity(animal.get_id()), None);
+        assert_eq!(scene.get_entity(last.get_id()), Some(last));
+        assert_eq!(scene.despawn(animal.get_id()), None);
+        assert_eq!(scene.drain_events(despawned)[0].entity_id, animal.get_id());
+    }
+
+    #[test]
+    fn should_find_the_animals_left_after_a_death() {
+        let mut scene = create_scene();
+        let animal = scene.get_animals()[1];
+        let mut causes = vec![None; 5];
+        causes[1] = Some(DeathCause::Burned);
+
+        assert_eq!(
+            scene.remove_animals(&causes),
+            vec![(EntityType::Animal2, DeathCause::Burned)]
+        );
+
+        assert_eq!(scene.get_entity(animal.get_id()), None);
+        for animal in scene.get_animals() {
+            assert_eq!(scene.get_entity(animal.get_id()), Some(*animal));
+        }
+    }
+
+    #[test]
+    fn should_despawn_a_plant() {
+        let mut scene = create_scene();
+        let plants: Vec<Entity> = scene.iter_plants().copied().collect();
+
+        assert_eq!(scene.despawn(plants[0].get_id()), Some(plants[0]));
+
+        assert_eq!(scene.get_plant_count(), plants.len() - 1);
+        for plant in plants[1..].iter() {
+            assert_eq!(scene.get_entity(plant.get_id()), Some(*plant));
+        }
+    }
+}
+
+mod cycle {
+    use crate::scene::cycle::Cycle;
+
+    #[test]
+    fn should_count_the_periods_and_their_progress() {
+        let cycle = Cycle::new(8, 2);
+
+        assert_eq!(cycle.get_count(7), 0);
+        assert_eq!(cycle.get_count(17), 2);
+        assert_eq!(cycle.get_progress(18), 0.25_f32);
+        assert_eq!(Cycle::new(0, 2).get_length(), 2);
+    }
+}
+
+mod day_cycle {
+    use super::*;
+    use crate::scene::entity::EntityMode;
+
+    #[test]
+    fn should_split_the_day_and_the_night() {
+        let cycle = DayCycle::new(100);
+
+        assert_eq!(cycle.get_time_of_day(25), 0.25_f32);
+        assert_eq!(cycle.get_time_of_day(130), 0.3_f32);
+        assert!(!cycle.is_night(49));
+        assert!(cycle.is_night(50));
+        assert!(cycle.is_night(99));
+        assert!(!cycle.is_night(100));
+    }
+
+    #[test]
+    fn should_put_the_diurnal_animals_to_sleep_at_night() {
+        let mut scene = Scene::<40, 40>::with_seed(3);
+        scene.set_day_length(60);
+        scene.create_entities(10, EntityType::Animal2).unwrap();
+        assert_eq!(scene.create_entities(60, EntityType::Plant1), Ok(60));
+
+        let sleeping = |scene: &Scene<40, 40>| {
+            scene
+                .get_animals()
+                .iter()
+                .filter(|animal| animal.get_mode() == EntityMode::Sleeping)
+                .count()
+        };
+
+        while !scene.is_night() {
+            scene.update();
+        }
+        let at_dusk = sleeping(&scene);
+        for _ in 0..10 {
+            scene.update();
+        }
+
+        assert!(scene.is_night());
+        assert!(sleeping(&scene) > at_dusk);
+    }
+}
+
+mod weather {
+    use super::*;
+    use crate::utils::height_map::init_height_map;
+
+    /// Land with a shallow lake (on the cells (5, 5) and (5, 6)) and a deep one (on the cell
+    /// (2, 2)).
+    fn lake_scene() -> Scene<12, 12> {
+        let mut height_map = init_height_map::<12, 12>(1.2_f32);
+        height_map[5][5] = 0.5_f32;
+        height_map[5][6] = 0.5_f32;
+        height_map[2][2] = -2.5_f32;
+
+        Scene::from_height_map(height_map, ChaCha8Rng::seed_from_u64(1))
+    }
+
+    fn set_weather(scene: &mut Scene<12, 12>, kind: WeatherKind, lake_fill: f32) {
+        scene.weather = Weather {
+            kind,
+            ticks_left: 1000,
+            lake_fill,
+            wind: [0_f32; 2],
+        };
+    }
+
+    #[test]
+    fn should_fill_the_lakes_with_rain_and_empty_them_in_a_drought() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let mut rain = Weather {
+            kind: WeatherKind::Rain,
+            ticks_left: 10,
+            lake_fill: 0.5_f32,
+            wind: [0_f32; 2],
+        };
+        let mut drought = Weather {
+            kind: WeatherKind::Drought,
+            ..rain
+        };
+
+        rain.update(&mut rng);
+        drought.update(&mut rng);
+
+        assert!(rain.lake_fill > 0.5_f32);
+        assert!(drought.lake_fill < 0.5_f32);
+        assert!(rain.is_raining());
+    }
+
+    #[test]
+    fn should_clear_up_after_the_rain() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let mut weather = Weather {
+            kind: WeatherKind::Rain,
+            ticks_left: 1,
+            lake_fill: 1_f32,
+            wind: [0_f32; 2],
+        };
+
+        weather.update(&mut rng);
+
+        assert_eq!(weather.kind, WeatherKind::Clear);
+        assert!(weather.ticks_left > 0);
+    }
+
+    #[test]
+    fn should_find_the_weather_by_name() {
+        for kind in WeatherKind::ALL {
+            assert_eq!(WeatherKind::from_name(kind.get_name()), Some(kind));
+        }
+        assert_eq!(WeatherKind::from_name("snow"), None);
+    }
+
+    #[test]
+    fn should_dry_the_shallow_lakes() {
+        let mut scene = lake_scene();
+        set_weather(&mut scene, WeatherKind::Drought, 0.5_f32);
+
+        scene.update_lakes();
+
+        assert_eq!(scene.height_map[5][5], WATER_LEVEL);
+        assert_eq!(scene.height_map[5][6], WATER_LEVEL);
+        assert_eq!(scene.height_map[2][2], -2.5_f32);
+        assert_eq!(scene.get_terrain_version(), 1);
+        assert_eq!(scene.get_bed_map()[5][5], 0.5_f32);
+    }
+
+    #[test]
+    fn should_drown_the_plants_of_a_lake_that_refills() {
+        let mut scene = lake_scene();
+        set_weather(&mut scene, WeatherKind::Drought, 0_f32);
+        scene.update_lakes();
+        scene.add_entity(Entity::new([5_f32, 1_f32, 5_f32], EntityType::Plant1));
+        scene.add_entity(Entity::new([8_f32, 1.2_f32, 8_f32], EntityType::Plant1));
+
+        set_weather(&mut scene, WeatherKind::Rain, 1_f32);
+        scene.update_lakes();
+
+        assert_eq!(scene.height_map[5][5], 0.5_f32);
+        assert_eq!(scene.get_plant_count(), 1);
+        assert_eq!(scene.iter_plants().next().unwrap().position[0], 8_f32);
+    }
+
+    #[test]
+    fn should_strand_the_fish_of_a_lake_that_dries() {
+        let mut scene = lake_scene();
+        scene.add_entity(Entity::new([5_f32, 0.7_f32, 5_f32], EntityType::Fish));
+        scene.add_entity(Entity::new([2_f32, 0.7_f32, 2_f32], EntityType::Fish));
+        let subscription = scene.subscribe(|event| matches!(event.kind, EventKind::Died { .. }));
+
+        set_weather(&mut scene, WeatherKind::Drought, 0_f32);
+        let deaths = scene.update_lakes();
+
+        assert_eq!(deaths, vec![(EntityType::Fish, DeathCause::Stranded)]);
+        assert_eq!(scene.get_population(EntityType::Fish), 1);
+        assert_eq!(scene.drain_events(subscription).len(), 1);
+    }
+
+    #[test]
+    fn should_wet_the_soil_with_rain_and_dry_it_in_a_drought() {
+        let mut scene = lake_scene();
+        let base = scene.moisture_map[11][11];
+
+        set_weather(&mut scene, WeatherKind::Rain, 1_f32);
+        for _ in 0..20 {
+            scene.update_weather();
+        }
+        let wet = scene.moisture_map[11][11];
+
+        set_weather(&mut scene, WeatherKind::Drought, 1_f32);
+        for _ in 0..100 {
+            scene.update_weather();
+        }
+
+        assert!(wet > base);
+        assert!(scene.moisture_map[5][4] < scene.base_moisture[5][4]);
+    }
+
+    #[test]
+    fn should_tell_the_animals_that_it_rains() {
+        let mut scene = lake_scene();
+        assert!(!scene.get_environment(&[]).raining);
+
+        set_weather(&mut scene, WeatherKind::Rain, 1_f32);
+        assert!(scene.get_environment(&[]).raining);
+    }
+}
+
+mod seasons {
+    use super::*;
+
+    #[test]
+    fn should_follow_the_simulation_clock() {
+        let cycle = SeasonCycle::new(10);
+
+        assert_eq!(cycle.get_season(0), Season::Spring);
+        assert_eq!(cycle.get_season(9), Season::Spring);
+        assert_eq!(cycle.get_season(10), Season::Summer);
+        assert_eq!(cycle.get_season(35), Season::Winter);
+        assert_eq!(cycle.get_season(40), Season::Spring);
+        assert_eq!(cycle.get_progress(25), 0.5_f32);
+    }
+
+    #[test]
+    fn should_turn_the_leaves_orange_in_the_autumn() {
+        let cycle = SeasonCycle::new(100);
+        let green = [0.1_f32, 0.6_f32, 0.1_f32];
+
+        let summer = cycle.tint_leaves(110, green);
+        let autumn = cycle.tint_leaves(210, green);
+        let late_summer = cycle.tint_leaves(190, green);
+
+        assert_eq!(summer, green);
+        assert!(autumn[0] > autumn[1]);
+        assert!(late_summer[0] > summer[0] && late_summer[0] < autumn[0]);
+    }
+
+    #[test]
+    fn should_grow_the_plants_slower_in_the_winter() {
+        let mut scene = land_scene();
+        scene.set_season_length(100);
+        let mut plant = Entity::new([4_f32, 1.2_f32, 4_f32], EntityType::Plant1);
+        plant.set_growth(0_f32);
+        scene.add_entity(plant);
+
+        scene.grow_plants();
+        let spring_growth = scene.iter_plants().next().unwrap().get_growth();
+
+        scene.tick = 300;
+        scene.grow_plants();
+        let winter_growth = scene.iter_plants().next().unwrap().get_growth() - spring_growth;
+
+        assert!(winter_growth < spring_growth);
+    }
+
+    #[test]
+    fn should_only_mate_in_the_breeding_season() {
+        let mut scene = land_scene();
+        scene.set_season_length(100);
+        scene.add_entity(adult([3_f32, 1.2_f32, 3_f32], EntityType::Animal2));
+        scene.add_entity(adult([4_f32, 1.2_f32, 3_f32], EntityType::Animal2));
+
+        scene.tick = 300;
+        assert!(scene.resolve_mating().is_empty());
+
+        scene.tick = 400;
+        assert_eq!(scene.resolve_mating(), vec![EntityType::Animal2]);
+    }
+
+    /// Run with `cargo test --release -- --ignored` (the debug build is too slow).
+    #[test]
+    #[ignore]
+    fn should_keep_every_species_alive_for_two_years() {
+        let mut scene = Scene::<{ crate::ISLAND_WIDTH }, { crate::ISLAND_HEIGHT }>::with_seed(1);
+        scene.scatter_plants(EntityType::Plant1);
+        scene.scatter_plants(EntityType::Plant2);
+        for (entity_type, quantity) in crate::INITIAL_ANIMALS {
+            scene.create_entities(quantity, entity_type).unwrap();
+        }
+
+        for _ in 0..season::DEFAULT_SEASON_LENGTH * 8 {
+            scene.update();
+
+            for (entity_type, _) in crate::INITIAL_ANIMALS {
+                assert!(
+                    scene.get_population(entity_type) > 0,
+                    "{entity_type:?} died out"
+                );
+            }
+        }
+    }
+}
+
+mod resolve_deaths {
+    use super::*;
+
+    fn hungry_predator(position: [f32; 3]) -> Entity {
+        let mut predator = Entity::new(position, EntityType::Animal1);
+        predator.spend_energy(50_f32);
+        predator
+    }
+
+    #[test]
+    fn should_remove_a_caught_prey() {
+        let mut animals = vec![
+            hungry_predator([5_f32, 0_f32, 5_f32]),
+            Entity::new([6_f32, 0_f32, 5_f32], EntityType::Animal2),
+            Entity::new([15_f32, 0_f32, 5_f32], EntityType::Animal2),
+        ];
+
+        Scene::<25, 25>::resolve_deaths(&mut animals, &mut EventBus::default(), 0);
+
+        assert_eq!(animals.len(), 2);
+        assert_eq!(animals[0].get_type(), EntityType::Animal1);
+        assert_eq!(animals[1].position, [15_f32, 0_f32, 5_f32]);
+    }
+
+    #[test]
+    fn should_keep_animals_that_are_not_prey() {
+        let mut animals = vec![
+            Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal2),
+            Entity::new([6_f32, 0_f32, 5_f32], EntityType::Animal2),
+        ];
+
+        Scene::<25, 25>::resolve_deaths(&mut animals, &mut EventBus::default(), 0);
+
+        assert_eq!(animals.len(), 2);
+    }
+
+    #[test]
+    fn should_not_hunt_when_fed() {
+        let mut animals = vec![
+            Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal1),
+            Entity::new([6_f32, 0_f32, 5_f32], EntityType::Animal2),
+        ];
+
+        Scene::<25, 25>::resolve_deaths(&mut animals, &mut EventBus::default(), 0);
+
+        assert_eq!(animals.len(), 2);
+    }
+
+    #[test]
+    fn should_remove_starving_animals() {
+        let mut predator = Entity::new([5_f32, 0_f32, 5_f32], EntityType::Animal1);
+        predator.feed(-1000_f32);
+        let mut animals = vec![predator];
+
+        Scene::<25, 25>::resolve_deaths(&mut animals, &mut EventBus::default(), 0);
+
+        assert!(animals.is_empty());
+    }
+
+    #[test]
+    fn should_publish_the_meal_and_the_death() {
+        let mut events = EventBus::default();
+        let subscription = events.subscribe(|_| true);
+        let mut predator = hungry_predator([5_f32, 0_f32, 5_f32]);
+        predator.set_id(1);
+        let mut prey = Entity::new([6_f32, 0_f32, 5_f32], EntityType::Animal2);
+        prey.set_id(2);
+        let mut animals = vec![predator, prey];
+
+        Scene::<25, 25>::resolve_deaths(&mut animals, &mut events, 7);
+
+        let kinds: Vec<(EntityId, EventKind)> = events
+            .drain(subscription)
+            .iter()
+            .map(|event| (event.entity_id, event.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (
+                    1,
+                    EventKind::Ate {
+                        food: EntityType::Animal2
+                    }
+                ),
+                (
+                    2,
+                    EventKind::Died {
+                        cause: DeathCause::Eaten
+                    }
+                ),
+                (2, EventKind::EntityDespawned),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_report_the_death_causes() {
+        let mut starving = Entity::new([20_f32, 0_f32, 20_f32], EntityType::Animal1);
+        starving.spend_energy(1000_f32);
+
+        let mut animals = vec![
+            hungry_predator([5_f32, 0_f32, 5_f32]),
+            Entity::new([6_f32, 0_f32, 5_f32], EntityType::Animal2),
+            starving,
+        ];
+
+        let deaths = Scene::<25, 25>::resolve_deaths(&mut animals, &mut EventBus::default(), 0);
+
+        assert_eq!(animals.len(), 1);
+        assert_eq!(
+            deaths,
+            vec![
+                (EntityType::Animal2, DeathCause::Eaten),
+                (EntityType::Animal1, DeathCause::Starved),
+            ]
+        );
+    }
+}
+
+mod resolve_grazing {
+    use super::*;
+
+    /// Scene with a plant and a hungry herbivore beside it.
+    ///
+    /// # Arguments
+    /// * `mode` - Mode of the herbivore.
+    fn grazing_scene(mode: EntityMode) -> Scene<10, 10> {
+        let mut scene = Scene::<10, 10>::with_seed(1);
+        scene.add_entity(Entity::new([5_f32, 1.2_f32, 5_f32], EntityType::Plant1));
+        let mut animal = Entity::new([6_f32, 1.2_f32, 5_f32], EntityType::Animal2);
+        animal.spend_energy(50_f32);
+        animal.set_mode(mode, Condition::Script, &Senses::default());
+        scene.add_entity(animal);
+        scene
+    }
+
+    #[test]
+    fn should_eat_a_bite_on_each_tick() {
+        let mut scene = grazing_scene(EntityMode::Eating);
+        let energy = scene.get_animals()[0].get_energy();
+
+        scene.resolve_grazing();
+
+        let plant = scene.iter_plants().next().unwrap();
+        let nutrition = EntityType::Plant1.species().nutrition;
+        let ratio = scene.get_season_cycle().get_season(0).get_nutrition_ratio();
+        assert_eq!(plant.get_growth(), 1_f32 - entity::BITE_GROWTH);
+        assert_eq!(
+            scene.get_animals()[0].get_energy(),
+            energy + nutrition * entity::BITE_GROWTH * ratio
+        );
+    }
+
+    #[test]
+    fn should_eat_the_nearest_plant() {
+        let mut scene = grazing_scene(EntityMode::Eating);
+        scene.add_entity(Entity::new([6_f32, 1.2_f32, 5.5_f32], EntityType::Plant1));
+
+        scene.resolve_grazing();
+
+        let growth = |x: f32, z: f32| {
+            scene
+                .iter_plants()
+                .find(|plant| plant.position[0] == x && plant.position[2] == z)
+                .unwrap()
+                .get_growth()
+        };
+        assert_eq!(growth(5_f32, 5_f32), 1_f32);
+        assert_eq!(growth(6_f32, 5.5_f32), 1_f32 - entity::BITE_GROWTH);
+    }
+
+    #[test]
+    fn should_only_feed_the_eating_animals() {
+        let mut scene = grazing_scene(EntityMode::Wandering);
+        let energy = scene.get_animals()[0].get_energy();
+
+        scene.resolve_grazing();
+
+        assert_eq!(scene.iter_plants().next().unwrap().get_growth(), 1_f32);
+        assert_eq!(scene.get_animals()[0].get_energy(), energy);
+    }
+}
+
+mod resolve_mating {
+    use super::*;
+
+    /// Scene with a couple and young animals of the same species around it.
+    ///
+    /// # Arguments
+    /// * `young` - Amount of young animals.
+    fn crowded_scene(young: usize) -> Scene<12, 12> {
+        let mut scene = land_scene();
+        scene.add_entity(adult([3_f32, 1.2_f32, 3_f32], EntityType::Animal2));
+        scene.add_entity(adult([4_f32, 1.2_f32, 3_f32], EntityType::Animal2));
+        for x in 0..young {
+            scene.add_entity(Entity::new(
+                [x as f32 + 3_f32, 1.2_f32, 6_f32],
+                EntityType::Animal2,
+            ));
+        }
+        scene
+    }
+
+    #[test]
+    fn should_not_mate_when_crowded() {
+        let max_density = EntityType::Animal2.species().max_density as usize;
+
+        assert_eq!(
+            crowded_scene(max_density - 2).resolve_mating(),
+            vec![EntityType::Animal2]
+        );
+        assert!(crowded_scene(max_density - 1).resolve_mating().is_empty());
+    }
+
+    #[test]
+    fn should_start_the_offspring_with_the_energy_spent_by_the_parents() {
+        let mut scene = crowded_scene(0);
+        scene.resolve_mating();
+
+        let species = EntityType::Animal2.species();
+        assert_eq!(scene.get_animals().len(), 3);
+        let child = scene.get_animals().last().unwrap();
+        assert_eq!(child.get_energy(), 2_f32 * species.mating_cost);
+    }
+}
+
+mod events {
+    use super::*;
+
+    #[test]
+    fn should_publish_the_spawned_entities_with_new_ids() {
+        let mut scene = Scene::<20, 20>::with_seed(4);
+        let spawned = scene.subscribe(|event| event.kind == EventKind::EntitySpawned);
+
+        scene.create_entities(3, EntityType::Animal2).unwrap();
+        let events = scene.drain_events(spawned);
+
+        let ids: Vec<EntityId> = events.iter().map(|event| event.entity_id).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+        for (event, animal) in events.iter().zip(scene.get_animals()) {
+            assert_eq!(event.entity_type, EntityType::Animal2);
+            assert_eq!(event.position, animal.position);
+            assert_eq!(event.tick, 0);
+        }
+    }
+
+    #[test]
+    fn should_drain_the_events_only_once() {
+        let mut scene = Scene::<20, 20>::with_seed(4);
+        let all = scene.subscribe(|_| true);
+        scene.create_entities(2, EntityType::Animal1).unwrap();
+
+        assert_eq!(scene.drain_events(all).len(), 2);
+        assert!(scene.drain_events(all).is_empty());
+    }
+
+    #[test]
+    fn should_stop_keeping_the_events_after_unsubscribing() {
+        let mut scene = Scene::<20, 20>::with_seed(4);
+        let first = scene.subscribe(|_| true);
+        let second = scene.subscribe(|_| true);
+        scene.create_entities(2, EntityType::Animal1).unwrap();
+
+        scene.unsubscribe(first);
+        scene.create_entities(1, EntityType::Animal1).unwrap();
+
+        assert!(scene.drain_events(first).is_empty());
+        assert_eq!(scene.drain_events(second).len(), 3);
+        let third = scene.subscribe(|_| true);
+        assert!(third != first && third != second);
+    }
+
+    #[test]
+    fn should_only_keep_the_filtered_events() {
+        let mut scene = Scene::<30, 30>::with_seed(6);
+        let animal1 = scene.subscribe(|event| event.entity_type == EntityType::Animal1);
+        let moves = scene.subscribe(|event| matches!(event.kind, EventKind::ModeChanged { .. }));
+        scene.create_entities(4, EntityType::Animal1).unwrap();
+        scene.create_entities(4, EntityType::Animal2).unwrap();
+
+        for _ in 0..20 {
+            scene.update();
+        }
+
+        let events = scene.drain_events(animal1);
+        assert!(!events.is_empty());
+        assert!(events
+            .iter()
+            .all(|event| event.entity_type == EntityType::Animal1));
+        assert!(scene
+            .drain_events(moves)
+            .iter()
+            .all(|event| matches!(event.kind, EventKind::ModeChanged { .. })));
+    }
+
+    #[test]
+    fn should_publish_the_births_and_the_deaths() {
+        let mut scene = Scene::<40, 40>::with_seed(3);
+        let life =
+            scene.subscribe(|event| matches!(event.kind, EventKind::Born | EventKind::Died { .. }));
+        let despawned = scene.subscribe(|event| event.kind == EventKind::EntityDespawned);
+        scene.create_entities(6, EntityType::Animal1).unwrap();
+        scene.create_entities(20, EntityType::Animal2).unwrap();
+        assert_eq!(scene.create_entities(40, EntityType::Plant1), Ok(40));
+        for predator in scene.animals.iter_mut().take(6) {
+            predator.spend_energy(1000_f32);
+        }
+
+        let (mut births, mut deaths, mut removed) = (0, 0, 0);
+        for _ in 0..150 {
+            let report = scene.update();
+            births += report.births.len();
+            deaths += report.deaths.len();
+            removed += report.deaths.len();
+
+            for event in scene.drain_events(life) {
+                assert_eq!(event.tick, scene.get_tick());
+                match event.kind {
+                    EventKind::Born => births -= 1,
+                    _ => deaths -= 1,
+                }
+            }
+        }
+
+        assert_eq!((births, deaths), (0, 0));
+        assert!(removed > 0);
+        assert_eq!(scene.drain_events(despawned).len(), removed);
+    }
+}
+
+mod with_seed {
+    use super::*;
+
+    #[test]
+    fn should_create_the_same_scene_with_the_same_seed() {
+        let create = || {
+            let mut scene = Scene::<20, 20>::with_seed(42);
+            scene.create_entities(3, EntityType::Animal1).unwrap();
+            assert_eq!(scene.create_entities(5, EntityType::Plant2), Ok(5));
+            scene.update();
+            scene
+        };
+
+        let (first, second) = (create(), create());
+        let records = |entities: Vec<Entity>| -> Vec<String> {
+            entities.iter().map(Entity::get_record).collect()
+        };
+
+        assert_eq!(first.get_height_map(), second.get_height_map());
+        assert_eq!(
+            records(first.iter_plants().copied().collect()),
+            records(second.iter_plants().copied().collect())
+        );
+        assert_eq!(
+            records(first.get_animals().to_vec()),
+            records(second.get_animals().to_vec())
+        );
+    }
+}
+
+mod resolve_conflicts {
+    use super::*;
+
+    fn walking_to(position: [f32; 3], target: [f32; 3]) -> Entity {
+        let mut animal = Entity::new(position, EntityType::Animal2);
+        animal.steer_to(
+            target,
+            &[[2_f32; 10]; 10],
+            &entity::tree_entity::TreeEntity::new(Entity::new([-1000_f32; 3], EntityType::Plant1)),
+            &vec![],
+        );
+        animal
+    }
+
+    #[test]
+    fn should_keep_the_cell_for_the_first_animal() {
+        let animals = vec![
+            Entity::new([3_f32, 0_f32, 3_f32], EntityType::Animal2),
+            Entity::new([5_f32, 0_f32, 3_f32], EntityType::Animal2),
+        ];
+        let mut next_animals = vec![
+            walking_to([3_f32, 0_f32, 3_f32], [4_f32, 0_f32, 3_f32]),
+            walking_to([5_f32, 0_f32, 3_f32], [4_f32, 0_f32, 3_f32]),
+        ];
+        let mut reports = vec![MoveReport::default(); 2];
+
+        Scene::<10, 10>::resolve_conflicts(&animals, &mut next_animals, &mut reports);
+
+        assert_eq!(next_animals[0].get_target(), Some((4_f32, 3_f32)));
+        assert_eq!(next_animals[1].get_target(), None);
+        assert!(!reports[0].failed);
+        assert!(reports[1].failed);
+        assert_eq!(reports[1].collisions_avoided, 1);
+    }
+
+    #[test]
+    fn should_keep_the_cell_of_an_animal_already_walking() {
+        let animals = vec![
+            Entity::new([3_f32, 0_f32, 3_f32], EntityType::Animal2),
+            walking_to([5_f32, 0_f32, 3_f32], [4_f32, 0_f32, 3_f32]),
+        ];
+        let mut next_animals = vec![
+            walking_to([3_f32, 0_f32, 3_f32], [4_f32, 0_f32, 3_f32]),
+            animals[1],
+        ];
+        let mut reports = vec![MoveReport::default(); 2];
+
+        Scene::<10, 10>::resolve_conflicts(&animals, &mut next_animals, &mut reports);
+
+        assert_eq!(next_animals[0].get_target(), None);
+        assert_eq!(next_animals[1].get_target(), Some((4_f32, 3_f32)));
+    }
+
+    #[test]
+    fn should_give_back_the_energy_of_a_cancelled_climb() {
+        let mut height_map = [[1_f32; 10]; 10];
+        height_map[4][3] = 1.3_f32;
+        let animals = vec![
+            Entity::new([3_f32, 1_f32, 3_f32], EntityType::Animal2),
+            Entity::new([5_f32, 1_f32, 3_f32], EntityType::Animal2),
+        ];
+        let mut next_animals = animals.clone();
+        for next in next_animals.iter_mut() {
+            next.steer_to(
+                [4_f32, 1.3_f32, 3_f32],
+                &height_map,
+                &entity::tree_entity::TreeEntity::new(Entity::new(
+                    [-1000_f32; 3],
+                    EntityType::Plant1,
+                )),
+                &vec![],
+            );
+        }
+        let climb_energy = next_animals[1].get_energy();
+        assert!(climb_energy < animals[1].get_energy());
+        let mut reports = vec![MoveReport::default(); 2];
+
+        Scene::<10, 10>::resolve_conflicts(&animals, &mut next_animals, &mut reports);
+
+        assert_eq!(next_animals[0].get_energy(), climb_energy);
+        assert_eq!(next_animals[1].get_target(), None);
+        assert_eq!(next_animals[1].get_energy(), animals[1].get_energy());
+    }
+}
+
+mod move_animals {
+    use super::*;
+    use crate::utils::height_map::init_height_map;
+
+    #[test]
+    fn should_not_move_two_animals_to_the_same_cell() {
+        let mut scene = Scene::<40, 40>::with_seed(5);
+        scene.create_entities(120, EntityType::Animal2).unwrap();
+
+        for _ in 0..10 {
+            scene.move_animals();
+
+            let mut targets: Vec<(isize, isize)> = scene
+                .get_animals()
+                .iter()
+                .filter_map(|animal| animal.get_target())
+                .map(|(x, z)| (x as isize, z as isize))
+                .collect();
+            let count = targets.len();
+            targets.sort();
+            targets.dedup();
+
+            assert_eq!(targets.len(), count);
+        }
+    }
+
+    #[test]
+    fn should_spend_the_climb_energy_of_a_scripted_move_once() {
+        // The animal is on a pit, so any walk chosen on the tick is a climb.
+        let mut height_map = init_height_map::<12, 12>(1.5_f32);
+        height_map[3][3] = 1.2_f32;
+        let mut scene = Scene::from_height_map(height_map, ChaCha8Rng::seed_from_u64(1));
+        scene
+            .scripts
+            .load(EntityType::Animal2, "set_target(4, 3);")
+            .unwrap();
+        let mut animal = Entity::new([3_f32, 1.2_f32, 3_f32], EntityType::Animal2);
+        animal.set_mode(EntityMode::Wandering, Condition::Script, &Senses::default());
+        scene.add_entity(animal);
+        let climb_cost = animal.get_move_cost((4, 3), &height_map).unwrap();
+        let tick_cost =
+            EntityType::Animal2.species().energy_cost * animal.get_genome().get_energy_ratio();
+
+        scene.move_animals();
+
+        let climber = scene.get_animals()[0];
+        let expected = animal.get_energy() - tick_cost - climb_cost;
+        assert!(climb_cost > 0_f32);
+        assert_eq!(climber.get_target(), Some((4_f32, 3_f32)));
+        assert!((climber.get_energy() - expected).abs() < 1e-5);
+    }
+}
+
+mod benchmark {
+    use super::*;
+
+    /// Run with `cargo test --release -- --ignored` (the debug build is too slow).
+    #[test]
+    #[ignore]
+    fn should_update_ten_thousand_animals_in_real_time() {
+        let mut scene = Scene::<160, 160>::with_seed(1);
+        let _ = scene.create_entities(2000, EntityType::Plant1);
+        let _ = scene.create_entities(1500, EntityType::Plant2);
+        for (entity_type, count) in [
+            (EntityType::Animal2, 6000),
+            (EntityType::Animal1, 1000),
+            (EntityType::Fish, 1400),
+            (EntityType::Dolphin, 300),
+            (EntityType::Bird, 2500),
+        ] {
+            scene.create_entities(count, entity_type).unwrap();
+        }
+        assert!(scene.get_animals().len() > 10000);
+
+        let ticks = 20;
+        let start = std::time::Instant::now();
+        for _ in 0..ticks {
+            scene.update();
+        }
+        let tick_time = start.elapsed().as_secs_f32() / ticks as f32;
+
+        println!("{tick_time:.3}s per tick");
+        assert!(tick_time < 1_f32 / crate::TICK_RATE);
+    }
+}
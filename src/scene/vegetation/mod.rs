@@ -0,0 +1,155 @@
+use rand::Rng;
+
+use super::entity::species::Vegetation;
+use super::{EntityType, Scene};
+use crate::utils::height_map::{create_water_distance_map, get_slope, LAND_VALUE};
+use crate::utils::poisson_disk::poisson_disk;
+
+#[cfg(test)]
+mod test;
+
+impl<const W: usize, const H: usize> Scene<W, H> {
+    /// Scatter the plants of a species on the island following its vegetation rules.
+    ///
+    /// The points come from a Poisson-disk sampling (so the plants are never closer than the
+    /// species spacing) and only the cells with a suitable terrain are used. The density and
+    /// the clustering of the species decide which of these cells get a plant.
+    ///
+    /// Returns the amount of plants created.
+    ///
+    /// # Arguments
+    /// * `entity_type` - Species of the plants.
+    pub fn scatter_plants(&mut self, entity_type: EntityType) -> usize {
+        self.place_plants(entity_type, usize::MAX, true)
+    }
+
+    /// Create plants on the cells allowed by the vegetation rules of the species.
+    ///
+    /// Returns the amount of plants created.
+    ///
+    /// # Arguments
+    /// * `entity_type` - Species of the plants.
+    /// * `quantity` - Max amount of plants.
+    /// * `use_density` - If should skip points by the density and clustering of the species
+    ///   (otherwise all the suitable points are used until the quantity is reached).
+    pub(super) fn place_plants(
+        &mut self,
+        entity_type: EntityType,
+        quantity: usize,
+        use_density: bool,
+    ) -> usize {
+        let rules = match entity_type.species().vegetation {
+            Some(rules) => rules,
+            None => return 0,
+        };
+
+        let water_distances = create_water_distance_map(&self.height_map, rules.max_water_distance);
+        let points = poisson_disk(W as f32, H as f32, rules.min_spacing, &mut self.rng);
+        let mut placed = 0;
+
+        for point in points {
+            if placed >= quantity {
+                break;
+            }
+
+            let (x, z) = (point[0].round() as usize, point[1].round() as usize);
+            if x >= W
+                || z >= H
+                || !is_suitable(&rules, &self.height_map, &water_distances, (x, z))
+                || self.collides((x, z))
+                || self.has_plant_near(entity_type, (x, z), rules.min_spacing)
+            {
+                continue;
+            }
+
+            if use_density {
+                if !self.rng.gen_bool(rules.density.clamp(0_f32, 1_f32) as f64) {
+                    continue;
+                }
+
+                let clustered = self.has_plant_near(entity_type, (x, z), 2_f32 * rules.min_spacing);
+                if !clustered && self.rng.gen::<f32>() < rules.clustering {
+                    continue;
+                }
+            }
+
+            let plant = self.create_entity((x as isize, z as isize), entity_type);
+            self.add_entity(plant);
+            placed += 1;
+        }
+
+        placed
+    }
+
+    /// Keep only the cells with a terrain allowed by the vegetation rules of a species
+    /// (ignoring the spacing between the plants).
+    ///
+    /// # Arguments
+    /// * `entity_type` - Species of the plants.
+    /// * `cells` - The cells.
+    pub(super) fn get_suitable_cells(
+        &self,
+        entity_type: EntityType,
+        cells: Vec<(usize, usize)>,
+    ) -> Vec<(usize, usize)> {
+        let rules = match entity_type.species().vegetation {
+            Some(rules) => rules,
+            None => return cells,
+        };
+
+        let water_distances = create_water_distance_map(&self.height_map, rules.max_water_distance);
+        cells
+            .into_iter()
+            .filter(|cell| is_suitable(&rules, &self.height_map, &water_distances, *cell))
+            .collect()
+    }
+
+    /// Check if there is a plant of a species closer than a distance (ignoring the height).
+    ///
+    /// # Arguments
+    /// * `entity_type` - Species of the plants.
+    /// * `(x, z)` - The cell.
+    /// * `distance` - Max distance.
+    fn has_plant_near(
+        &self,
+        entity_type: EntityType,
+        (x, z): (usize, usize),
+        distance: f32,
+    ) -> bool {
+        let plants = match &self.plants {
+            Some(plants) => plants,
+            None => return false,
+        };
+
+        // The plants exactly on the min distance are allowed.
+        plants
+            .get_near([x as f32, 0_f32, z as f32], distance)
+            .iter()
+            .any(|plant| {
+                plant.get_type() == entity_type
+                    && plant.distance([x as f32, 0_f32, z as f32]) < distance
+            })
+    }
+}
+
+/// Check the terrain rules (land, elevation, slope and distance from the water) on a cell.
+///
+/// # Arguments
+/// * `rules` - Vegetation rules of the species.
+/// * `height_map` - Height map of the island.
+/// * `water_distances` - Distance from each cell to the water.
+/// * `(x, z)` - The cell.
+fn is_suitable<const W: usize, const H: usize>(
+    rules: &Vegetation,
+    height_map: &[[f32; H]; W],
+    water_distances: &[[usize; H]; W],
+    (x, z): (usize, usize),
+) -> bool {
+    let height = height_map[x][z];
+
+    height >= LAND_VALUE
+        && height >= rules.elevation.0
+        && height <= rules.elevation.1
+        && get_slope(height_map, x, z) <= rules.max_slope
+        && water_distances[x][z] <= rules.max_water_distance
+}
@@ -0,0 +1,122 @@
+use super::*;
+use crate::utils::height_map::init_height_map;
+
+fn rules() -> Vegetation {
+    Vegetation {
+        min_spacing: 3_f32,
+        density: 1_f32,
+        max_slope: 0.5_f32,
+        elevation: (1_f32, 2_f32),
+        max_water_distance: 3,
+        clustering: 0_f32,
+    }
+}
+
+mod scatter_plants {
+    use super::*;
+
+    #[test]
+    fn should_keep_the_min_spacing() {
+        let mut scene = Scene::<40, 40>::with_seed(4);
+
+        let placed = scene.scatter_plants(EntityType::Plant1);
+        let plants: Vec<_> = scene.iter_plants().collect();
+        let min_spacing = EntityType::Plant1.species().vegetation.unwrap().min_spacing;
+
+        assert!(placed > 0);
+        assert_eq!(plants.len(), placed);
+        for (i, plant) in plants.iter().enumerate() {
+            for other in plants.iter().skip(i + 1) {
+                assert!(plant.distance(other.position) >= min_spacing);
+            }
+        }
+    }
+
+    #[test]
+    fn should_only_use_suitable_cells() {
+        let mut scene = Scene::<40, 40>::with_seed(8);
+        let rules = EntityType::Plant1.species().vegetation.unwrap();
+        let water_distances =
+            create_water_distance_map(scene.get_height_map(), rules.max_water_distance);
+
+        scene.scatter_plants(EntityType::Plant1);
+
+        for plant in scene.iter_plants() {
+            let cell = (plant.position[0] as usize, plant.position[2] as usize);
+            assert!(is_suitable(
+                &rules,
+                scene.get_height_map(),
+                &water_distances,
+                cell
+            ));
+        }
+    }
+
+    #[test]
+    fn should_ignore_the_animals() {
+        let mut scene = Scene::<20, 20>::with_seed(1);
+
+        assert_eq!(scene.scatter_plants(EntityType::Animal1), 0);
+        assert_eq!(scene.get_plant_count(), 0);
+    }
+}
+
+mod place_plants {
+    use super::*;
+
+    #[test]
+    fn should_create_up_to_the_quantity() {
+        let mut scene = Scene::<40, 40>::with_seed(6);
+
+        assert_eq!(scene.place_plants(EntityType::Plant2, 3, false), 3);
+        assert_eq!(scene.get_plant_count(), 3);
+    }
+}
+
+mod is_suitable {
+    use super::*;
+
+    #[test]
+    fn should_check_the_elevation() {
+        let mut low_map = init_height_map::<5, 5>(1.2_f32);
+        low_map[0][0] = 0_f32;
+        let mut high_map = init_height_map::<5, 5>(2.5_f32);
+        high_map[0][0] = 0_f32;
+        let water_distances = create_water_distance_map(&low_map, 10);
+
+        assert!(is_suitable(&rules(), &low_map, &water_distances, (3, 3)));
+        assert!(!is_suitable(&rules(), &low_map, &water_distances, (0, 0)));
+        assert!(!is_suitable(&rules(), &high_map, &water_distances, (3, 3)));
+    }
+
+    #[test]
+    fn should_check_the_slope() {
+        let mut height_map = init_height_map::<5, 5>(1.2_f32);
+        height_map[0][0] = 0_f32;
+        height_map[2][3] = 1.9_f32;
+        let water_distances = create_water_distance_map(&height_map, 10);
+
+        assert!(!is_suitable(
+            &rules(),
+            &height_map,
+            &water_distances,
+            (2, 2)
+        ));
+        assert!(is_suitable(&rules(), &height_map, &water_distances, (3, 0)));
+    }
+
+    #[test]
+    fn should_check_the_distance_from_the_water() {
+        let mut height_map = init_height_map::<8, 1>(1.2_f32);
+        height_map[0][0] = 0_f32;
+        let water_distances = create_water_distance_map(&height_map, 10);
+
+        assert!(is_suitable(&rules(), &height_map, &water_distances, (3, 0)));
+        assert!(!is_suitable(
+            &rules(),
+            &height_map,
+            &water_distances,
+            (4, 0)
+        ));
+    }
+}
@@ -0,0 +1,159 @@
+use rand::Rng;
+
+/// Ticks of each spell of weather (min and max).
+const CLEAR_TICKS: (u32, u32) = (150, 400);
+const RAIN_TICKS: (u32, u32) = (40, 120);
+const DROUGHT_TICKS: (u32, u32) = (200, 500);
+/// Chances (between 0 and 1) of rain and drought after a clear spell (the rest is clear).
+const RAIN_CHANCE: f64 = 0.6_f64;
+const DROUGHT_CHANCE: f64 = 0.25_f64;
+/// Change of the lake fill (between 0 and 1) on each tick of each kind of weather.
+const RAIN_FILL: f32 = 0.004_f32;
+const CLEAR_EVAPORATION: f32 = 0.0005_f32;
+const DROUGHT_EVAPORATION: f32 = 0.002_f32;
+/// Chance (between 0 and 1) of a lightning on each tick of each kind of weather.
+const CLEAR_LIGHTNING: f64 = 0.0005_f64;
+const RAIN_LIGHTNING: f64 = 0.001_f64;
+const DROUGHT_LIGHTNING: f64 = 0.003_f64;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum WeatherKind {
+    Clear,
+    Rain,
+    /// Dry spell (the soil dries and the lakes shrink).
+    Drought,
+}
+
+impl WeatherKind {
+    pub const ALL: [WeatherKind; 3] = [WeatherKind::Clear, WeatherKind::Rain, WeatherKind::Drought];
+
+    /// Name used by the saved scenes.
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            WeatherKind::Clear => "clear",
+            WeatherKind::Rain => "rain",
+            WeatherKind::Drought => "drought",
+        }
+    }
+
+    /// Find a kind of weather by its name.
+    ///
+    /// # Arguments
+    /// * `name` - Name of the weather.
+    pub fn from_name(name: &str) -> Option<WeatherKind> {
+        Self::ALL.into_iter().find(|kind| kind.get_name() == name)
+    }
+
+    /// Soil moisture (between 0 and 1) that the weather leads to.
+    ///
+    /// # Arguments
+    /// * `base` - Moisture of the cell on clear weather (based on the distance to the water).
+    pub fn get_moisture(&self, base: f32) -> f32 {
+        match self {
+            WeatherKind::Clear => base,
+            WeatherKind::Rain => 1_f32,
+            WeatherKind::Drought => base * 0.25_f32,
+        }
+    }
+
+    /// Chance (between 0 and 1) of a lightning striking the island on a tick.
+    pub fn get_lightning_chance(&self) -> f64 {
+        match self {
+            WeatherKind::Clear => CLEAR_LIGHTNING,
+            WeatherKind::Rain => RAIN_LIGHTNING,
+            WeatherKind::Drought => DROUGHT_LIGHTNING,
+        }
+    }
+
+    /// Change of the lake fill on each tick.
+    fn get_fill_change(&self) -> f32 {
+        match self {
+            WeatherKind::Clear => -CLEAR_EVAPORATION,
+            WeatherKind::Rain => RAIN_FILL,
+            WeatherKind::Drought => -DROUGHT_EVAPORATION,
+        }
+    }
+}
+
+/// Weather of the island: spells of clear weather, rain and drought.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Weather {
+    pub kind: WeatherKind,
+    /// Ticks until the spell ends.
+    pub ticks_left: u32,
+    /// Water of the lakes (1 is full and 0 is dry).
+    pub lake_fill: f32,
+    /// Direction where the wind blows (on the x and z axes), with the speed (between 0 and 1)
+    /// as its length.
+    pub wind: [f32; 2],
+}
+
+impl Default for Weather {
+    fn default() -> Self {
+        Self {
+            kind: WeatherKind::Clear,
+            ticks_left: CLEAR_TICKS.0,
+            lake_fill: 1_f32,
+            wind: [0_f32; 2],
+        }
+    }
+}
+
+impl Weather {
+    pub fn is_raining(&self) -> bool {
+        self.kind == WeatherKind::Rain
+    }
+
+    /// Run a tick of the weather (starting a new spell when the current one ends).
+    ///
+    /// # Arguments
+    /// * `rand` - Random generator of the scene.
+    pub fn update(&mut self, rand: &mut impl Rng) {
+        self.lake_fill = (self.lake_fill + self.kind.get_fill_change()).clamp(0_f32, 1_f32);
+
+        self.ticks_left = self.ticks_left.saturating_sub(1);
+        if self.ticks_left == 0 {
+            self.start_spell(self.next_kind(rand), rand);
+        }
+    }
+
+    /// Start a spell of weather (with a new wind).
+    ///
+    /// # Arguments
+    /// * `kind` - Kind of weather.
+    /// * `rand` - Random generator used for the length of the spell.
+    pub fn start_spell(&mut self, kind: WeatherKind, rand: &mut impl Rng) {
+        let (min, max) = match kind {
+            WeatherKind::Clear => CLEAR_TICKS,
+            WeatherKind::Rain => RAIN_TICKS,
+            WeatherKind::Drought => DROUGHT_TICKS,
+        };
+
+        self.kind = kind;
+        self.ticks_left = rand.gen_range(min..=max);
+
+        let angle = rand.gen_range(0_f32..std::f32::consts::TAU);
+        let speed = rand.gen_range(0_f32..=1_f32);
+        self.wind = [angle.cos() * speed, angle.sin() * speed];
+    }
+
+    /// Choose the kind of the next spell (the rain and the drought are always followed by
+    /// clear weather).
+    ///
+    /// # Arguments
+    /// * `rand` - Random generator.
+    fn next_kind(&self, rand: &mut impl Rng) -> WeatherKind {
+        if self.kind != WeatherKind::Clear {
+            return WeatherKind::Clear;
+        }
+
+        let chance = rand.gen::<f64>();
+        if chance < RAIN_CHANCE {
+            WeatherKind::Rain
+        } else if chance < RAIN_CHANCE + DROUGHT_CHANCE {
+            WeatherKind::Drought
+        } else {
+            WeatherKind::Clear
+        }
+    }
+}
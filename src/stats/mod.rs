@@ -0,0 +1,353 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+use crate::scene::{Entity, EntityType, LifeReport, Scene, TRAIT_COUNT, TRAIT_NAMES};
+
+#[cfg(test)]
+mod test;
+
+/// File formats used to export the statistics.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ExportFormat {
+    Csv,
+    JsonLines,
+}
+
+impl ExportFormat {
+    /// Choose the format based on the file extension (`.csv` or `.jsonl`).
+    ///
+    /// # Arguments
+    /// * `path` - Path of the exported file.
+    pub fn from_path(path: &str) -> Result<Self, String> {
+        match std::path::Path::new(path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+        {
+            Some("csv") => Ok(ExportFormat::Csv),
+            Some("jsonl") | Some("ndjson") => Ok(ExportFormat::JsonLines),
+            _ => Err(format!(
+                "Unknown statistics format (use .csv or .jsonl): {path}"
+            )),
+        }
+    }
+}
+
+/// Distribution of a heritable trait on the animals of a type.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct TraitStats {
+    pub mean: f32,
+    /// Standard deviation.
+    pub deviation: f32,
+}
+
+impl TraitStats {
+    /// Calculate the distribution of the values of a trait (zero when there is no value).
+    ///
+    /// # Arguments
+    /// * `values` - Values of the trait on each animal.
+    pub fn from_values(values: &[f32]) -> Self {
+        if values.is_empty() {
+            return Self::default();
+        }
+
+        let count = values.len() as f32;
+        let mean = values.iter().sum::<f32>() / count;
+        let variance = values
+            .iter()
+            .map(|value| (value - mean) * (value - mean))
+            .sum::<f32>()
+            / count;
+
+        Self {
+            mean,
+            deviation: variance.sqrt(),
+        }
+    }
+}
+
+/// Average needs of the animals of a type.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct NeedStats {
+    pub energy: f32,
+    pub hydration: f32,
+    pub stamina: f32,
+}
+
+impl NeedStats {
+    /// Calculate the average needs of some animals (zero when there is no animal).
+    ///
+    /// # Arguments
+    /// * `animals` - The animals.
+    pub fn from_animals(animals: &[&Entity]) -> Self {
+        if animals.is_empty() {
+            return Self::default();
+        }
+
+        let count = animals.len() as f32;
+        let average = |need: fn(&Entity) -> f32| {
+            animals.iter().map(|animal| need(animal)).sum::<f32>() / count
+        };
+
+        Self {
+            energy: average(Entity::get_energy),
+            hydration: average(Entity::get_hydration),
+            stamina: average(Entity::get_stamina),
+        }
+    }
+}
+
+/// Metrics of a simulation tick.
+#[derive(Clone, PartialEq, Debug)]
+pub struct TickStats {
+    pub tick: u64,
+    /// Population of each entity type (in the `EntityType::ALL` order).
+    pub populations: Vec<(EntityType, usize)>,
+    /// Count of the plants of all types.
+    pub plants: usize,
+    /// Average needs of each animal type.
+    pub average_needs: Vec<(EntityType, NeedStats)>,
+    /// Distribution of each trait (in the `TRAIT_NAMES` order) of each animal type.
+    pub traits: Vec<(EntityType, [TraitStats; TRAIT_COUNT])>,
+    pub births: usize,
+    pub deaths: usize,
+    pub distance_travelled: f32,
+    pub collisions_avoided: usize,
+    pub failed_moves: usize,
+}
+
+impl TickStats {
+    /// Collect the metrics of the scene after a simulation step.
+    ///
+    /// # Arguments
+    /// * `tick` - Number of the tick.
+    /// * `scene` - The scene after the step.
+    /// * `report` - Births and deaths of the step.
+    pub fn collect<const W: usize, const H: usize>(
+        tick: u64,
+        scene: &Scene<W, H>,
+        report: &LifeReport,
+    ) -> Self {
+        let populations = EntityType::ALL
+            .iter()
+            .map(|entity_type| (*entity_type, scene.get_population(*entity_type)))
+            .collect();
+
+        let average_needs = EntityType::ALL
+            .iter()
+            .filter(|entity_type| !entity_type.is_plant())
+            .map(|entity_type| {
+                let animals: Vec<&Entity> = scene
+                    .get_animals()
+                    .iter()
+                    .filter(|animal| animal.get_type() == *entity_type)
+                    .collect();
+
+                (*entity_type, NeedStats::from_animals(&animals))
+            })
+            .collect();
+
+        let traits = EntityType::ALL
+            .iter()
+            .filter(|entity_type| !entity_type.is_plant())
+            .map(|entity_type| {
+                let genomes: Vec<[f32; TRAIT_COUNT]> = scene
+                    .get_animals()
+                    .iter()
+                    .filter(|animal| animal.get_type() == *entity_type)
+                    .map(|animal| animal.get_genome().get_traits())
+                    .collect();
+
+                let distributions = std::array::from_fn(|i| {
+                    let values: Vec<f32> = genomes.iter().map(|traits| traits[i]).collect();
+                    TraitStats::from_values(&values)
+                });
+
+                (*entity_type, distributions)
+            })
+            .collect();
+
+        let movement = scene.get_last_movement();
+
+        Self {
+            tick,
+            populations,
+            plants: scene.get_plant_count(),
+            average_needs,
+            traits,
+            births: report.births.len(),
+            deaths: report.deaths.len(),
+            distance_travelled: movement.distance_travelled,
+            collisions_avoided: movement.collisions_avoided,
+            failed_moves: movement.failed_moves,
+        }
+    }
+
+    fn get_csv_header(&self) -> String {
+        let mut columns = vec!["tick".to_string()];
+
+        for (entity_type, _) in self.populations.iter() {
+            columns.push(entity_type.get_name());
+        }
+        columns.push("plants".to_string());
+        for (entity_type, _) in self.average_needs.iter() {
+            for need in ["energy", "hydration", "stamina"] {
+                columns.push(format!("{}_{need}", entity_type.get_name()));
+            }
+        }
+        for (entity_type, _) in self.traits.iter() {
+            for name in TRAIT_NAMES {
+                columns.push(format!("{}_{name}_mean", entity_type.get_name()));
+                columns.push(format!("{}_{name}_sd", entity_type.get_name()));
+            }
+        }
+
+        columns.extend(
+            [
+                "births",
+                "deaths",
+                "distance_travelled",
+                "collisions_avoided",
+                "failed_moves",
+            ]
+            .map(String::from),
+        );
+
+        columns.join(",")
+    }
+
+    fn to_csv_row(&self) -> String {
+        let mut columns = vec![self.tick.to_string()];
+
+        for (_, population) in self.populations.iter() {
+            columns.push(population.to_string());
+        }
+        columns.push(self.plants.to_string());
+        for (_, needs) in self.average_needs.iter() {
+            columns.push(needs.energy.to_string());
+            columns.push(needs.hydration.to_string());
+            columns.push(needs.stamina.to_string());
+        }
+        for (_, distributions) in self.traits.iter() {
+            for distribution in distributions {
+                columns.push(distribution.mean.to_string());
+                columns.push(distribution.deviation.to_string());
+            }
+        }
+
+        columns.push(self.births.to_string());
+        columns.push(self.deaths.to_string());
+        columns.push(self.distance_travelled.to_string());
+        columns.push(self.collisions_avoided.to_string());
+        columns.push(self.failed_moves.to_string());
+
+        columns.join(",")
+    }
+
+    fn to_json(&self) -> String {
+        let populations: Vec<String> = self
+            .populations
+            .iter()
+            .map(|(entity_type, population)| format!("\"{}\":{population}", entity_type.get_name()))
+            .collect();
+        let average_needs: Vec<String> = self
+            .average_needs
+            .iter()
+            .map(|(entity_type, needs)| {
+                format!(
+                    "\"{}\":{{\"energy\":{},\"hydration\":{},\"stamina\":{}}}",
+                    entity_type.get_name(),
+                    needs.energy,
+                    needs.hydration,
+                    needs.stamina
+                )
+            })
+            .collect();
+        let traits: Vec<String> = self
+            .traits
+            .iter()
+            .map(|(entity_type, distributions)| {
+                let distributions: Vec<String> = TRAIT_NAMES
+                    .iter()
+                    .zip(distributions.iter())
+                    .map(|(name, distribution)| {
+                        format!(
+                            "\"{name}\":{{\"mean\":{},\"sd\":{}}}",
+                            distribution.mean, distribution.deviation
+                        )
+                    })
+                    .collect();
+
+                format!(
+                    "\"{}\":{{{}}}",
+                    entity_type.get_name(),
+                    distributions.join(",")
+                )
+            })
+            .collect();
+
+        format!(
+            "{{\"tick\":{},\"population\":{{{}}},\"plants\":{},\"average_needs\":{{{}}},\"traits\":{{{}}},\"births\":{},\"deaths\":{},\"distance_travelled\":{},\"collisions_avoided\":{},\"failed_moves\":{}}}",
+            self.tick,
+            populations.join(","),
+            self.plants,
+            average_needs.join(","),
+            traits.join(","),
+            self.births,
+            self.deaths,
+            self.distance_travelled,
+            self.collisions_avoided,
+            self.failed_moves
+        )
+    }
+}
+
+/// Time series with the metrics of each tick.
+#[derive(Default)]
+pub struct StatsRecorder {
+    records: Vec<TickStats>,
+}
+
+impl StatsRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, stats: TickStats) {
+        self.records.push(stats);
+    }
+
+    /// Write all the records.
+    ///
+    /// # Arguments
+    /// * `writer` - Where the records are written.
+    /// * `format` - Format of the output.
+    pub fn write(&self, writer: &mut impl Write, format: ExportFormat) -> io::Result<()> {
+        if format == ExportFormat::Csv {
+            if let Some(first) = self.records.first() {
+                writeln!(writer, "{}", first.get_csv_header())?;
+            }
+        }
+
+        for record in self.records.iter() {
+            match format {
+                ExportFormat::Csv => writeln!(writer, "{}", record.to_csv_row())?,
+                ExportFormat::JsonLines => writeln!(writer, "{}", record.to_json())?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Save all the records in a file (the format is chosen by the extension).
+    ///
+    /// # Arguments
+    /// * `path` - Path of the file.
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let format = ExportFormat::from_path(path)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+        let mut writer = BufWriter::new(File::create(path)?);
+
+        self.write(&mut writer, format)?;
+        writer.flush()
+    }
+}
@@ -0,0 +1,148 @@
+use super::*;
+
+fn create_stats(tick: u64) -> TickStats {
+    TickStats {
+        tick,
+        populations: vec![(EntityType::Animal1, 2), (EntityType::Plant1, 10)],
+        plants: 10,
+        average_needs: vec![(
+            EntityType::Animal1,
+            NeedStats {
+                energy: 50.5_f32,
+                hydration: 20_f32,
+                stamina: 0.5_f32,
+            },
+        )],
+        traits: vec![(
+            EntityType::Animal1,
+            [TraitStats {
+                mean: 1_f32,
+                deviation: 0.5_f32,
+            }; TRAIT_COUNT],
+        )],
+        births: 1,
+        deaths: 0,
+        distance_travelled: 1.5_f32,
+        collisions_avoided: 3,
+        failed_moves: 1,
+    }
+}
+
+mod from_path {
+    use super::*;
+
+    #[test]
+    fn should_choose_the_format_by_extension() {
+        assert_eq!(ExportFormat::from_path("out.csv"), Ok(ExportFormat::Csv));
+        assert_eq!(
+            ExportFormat::from_path("out/stats.jsonl"),
+            Ok(ExportFormat::JsonLines)
+        );
+        assert!(ExportFormat::from_path("stats.txt").is_err());
+    }
+}
+
+mod write {
+    use super::*;
+
+    #[test]
+    fn should_write_csv_with_header() {
+        let mut recorder = StatsRecorder::new();
+        recorder.record(create_stats(1));
+        recorder.record(create_stats(2));
+
+        let mut output = vec![];
+        recorder.write(&mut output, ExportFormat::Csv).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "tick,animal1,plant1,plants,animal1_energy,animal1_hydration,animal1_stamina,\
+             animal1_speed_mean,animal1_speed_sd,animal1_sight_mean,animal1_sight_sd,animal1_size_mean,animal1_size_sd,animal1_metabolism_mean,animal1_metabolism_sd,animal1_tint_r_mean,animal1_tint_r_sd,animal1_tint_g_mean,animal1_tint_g_sd,animal1_tint_b_mean,animal1_tint_b_sd,\
+             births,deaths,distance_travelled,collisions_avoided,failed_moves\n\
+             1,2,10,10,50.5,20,0.5,1,0.5,1,0.5,1,0.5,1,0.5,1,0.5,1,0.5,1,0.5,1,0,1.5,3,1\n\
+             2,2,10,10,50.5,20,0.5,1,0.5,1,0.5,1,0.5,1,0.5,1,0.5,1,0.5,1,0.5,1,0,1.5,3,1\n"
+        );
+    }
+
+    #[test]
+    fn should_write_a_json_object_per_line() {
+        let mut recorder = StatsRecorder::new();
+        recorder.record(create_stats(1));
+
+        let mut output = vec![];
+        recorder
+            .write(&mut output, ExportFormat::JsonLines)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "{\"tick\":1,\"population\":{\"animal1\":2,\"plant1\":10},\"plants\":10,\
+             \"average_needs\":{\"animal1\":{\"energy\":50.5,\"hydration\":20,\"stamina\":0.5}},\
+             \"traits\":{\"animal1\":{\"speed\":{\"mean\":1,\"sd\":0.5},\"sight\":{\"mean\":1,\"sd\":0.5},\"size\":{\"mean\":1,\"sd\":0.5},\"metabolism\":{\"mean\":1,\"sd\":0.5},\"tint_r\":{\"mean\":1,\"sd\":0.5},\"tint_g\":{\"mean\":1,\"sd\":0.5},\"tint_b\":{\"mean\":1,\"sd\":0.5}}},\"births\":1,\"deaths\":0,\"distance_travelled\":1.5,\"collisions_avoided\":3,\"failed_moves\":1}\n"
+        );
+    }
+}
+
+mod collect {
+    use super::*;
+
+    #[test]
+    fn should_collect_the_scene_metrics() {
+        let mut scene = Scene::<20, 20>::new();
+        scene.create_entities(3, EntityType::Animal2).unwrap();
+        scene.create_entities(4, EntityType::Plant1).ok();
+
+        let report = scene.update();
+        let stats = TickStats::collect(1, &scene, &report);
+
+        assert_eq!(stats.tick, 1);
+        assert_eq!(stats.populations.len(), EntityType::ALL.len());
+        assert!(stats
+            .populations
+            .contains(&(EntityType::Plant1, scene.get_population(EntityType::Plant1))));
+        assert_eq!(stats.plants, scene.get_plant_count());
+        assert_eq!(stats.traits.len(), stats.average_needs.len());
+        assert!(stats.traits.iter().any(|(entity_type, distributions)| {
+            *entity_type == EntityType::Animal2 && distributions[0].mean == 0.5_f32
+        }));
+        assert_eq!(
+            stats.average_needs.len(),
+            EntityType::ALL
+                .iter()
+                .filter(|entity_type| !entity_type.is_plant())
+                .count()
+        );
+    }
+}
+
+mod from_animals {
+    use super::*;
+
+    #[test]
+    fn should_average_each_need() {
+        let mut tired = Entity::new([0_f32; 3], EntityType::Animal2);
+        tired.spend_energy(10_f32);
+        let rested = Entity::new([1_f32, 0_f32, 0_f32], EntityType::Animal2);
+        let species = EntityType::Animal2.species();
+
+        let needs = NeedStats::from_animals(&[&tired, &rested]);
+
+        assert_eq!(needs.energy, species.max_energy - 5_f32);
+        assert_eq!(needs.hydration, species.max_hydration);
+        assert_eq!(needs.stamina, 1_f32);
+        assert_eq!(NeedStats::from_animals(&[]), NeedStats::default());
+    }
+}
+
+mod from_values {
+    use super::*;
+
+    #[test]
+    fn should_calculate_the_mean_and_the_standard_deviation() {
+        let stats = TraitStats::from_values(&[1_f32, 3_f32, 3_f32, 1_f32]);
+
+        assert_eq!(stats.mean, 2_f32);
+        assert_eq!(stats.deviation, 1_f32);
+        assert_eq!(TraitStats::from_values(&[]), TraitStats::default());
+    }
+}
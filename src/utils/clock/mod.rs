@@ -0,0 +1,88 @@
+use std::time::{Duration, Instant};
+
+#[cfg(test)]
+mod test;
+
+/// Max ticks run in a single frame (avoids the simulation spiral after a long frame).
+const MAX_TICKS_PER_FRAME: u32 = 10;
+
+/// Fixed timestep clock (accumulator pattern) for the simulation.
+pub struct SimulationClock {
+    tick_duration: Duration,
+    accumulator: Duration,
+    last_instant: Instant,
+    ticks: u64,
+}
+
+impl SimulationClock {
+    /// Constructor for the clock.
+    ///
+    /// # Arguments
+    /// * `tick_rate` - Simulation ticks per second.
+    pub fn new(tick_rate: f32) -> Self {
+        Self {
+            tick_duration: Self::get_tick_duration(tick_rate),
+            accumulator: Duration::ZERO,
+            last_instant: Instant::now(),
+            ticks: 0,
+        }
+    }
+
+    fn get_tick_duration(tick_rate: f32) -> Duration {
+        Duration::from_secs_f32(1_f32 / tick_rate.max(f32::EPSILON))
+    }
+
+    /// Advance the clock to the current instant.
+    ///
+    /// Returns how many simulation ticks should run.
+    pub fn update(&mut self) -> u32 {
+        self.advance(Instant::now())
+    }
+
+    /// Advance the clock to an instant.
+    ///
+    /// Returns how many simulation ticks should run.
+    ///
+    /// # Arguments
+    /// * `now` - The current instant.
+    pub fn advance(&mut self, now: Instant) -> u32 {
+        self.accumulator += now.saturating_duration_since(self.last_instant);
+        self.last_instant = now;
+
+        let mut ticks = 0;
+        while self.accumulator >= self.tick_duration {
+            self.accumulator -= self.tick_duration;
+            ticks += 1;
+
+            if ticks == MAX_TICKS_PER_FRAME {
+                self.accumulator = self.accumulator.min(self.tick_duration);
+                break;
+            }
+        }
+
+        self.ticks += ticks as u64;
+        ticks
+    }
+
+    /// Fraction (between 0 and 1) of the next tick that already passed, used to interpolate.
+    pub fn get_alpha(&self) -> f32 {
+        (self.accumulator.as_secs_f32() / self.tick_duration.as_secs_f32()).min(1_f32)
+    }
+
+    /// Total of ticks since the clock started.
+    pub fn get_ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    pub fn get_tick_rate(&self) -> f32 {
+        1_f32 / self.tick_duration.as_secs_f32()
+    }
+
+    /// Change the tick rate (the simulation speed).
+    ///
+    /// # Arguments
+    /// * `tick_rate` - Simulation ticks per second.
+    pub fn set_tick_rate(&mut self, tick_rate: f32) {
+        self.tick_duration = Self::get_tick_duration(tick_rate);
+    }
+}
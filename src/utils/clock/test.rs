@@ -0,0 +1,54 @@
+use super::*;
+
+mod advance {
+    use super::*;
+
+    #[test]
+    fn should_run_a_tick_per_tick_duration() {
+        let mut clock = SimulationClock::new(2_f32);
+        let start = clock.last_instant;
+
+        assert_eq!(clock.advance(start + Duration::from_millis(499)), 0);
+        assert_eq!(clock.advance(start + Duration::from_millis(500)), 1);
+        assert_eq!(clock.advance(start + Duration::from_millis(1600)), 2);
+        assert_eq!(clock.get_ticks(), 3);
+    }
+
+    #[test]
+    fn should_limit_the_ticks_per_frame() {
+        let mut clock = SimulationClock::new(10_f32);
+        let start = clock.last_instant;
+
+        assert_eq!(
+            clock.advance(start + Duration::from_secs(60)),
+            MAX_TICKS_PER_FRAME
+        );
+        assert!(clock.get_alpha() <= 1_f32);
+    }
+}
+
+mod get_alpha {
+    use super::*;
+
+    #[test]
+    fn should_be_the_fraction_of_the_next_tick() {
+        let mut clock = SimulationClock::new(1_f32);
+        let start = clock.last_instant;
+
+        clock.advance(start + Duration::from_millis(1250));
+
+        assert!((clock.get_alpha() - 0.25_f32).abs() < 0.001_f32);
+    }
+}
+
+mod set_tick_rate {
+    use super::*;
+
+    #[test]
+    fn should_change_the_tick_duration() {
+        let mut clock = SimulationClock::new(1_f32);
+        clock.set_tick_rate(4_f32);
+
+        assert!((clock.get_tick_rate() - 4_f32).abs() < 0.001_f32);
+    }
+}
@@ -26,12 +26,12 @@ pub fn init_height_map<const W: usize, const H: usize>(default_value: f32) -> [[
 /// # Arguments
 /// * `height_map` - Matrix with initial height_map.
 /// * `max_size` - Total size of the island.
+/// * `rand` - Random generator used to choose the land cells.
 pub fn create_land<const W: usize, const H: usize>(
     height_map: &mut [[f32; H]; W],
     max_size: usize,
+    rand: &mut impl Rng,
 ) {
-    let mut rand = rand::thread_rng();
-
     let half_w = W / 2;
     let half_h = H / 2;
 
@@ -39,7 +39,7 @@ pub fn create_land<const W: usize, const H: usize>(
 
     let mut land_count = 1;
     while land_count < max_size {
-        let (x, y) = get_height_map_position(height_map, &mut rand);
+        let (x, y) = get_height_map_position(height_map, rand);
 
         height_map[x][y] = LAND_VALUE;
         land_count += 1;
@@ -49,7 +49,7 @@ pub fn create_land<const W: usize, const H: usize>(
 /// Get a valid position for create a land based on height map.
 /// # Arguments
 /// * `height_map` - Matrix with height map data.
-/// * `rand` - ThreadRng for generate randomly coords.
+/// * `rand` - Random generator for generate randomly coords.
 ///
 /// # Examples
 /// ```
@@ -69,7 +69,7 @@ pub fn create_land<const W: usize, const H: usize>(
 /// ```
 fn get_height_map_position<const W: usize, const H: usize>(
     height_map: &[[f32; H]; W],
-    rand: &mut ThreadRng,
+    rand: &mut impl Rng,
 ) -> (usize, usize) {
     let mut x = rand.gen_range(0..W);
     let mut y = rand.gen_range(0..H);
@@ -323,6 +323,143 @@ fn get_valid_neightbors<const W: usize, const H: usize>(x: isize, y: isize) -> V
     valid_neightbors
 }
 
+/// Create a map with the distance (in cells, counting the diagonals as one) from each cell
+/// to the nearest water cell (`usize::MAX` when it is farther than the max distance).
+///
+/// # Arguments
+/// * `height_map` - Height map used to find the water cells.
+/// * `max_distance` - Max distance searched from the water.
+pub fn create_water_distance_map<const W: usize, const H: usize>(
+    height_map: &[[f32; H]; W],
+    max_distance: usize,
+) -> [[usize; H]; W] {
+    let mut distances = [[usize::MAX; H]; W];
+    let mut water = vec![];
+
+    for x in 0..W {
+        for y in 0..H {
+            if height_map[x][y] < LAND_VALUE {
+                distances[x][y] = 0;
+                water.push((x, y));
+            }
+        }
+    }
+
+    flood_fill::<W, H>(water, |(x, y), (n_x, n_y)| {
+        let distance = distances[x][y] + 1;
+        if distance > max_distance || distances[n_x][n_y] <= distance {
+            return false;
+        }
+
+        distances[n_x][n_y] = distance;
+        true
+    });
+
+    distances
+}
+
+/// Find the water cells of the lakes (the water that does not reach the border of the map).
+///
+/// # Arguments
+/// * `height_map` - Height map used to find the water cells.
+pub fn find_lakes<const W: usize, const H: usize>(
+    height_map: &[[f32; H]; W],
+) -> Vec<(usize, usize)> {
+    let mut sea = [[false; H]; W];
+    let mut border_water = vec![];
+
+    for x in 0..W {
+        for y in 0..H {
+            let border = x == 0 || y == 0 || x == W - 1 || y == H - 1;
+            if border && height_map[x][y] < LAND_VALUE {
+                sea[x][y] = true;
+                border_water.push((x, y));
+            }
+        }
+    }
+
+    flood_fill::<W, H>(border_water, |_, (n_x, n_y)| {
+        if sea[n_x][n_y] || height_map[n_x][n_y] >= LAND_VALUE {
+            return false;
+        }
+
+        sea[n_x][n_y] = true;
+        true
+    });
+
+    let mut lakes = vec![];
+    for x in 0..W {
+        for y in 0..H {
+            if height_map[x][y] < LAND_VALUE && !sea[x][y] {
+                lakes.push((x, y));
+            }
+        }
+    }
+
+    lakes
+}
+
+/// Breadth-first flood from some cells to their neighbors (including the diagonals).
+///
+/// # Arguments
+/// * `seeds` - Cells where the flood starts.
+/// * `spread` - Called with a flooded cell and one of its neighbors; returns if the neighbor
+///   is flooded (it must remember the flooded cells, so they are not flooded again).
+fn flood_fill<const W: usize, const H: usize>(
+    seeds: Vec<(usize, usize)>,
+    mut spread: impl FnMut((usize, usize), (usize, usize)) -> bool,
+) {
+    let mut queue = std::collections::VecDeque::from(seeds);
+
+    while let Some((x, y)) = queue.pop_front() {
+        for (n_x, n_y) in get_valid_neightbors::<W, H>(x as isize, y as isize) {
+            if n_x < W && n_y < H && spread((x, y), (n_x, n_y)) {
+                queue.push_back((n_x, n_y));
+            }
+        }
+    }
+}
+
+/// Create a moisture map (between 0 and 1) where the cells closer to the water are wetter.
+///
+/// # Arguments
+/// * `height_map` - Height map used to find the water cells.
+/// * `max_distance` - Distance (in cells) from the water where the soil becomes dry.
+pub fn create_moisture_map<const W: usize, const H: usize>(
+    height_map: &[[f32; H]; W],
+    max_distance: usize,
+) -> [[f32; H]; W] {
+    let distances = create_water_distance_map(height_map, max_distance);
+    let mut moisture_map = [[0_f32; H]; W];
+
+    for x in 0..W {
+        for y in 0..H {
+            let distance = distances[x][y].min(max_distance) as f32;
+            moisture_map[x][y] = 1_f32 - distance / max_distance.max(1) as f32;
+        }
+    }
+
+    moisture_map
+}
+
+/// Get the slope of a cell (the biggest height difference to its neighbors).
+///
+/// # Arguments
+/// * `height_map` - The height map.
+/// * `x` - Coordinate x of the cell.
+/// * `y` - Coordinate y of the cell.
+pub fn get_slope<const W: usize, const H: usize>(
+    height_map: &[[f32; H]; W],
+    x: usize,
+    y: usize,
+) -> f32 {
+    get_valid_neightbors::<W, H>(x as isize, y as isize)
+        .into_iter()
+        .filter(|(n_x, n_y)| *n_x < W && *n_y < H)
+        .map(|(n_x, n_y)| (height_map[n_x][n_y] - height_map[x][y]).abs())
+        .fold(0_f32, f32::max)
+}
+
 pub fn print_height_map<const W: usize, const H: usize>(height_map: &[[f32; H]; W]) {
     for row in height_map.iter() {
         for item in row {
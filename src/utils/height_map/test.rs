@@ -22,7 +22,7 @@ mod create_land {
 
         let mut height_map = init_height_map::<4, 4>(0_f32);
 
-        create_land(&mut height_map, LAND_SIZE);
+        create_land(&mut height_map, LAND_SIZE, &mut rand::thread_rng());
 
         let mut count_land = 0;
 
@@ -104,3 +104,80 @@ mod create_lakes {
         assert!(total_lake > 0 && total_lake <= EXPECTED_TOTAL_LAKE);
     }
 }
+
+mod create_moisture_map {
+    use super::*;
+
+    #[test]
+    fn should_be_wetter_near_the_water() {
+        let mut height_map = init_height_map::<5, 1>(LAND_VALUE);
+        height_map[0][0] = LAKE_VALUE;
+
+        let moisture_map = create_moisture_map(&height_map, 4);
+
+        assert_eq!(moisture_map[0][0], 1_f32);
+        assert_eq!(moisture_map[4][0], 0_f32);
+
+        for x in 1..5 {
+            assert!(moisture_map[x][0] < moisture_map[x - 1][0]);
+        }
+    }
+
+    #[test]
+    fn should_be_dry_without_water() {
+        let height_map = init_height_map::<3, 3>(LAND_VALUE);
+
+        let moisture_map = create_moisture_map(&height_map, 2);
+
+        assert_eq!(moisture_map, [[0_f32; 3]; 3]);
+    }
+}
+
+mod find_lakes {
+    use super::*;
+
+    #[test]
+    fn should_ignore_the_sea() {
+        let mut height_map = init_height_map::<7, 7>(LAND_VALUE);
+        height_map[0][3] = SEA_VALUE;
+        height_map[1][3] = SEA_VALUE;
+        height_map[4][4] = LAKE_VALUE;
+        height_map[5][5] = LAKE_VALUE;
+
+        let lakes = find_lakes(&height_map);
+
+        assert_eq!(lakes, vec![(4, 4), (5, 5)]);
+    }
+}
+
+mod create_water_distance_map {
+    use super::*;
+
+    #[test]
+    fn should_count_the_cells_to_the_water() {
+        let mut height_map = init_height_map::<5, 3>(LAND_VALUE);
+        height_map[0][1] = LAKE_VALUE;
+
+        let distances = create_water_distance_map(&height_map, 3);
+
+        assert_eq!(distances[0][1], 0);
+        assert_eq!(distances[1][0], 1);
+        assert_eq!(distances[3][2], 3);
+        assert_eq!(distances[4][1], usize::MAX);
+    }
+}
+
+mod get_slope {
+    use super::*;
+
+    #[test]
+    fn should_get_the_biggest_height_difference() {
+        let mut height_map = init_height_map::<3, 3>(LAND_VALUE);
+        height_map[2][2] = 3_f32;
+        height_map[0][1] = 0.5_f32;
+
+        assert_eq!(get_slope(&height_map, 1, 1), 2_f32);
+        assert_eq!(get_slope(&height_map, 0, 0), 0.5_f32);
+        assert_eq!(get_slope(&init_height_map::<3, 3>(LAND_VALUE), 1, 1), 0_f32);
+    }
+}
@@ -1 +1,4 @@
+pub mod clock;
 pub mod height_map;
+pub mod poisson_disk;
+pub mod spatial_grid;
@@ -0,0 +1,88 @@
+use rand::Rng;
+
+#[cfg(test)]
+mod test;
+
+/// Candidates tried around each point before it stops spawning new points.
+const MAX_ATTEMPTS: usize = 30;
+
+/// Poisson-disk sampling (Bridson's algorithm): random points inside a rectangle where no two
+/// points are closer than a radius.
+///
+/// The points are returned in the order they were created (each one near an earlier point).
+///
+/// # Arguments
+/// * `width` - Width of the rectangle.
+/// * `height` - Height of the rectangle.
+/// * `radius` - Min distance between two points.
+/// * `rand` - Random generator used to create the points.
+pub fn poisson_disk(width: f32, height: f32, radius: f32, rand: &mut impl Rng) -> Vec<[f32; 2]> {
+    if width <= 0_f32 || height <= 0_f32 || radius <= 0_f32 {
+        return vec![];
+    }
+
+    // Each cell of the grid has at most one point.
+    let cell_size = radius / std::f32::consts::SQRT_2;
+    let columns = (width / cell_size).ceil() as usize;
+    let rows = (height / cell_size).ceil() as usize;
+    let mut grid: Vec<Option<usize>> = vec![None; columns * rows];
+    let get_cell = |point: [f32; 2]| {
+        let column = ((point[0] / cell_size) as usize).min(columns - 1);
+        let row = ((point[1] / cell_size) as usize).min(rows - 1);
+        (column, row)
+    };
+
+    let first = [rand.gen_range(0_f32..width), rand.gen_range(0_f32..height)];
+    let (column, row) = get_cell(first);
+    grid[column * rows + row] = Some(0);
+
+    let mut points = vec![first];
+    let mut active = vec![0];
+
+    while !active.is_empty() {
+        let active_index = rand.gen_range(0..active.len());
+        let center = points[active[active_index]];
+        let mut found = false;
+
+        for _ in 0..MAX_ATTEMPTS {
+            let angle = rand.gen_range(0_f32..std::f32::consts::TAU);
+            let distance = rand.gen_range(radius..2_f32 * radius);
+            let candidate = [
+                center[0] + distance * angle.cos(),
+                center[1] + distance * angle.sin(),
+            ];
+
+            let inside = candidate[0] >= 0_f32
+                && candidate[0] < width
+                && candidate[1] >= 0_f32
+                && candidate[1] < height;
+            if !inside {
+                continue;
+            }
+
+            let (column, row) = get_cell(candidate);
+            let is_far = (column.saturating_sub(2)..(column + 3).min(columns))
+                .flat_map(|c| (row.saturating_sub(2)..(row + 3).min(rows)).map(move |r| (c, r)))
+                .filter_map(|(c, r)| grid[c * rows + r])
+                .all(|index| {
+                    let delta_x = points[index][0] - candidate[0];
+                    let delta_y = points[index][1] - candidate[1];
+                    delta_x * delta_x + delta_y * delta_y >= radius * radius
+                });
+
+            if is_far {
+                grid[column * rows + row] = Some(points.len());
+                active.push(points.len());
+                points.push(candidate);
+                found = true;
+                break;
+            }
+        }
+
+        if !found {
+            active.swap_remove(active_index);
+        }
+    }
+
+    points
+}
@@ -0,0 +1,38 @@
+use super::*;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+mod poisson_disk {
+    use super::*;
+
+    #[test]
+    fn should_keep_the_points_apart() {
+        let points = poisson_disk(50_f32, 30_f32, 3_f32, &mut ChaCha8Rng::seed_from_u64(1));
+
+        for (i, a) in points.iter().enumerate() {
+            assert!(a[0] >= 0_f32 && a[0] < 50_f32 && a[1] >= 0_f32 && a[1] < 30_f32);
+
+            for b in points.iter().skip(i + 1) {
+                let distance = ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2)).sqrt();
+                assert!(distance >= 3_f32, "{a:?} {b:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn should_cover_the_rectangle() {
+        let points = poisson_disk(40_f32, 40_f32, 4_f32, &mut ChaCha8Rng::seed_from_u64(2));
+
+        // A packed rectangle has around one point for each (2 * radius)² area.
+        assert!(points.len() >= (40 * 40) / 64);
+    }
+
+    #[test]
+    fn should_repeat_with_the_same_seed() {
+        let first = poisson_disk(20_f32, 20_f32, 2_f32, &mut ChaCha8Rng::seed_from_u64(3));
+        let second = poisson_disk(20_f32, 20_f32, 2_f32, &mut ChaCha8Rng::seed_from_u64(3));
+
+        assert_eq!(first, second);
+        assert!(poisson_disk(20_f32, 20_f32, 0_f32, &mut ChaCha8Rng::seed_from_u64(3)).is_empty());
+    }
+}
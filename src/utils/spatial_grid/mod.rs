@@ -0,0 +1,129 @@
+#[cfg(test)]
+mod test;
+
+/// Buckets with the indexes of the entities on each region of the map.
+///
+/// It is used to find the entities near a point without checking all of them.
+pub struct SpatialGrid {
+    cell_size: f32,
+    columns: usize,
+    rows: usize,
+    /// Indexes and positions (x and z) of the entities on each bucket.
+    cells: Vec<Vec<(usize, [f32; 2])>>,
+}
+
+impl SpatialGrid {
+    /// Create an empty grid.
+    ///
+    /// # Arguments
+    /// * `width` - Width of the map.
+    /// * `height` - Height of the map.
+    /// * `cell_size` - Size of each bucket (in map cells).
+    pub fn new(width: usize, height: usize, cell_size: f32) -> Self {
+        let columns = (width as f32 / cell_size).ceil().max(1_f32) as usize;
+        let rows = (height as f32 / cell_size).ceil().max(1_f32) as usize;
+
+        Self {
+            cell_size,
+            columns,
+            rows,
+            cells: vec![vec![]; columns * rows],
+        }
+    }
+
+    /// Create a grid with the indexes of the positions.
+    ///
+    /// # Arguments
+    /// * `width` - Width of the map.
+    /// * `height` - Height of the map.
+    /// * `cell_size` - Size of each bucket (in map cells).
+    /// * `positions` - Positions of the entities.
+    pub fn from_positions(
+        width: usize,
+        height: usize,
+        cell_size: f32,
+        positions: impl Iterator<Item = [f32; 3]>,
+    ) -> Self {
+        let mut grid = Self::new(width, height, cell_size);
+
+        for (index, position) in positions.enumerate() {
+            grid.insert(index, position);
+        }
+
+        grid
+    }
+
+    /// Add the index of an entity (the positions outside the map go to the border buckets).
+    ///
+    /// # Arguments
+    /// * `index` - Index of the entity.
+    /// * `position` - Position of the entity.
+    pub fn insert(&mut self, index: usize, position: [f32; 3]) {
+        let (column, row) = self.get_bucket(position[0], position[2]);
+        self.cells[column * self.rows + row].push((index, [position[0], position[2]]));
+    }
+
+    /// Get the indexes (in increasing order) of the entities on the buckets that touch a circle.
+    ///
+    /// The result can have entities outside the circle, so the distance must still be checked.
+    ///
+    /// # Arguments
+    /// * `position` - Center of the circle.
+    /// * `radius` - Radius of the circle.
+    pub fn get_near(&self, position: [f32; 3], radius: f32) -> Vec<usize> {
+        let mut indexes: Vec<usize> = self
+            .iter_buckets(position, radius)
+            .map(|(index, _)| index)
+            .collect();
+
+        indexes.sort_unstable();
+        indexes
+    }
+
+    /// Iterate over the indexes of the entities inside a circle (ignoring the height), bucket
+    /// by bucket (without allocating or sorting them).
+    ///
+    /// # Arguments
+    /// * `position` - Center of the circle.
+    /// * `radius` - Radius of the circle.
+    pub fn iter_in_radius(
+        &self,
+        position: [f32; 3],
+        radius: f32,
+    ) -> impl Iterator<Item = usize> + '_ {
+        self.iter_buckets(position, radius)
+            .filter(move |(_, [x, z])| {
+                let (delta_x, delta_z) = (x - position[0], z - position[2]);
+                (delta_x * delta_x + delta_z * delta_z).sqrt() <= radius
+            })
+            .map(|(index, _)| index)
+    }
+
+    /// Iterate over the entities on the buckets that touch a circle.
+    ///
+    /// # Arguments
+    /// * `position` - Center of the circle.
+    /// * `radius` - Radius of the circle.
+    fn iter_buckets(
+        &self,
+        position: [f32; 3],
+        radius: f32,
+    ) -> impl Iterator<Item = (usize, [f32; 2])> + '_ {
+        let (first_column, first_row) = self.get_bucket(position[0] - radius, position[2] - radius);
+        let (last_column, last_row) = self.get_bucket(position[0] + radius, position[2] + radius);
+
+        (first_column..=last_column).flat_map(move |column| {
+            self.cells[column * self.rows + first_row..=column * self.rows + last_row]
+                .iter()
+                .flatten()
+                .copied()
+        })
+    }
+
+    fn get_bucket(&self, x: f32, z: f32) -> (usize, usize) {
+        let column = (x / self.cell_size).floor().max(0_f32) as usize;
+        let row = (z / self.cell_size).floor().max(0_f32) as usize;
+
+        (column.min(self.columns - 1), row.min(self.rows - 1))
+    }
+}
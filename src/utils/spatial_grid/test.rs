@@ -0,0 +1,58 @@
+use super::*;
+
+mod get_near {
+    use super::*;
+
+    #[test]
+    fn should_find_the_entities_near_a_point() {
+        let positions = [
+            [1_f32, 0_f32, 1_f32],
+            [30_f32, 0_f32, 30_f32],
+            [3_f32, 0_f32, 2_f32],
+            [9_f32, 0_f32, 1_f32],
+        ];
+        let grid = SpatialGrid::from_positions(40, 40, 4_f32, positions.into_iter());
+
+        let near = grid.get_near([2_f32, 0_f32, 2_f32], 2_f32);
+
+        assert_eq!(near, vec![0, 2]);
+    }
+
+    #[test]
+    fn should_return_the_indexes_in_order() {
+        let mut grid = SpatialGrid::new(10, 10, 2_f32);
+        grid.insert(5, [5_f32, 0_f32, 5_f32]);
+        grid.insert(1, [3_f32, 0_f32, 3_f32]);
+        grid.insert(3, [5_f32, 0_f32, 4_f32]);
+
+        assert_eq!(grid.get_near([4_f32, 0_f32, 4_f32], 2_f32), vec![1, 3, 5]);
+    }
+
+    #[test]
+    fn should_keep_the_positions_outside_the_map() {
+        let mut grid = SpatialGrid::new(10, 10, 2_f32);
+        grid.insert(0, [-3_f32, 0_f32, 12_f32]);
+
+        assert_eq!(grid.get_near([0_f32, 0_f32, 9_f32], 1_f32), vec![0]);
+        assert!(grid.get_near([9_f32, 0_f32, 0_f32], 1_f32).is_empty());
+    }
+}
+
+mod iter_in_radius {
+    use super::*;
+
+    #[test]
+    fn should_skip_the_entities_outside_the_circle() {
+        let positions = [
+            [1_f32, 0_f32, 1_f32],
+            [2_f32, 5_f32, 2_f32],
+            [3_f32, 0_f32, 3_f32],
+        ];
+        let grid = SpatialGrid::from_positions(40, 40, 4_f32, positions.into_iter());
+
+        let mut near: Vec<usize> = grid.iter_in_radius([1_f32, 0_f32, 1_f32], 2_f32).collect();
+        near.sort_unstable();
+
+        assert_eq!(near, vec![0, 1]);
+    }
+}